@@ -0,0 +1,71 @@
+//! Benchmarks for the performance-critical parts of the game
+//! structures, measured on positions from `fixtures/positions`.
+//!
+//! Run with `cargo bench`, optionally passing a filter that
+//! the benchmark names have to contain (e.g. `cargo bench -- midgame`).
+
+use std::{env, hint::black_box, time::{Duration, Instant}};
+use socha_client_2021::game::{Board, GameState, Vec2};
+use socha_client_2021::util::{FromXmlNode, XmlNode};
+
+const FIXTURES: [(&str, &str); 3] = [
+    ("early", include_str!("../fixtures/positions/early.xml")),
+    ("midgame", include_str!("../fixtures/positions/midgame.xml")),
+    ("late", include_str!("../fixtures/positions/late.xml"))
+];
+
+/// The time spent measuring each benchmark.
+const MEASUREMENT_TIME: Duration = Duration::from_secs(1);
+
+/// Runs the given function repeatedly until the measurement
+/// time is exhausted and prints the mean time per iteration.
+fn bench<T>(filter: Option<&str>, name: &str, mut f: impl FnMut() -> T) {
+    if filter.is_some_and(|filter| !name.contains(filter)) {
+        return;
+    }
+
+    // Warm up
+    black_box(f());
+
+    let start = Instant::now();
+    let mut iterations = 0u64;
+    while start.elapsed() < MEASUREMENT_TIME {
+        for _ in 0..16 {
+            black_box(f());
+        }
+        iterations += 16;
+    }
+    let mean = start.elapsed() / iterations as u32;
+    println!("{:<32} {:>12.3} µs/iter ({} iterations)", name, mean.as_secs_f64() * 1e6, iterations);
+}
+
+fn main() {
+    // Cargo passes '--bench' to harness-less benchmarks
+    let filter = env::args().skip(1).find(|arg| !arg.starts_with("--"));
+    let filter = filter.as_deref();
+
+    for (name, xml) in FIXTURES {
+        let node: XmlNode = xml.parse().expect("Could not parse fixture");
+        let state = GameState::from_node(&node).expect("Invalid fixture");
+        let game_move = state.possible_moves().next().expect("Fixture has no moves");
+
+        bench(filter, &format!("{}/parse_xml", name), || xml.parse::<XmlNode>().unwrap());
+        bench(filter, &format!("{}/state_from_node", name), || GameState::from_node(&node).unwrap());
+        bench(filter, &format!("{}/possible_moves", name), || state.possible_moves().count());
+        bench(filter, &format!("{}/mobility", name), || state.mobility(state.current_color()));
+        bench(filter, &format!("{}/blocked_colors", name), || state.blocked_colors());
+        let moves: Vec<_> = state.possible_moves().collect();
+        bench(filter, &format!("{}/validate_move", name), || moves.iter().filter(|m| state.validate_move(m).is_ok()).count());
+        bench(filter, &format!("{}/validate_moves", name), || state.validate_moves(moves.iter()).len());
+        bench(filter, &format!("{}/after_move", name), || state.after_move(game_move.clone()).unwrap());
+        bench(filter, &format!("{}/board_get", name), || {
+            Board::positions().filter(|&p| state.board.get(p) == state.current_color()).count()
+        });
+        bench(filter, &format!("{}/corners_on_color", name), || {
+            Board::positions().filter(|&p| state.board.corners_on_color(p, state.current_color())).count()
+        });
+        bench(filter, &format!("{}/is_obstructed", name), || {
+            (0..20).filter(|&i| state.board.is_obstructed(Vec2::new(i, 19 - i))).count()
+        });
+    }
+}
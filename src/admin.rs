@@ -0,0 +1,144 @@
+//! A client for the server's administrative interface, e.g.
+//! for writing tournament managers or game observers.
+
+use std::{collections::VecDeque, io::{BufReader, BufWriter, Read, Write}, net::TcpStream};
+use log::{debug, info};
+use crate::client::GAME_TYPE;
+use crate::protocol::{AdminRequest, MessageStream, Observed, Prepared, SlotDescriptor};
+use crate::util::{SCError, SCResult, FromXmlNode, XmlNode};
+
+/// The message the server responds with to invalid requests.
+const ERROR_PACKET: &str = "errorpacket";
+
+/// A connection to the server authenticated as an administrator.
+///
+/// Messages that arrive while waiting for the response
+/// to a request (e.g. room messages of observed games)
+/// are kept and returned by `next_message` later.
+pub struct AdminClient<R, W> where R: Read, W: Write {
+    messages: MessageStream<R>,
+    writer: W,
+    pending: VecDeque<XmlNode>
+}
+
+impl AdminClient<BufReader<TcpStream>, BufWriter<TcpStream>> {
+    /// Connects to the server via TCP and authenticates.
+    pub fn connect(host: &str, port: u16, password: &str) -> SCResult<Self> {
+        let stream = TcpStream::connect((host, port))?;
+        info!("Connected to {}:{} as administrator", host, port);
+        Self::new(BufReader::new(stream.try_clone()?), BufWriter::new(stream), password)
+    }
+}
+
+impl<R, W> AdminClient<R, W> where R: Read, W: Write {
+    /// Opens the protocol stream on the given connection and authenticates.
+    pub fn new(reader: R, writer: W, password: &str) -> SCResult<Self> {
+        let mut client = Self { messages: MessageStream::new(reader), writer, pending: VecDeque::new() };
+        client.write("<protocol>")?;
+        client.send(AdminRequest::Authenticate { password: password.to_owned() })?;
+        Ok(client)
+    }
+
+    /// Writes raw XML to the server.
+    fn write(&mut self, xml: &str) -> SCResult<()> {
+        self.writer.write_all(xml.as_bytes())?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Sends a request without waiting for a response.
+    pub fn send(&mut self, request: AdminRequest) -> SCResult<()> {
+        let xml = XmlNode::from(request).serialize()?;
+        debug!("Sending admin request {}", xml);
+        self.write(&xml)
+    }
+
+    /// Reads messages until one with the given name arrives,
+    /// keeping the others for `next_message`.
+    fn await_response(&mut self, name: &str) -> SCResult<XmlNode> {
+        for message in self.messages.by_ref() {
+            let node = message?.node;
+            if node.name() == name {
+                return Ok(node);
+            } else if node.name() == ERROR_PACKET {
+                return Err(SCError::protocol(format!("Server rejected the request: {}", node.opt_attribute("message").unwrap_or("no message"))));
+            }
+            self.pending.push_back(node);
+        }
+        Err(SCError::protocol(format!("Connection closed while waiting for <{}>", name)))
+    }
+
+    /// Creates a room for the given slots, optionally paused.
+    pub fn prepare(&mut self, slots: Vec<SlotDescriptor>, pause: bool) -> SCResult<Prepared> {
+        self.send(AdminRequest::Prepare { game_type: GAME_TYPE.to_owned(), pause, slots })?;
+        Prepared::from_node(&self.await_response("prepared")?)
+    }
+
+    /// Subscribes to the messages of the given room.
+    pub fn observe(&mut self, room_id: &str) -> SCResult<Observed> {
+        self.send(AdminRequest::Observe { room_id: room_id.to_owned() })?;
+        Observed::from_node(&self.await_response("observed")?)
+    }
+
+    /// Pauses or resumes the given game.
+    pub fn pause(&mut self, room_id: &str, pause: bool) -> SCResult<()> {
+        self.send(AdminRequest::Pause { room_id: room_id.to_owned(), pause })
+    }
+
+    /// Performs a single move in the given paused game.
+    pub fn step(&mut self, room_id: &str) -> SCResult<()> {
+        self.send(AdminRequest::Step { room_id: room_id.to_owned() })
+    }
+
+    /// Aborts the given game.
+    pub fn cancel(&mut self, room_id: &str) -> SCResult<()> {
+        self.send(AdminRequest::Cancel { room_id: room_id.to_owned() })
+    }
+
+    /// Fetches the next message from the server, e.g. a
+    /// room message of an observed game, blocking if needed.
+    /// Returns `None` once the connection has ended.
+    pub fn next_message(&mut self) -> Option<SCResult<XmlNode>> {
+        self.pending.pop_front().map(Ok).or_else(|| self.messages.next().map(|m| m.map(|m| m.node)))
+    }
+
+    /// Closes the protocol stream.
+    pub fn close(mut self) -> SCResult<()> {
+        self.write("</protocol>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::protocol::{Room, SlotDescriptor};
+    use crate::util::FromXmlNode;
+    use super::AdminClient;
+
+    #[test]
+    fn test_admin_client() {
+        let responses = concat!(
+            "<protocol>",
+            "<room roomId=\"old\"><data class=\"sc.framework.plugins.protocol.MoveRequest\"/></room>",
+            "<prepared roomId=\"abc\"><reservation>r1</reservation><reservation>r2</reservation></prepared>",
+            "<errorpacket message=\"Room does not exist\"/>"
+        );
+        let mut sent = Vec::new();
+        let mut client = AdminClient::new(responses.as_bytes(), &mut sent, "secret").unwrap();
+
+        let prepared = client.prepare(vec![SlotDescriptor::new("One"), SlotDescriptor::new("Two")], true).unwrap();
+        assert_eq!(prepared.room_id, "abc");
+        assert_eq!(prepared.reservations, vec!["r1", "r2"]);
+        assert!(client.observe("xyz").is_err());
+        client.step("abc").unwrap();
+
+        // The room message received in between is kept
+        let room = Room::from_node(&client.next_message().unwrap().unwrap()).unwrap();
+        assert_eq!(room.room_id, "old");
+        assert!(client.next_message().is_none());
+        client.close().unwrap();
+
+        let sent = String::from_utf8(sent).unwrap();
+        assert!(sent.starts_with("<protocol><authenticate password=\"secret\" /><prepare "));
+        assert!(sent.ends_with("<observe roomId=\"xyz\" /><step roomId=\"abc\" /></protocol>"));
+    }
+}
@@ -0,0 +1,52 @@
+//! Regenerates a legal moves snapshot (see `LegalMoves`) for a position.
+//!
+//! By default, the moves are generated by this crate's rules engine,
+//! which is how the regression snapshots in `fixtures/legal_moves` are
+//! made. With `--backend`, the moves are taken from an XML export of the
+//! official backend instead, i.e. a root element containing the moves as
+//! `<data class="sc.plugin2021.SetMove">` nodes (as in the protocol).
+
+use std::{env, fs};
+use getopts::Options;
+use socha_client_2021::game::{GameState, Move};
+use socha_client_2021::sim::LegalMoves;
+use socha_client_2021::util::{FromXmlNode, SCResult, XmlNode};
+
+fn print_usage(program: &str, options: Options) {
+    let brief = format!("Usage: {} [options] POSITION", program);
+    print!("{}", options.usage(&brief));
+}
+
+fn load_backend_moves(path: &str) -> SCResult<Vec<Move>> {
+    let node: XmlNode = fs::read_to_string(path)?.parse()?;
+    node.childs_by_name("data").map(Move::from_node).collect()
+}
+
+fn main() {
+    let args = env::args().collect::<Vec<_>>();
+    let mut options = Options::new();
+    options.optopt("b", "backend", "Reads the legal moves from an XML export of the backend", "FILE");
+    options.optopt("o", "output", "Writes the fixture to the given file instead of stdout", "FILE");
+    options.optflag("H", "help", "Prints usage info");
+
+    let parsed_args = options.parse(&args[1..]).expect("Could not parse arguments!");
+    let position = match parsed_args.free.as_slice() {
+        [position] if !parsed_args.opt_present("help") => position,
+        _ => {
+            print_usage(&args[0], options);
+            return;
+        }
+    };
+
+    let state = GameState::load(position).expect("Could not load position.");
+    let (source, moves) = match parsed_args.opt_str("backend") {
+        Some(path) => ("the backend", LegalMoves::new(load_backend_moves(&path).expect("Could not load backend moves."))),
+        None => ("this crate's rules engine (a regression snapshot, not a backend export)", LegalMoves::generate(&state))
+    };
+    let fixture = format!("# Legal moves in {}, as generated by {}.\n{}", position, source, moves);
+
+    match parsed_args.opt_str("output") {
+        Some(path) => fs::write(path, fixture).expect("Could not write fixture."),
+        None => print!("{}", fixture)
+    }
+}
@@ -0,0 +1,88 @@
+use std::time::Duration;
+use log::info;
+use crate::BotInfo;
+use crate::client::SCClientDelegate;
+use crate::game::{GameState, Move, Team};
+use crate::protocol::GameResult;
+use crate::search::SearchResult;
+use crate::util::XmlNode;
+use super::OpeningBook;
+
+/// A delegate that plays moves from an opening book
+/// and falls back to the wrapped delegate otherwise.
+pub struct BookLogic<D> where D: SCClientDelegate {
+    book: OpeningBook,
+    inner: D
+}
+
+impl<D> BookLogic<D> where D: SCClientDelegate {
+    /// Wraps the given delegate.
+    pub fn new(book: OpeningBook, inner: D) -> Self {
+        Self { book, inner }
+    }
+
+    /// Fetches the wrapped delegate.
+    pub fn inner(&self) -> &D {
+        &self.inner
+    }
+}
+
+impl<D> SCClientDelegate for BookLogic<D> where D: SCClientDelegate {
+    fn bot_info(&self) -> BotInfo {
+        let info = self.inner.bot_info();
+        let settings = if info.settings_summary.is_empty() {
+            format!("book with {} positions", self.book.len())
+        } else {
+            format!("{}, book with {} positions", info.settings_summary, self.book.len())
+        };
+        info.settings_summary(settings)
+    }
+
+    fn on_update_state(&mut self, state: &GameState) {
+        self.inner.on_update_state(state);
+    }
+
+    fn on_game_end(&mut self, result: GameResult) {
+        self.inner.on_game_end(result);
+    }
+
+    fn on_welcome_message(&mut self, color: &Team) {
+        self.inner.on_welcome_message(color);
+    }
+
+    fn on_disconnect(&mut self) {
+        self.inner.on_disconnect();
+    }
+
+    fn on_rejoin(&mut self, success: bool) {
+        self.inner.on_rejoin(success);
+    }
+
+    fn on_search_finished(&mut self, result: &SearchResult) {
+        self.inner.on_search_finished(result);
+    }
+
+    fn on_unknown_data(&mut self, class: &str, node: &XmlNode) {
+        self.inner.on_unknown_data(class, node);
+    }
+
+    fn request_move(&mut self, state: &GameState, my_team: Team) -> Move {
+        match self.book.lookup(state) {
+            Some(game_move) => {
+                info!("Playing {:?} from the opening book", game_move);
+                game_move
+            },
+            None => self.inner.request_move(state, my_team)
+        }
+    }
+
+    fn request_search(&mut self, state: &GameState, my_team: Team) -> SearchResult {
+        match self.book.lookup(state) {
+            Some(game_move) => {
+                info!("Playing {:?} from the opening book", game_move);
+                SearchResult::unsearched(game_move, Duration::ZERO)
+            },
+            None => self.inner.request_search(state, my_team)
+        }
+    }
+}
@@ -0,0 +1,7 @@
+//! Opening books mapping early positions to preferred moves.
+
+mod book_logic;
+mod opening_book;
+
+pub use book_logic::*;
+pub use opening_book::*;
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+#[cfg(feature = "json")]
+use std::{fs, path::Path};
+use crate::game::{GameState, Move};
+#[cfg(feature = "json")]
+use crate::util::{SCError, SCResult, FromJson, ToJson, JsonValue};
+
+/// Maps positions (by their `canonical_hash`) to preferred moves,
+/// ordered by preference. Moves are stored relative to the canonical
+/// form, thus an entry also covers every symmetric position.
+///
+/// Books are stored as JSON:
+///
+/// ```text
+/// { "positions": [{ "key": "<hex key>", "moves": [<move>, ...] }, ...] }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OpeningBook {
+    entries: HashMap<u64, Vec<Move>>
+}
+
+impl OpeningBook {
+    /// Creates an empty book.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a book from the given JSON file.
+    #[cfg(feature = "json")]
+    pub fn load(path: impl AsRef<Path>) -> SCResult<Self> {
+        Self::from_json(&fs::read_to_string(path)?.parse()?)
+    }
+
+    /// Saves the book to the given JSON file.
+    #[cfg(feature = "json")]
+    pub fn save(&self, path: impl AsRef<Path>) -> SCResult<()> {
+        fs::write(path, self.to_json().pretty(2))?;
+        Ok(())
+    }
+
+    /// The number of positions in the book.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the book contains no positions.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Adds a move for the given position, with lower
+    /// preference than the moves added before.
+    pub fn insert(&mut self, state: &GameState, game_move: Move) {
+        let (canonical, symmetry) = state.canonical_form();
+        self.entries.entry(canonical.position_hash()).or_default().push(symmetry.apply_to_move(&game_move));
+    }
+
+    /// Fetches the preferred move in the given position that is
+    /// actually legal (which guards against hash collisions).
+    pub fn lookup(&self, state: &GameState) -> Option<Move> {
+        let (canonical, symmetry) = state.canonical_form();
+        self.entries.get(&canonical.position_hash())?
+            .iter()
+            .map(|m| symmetry.inverse().apply_to_move(m))
+            .find(|m| state.validate_move(m).is_ok())
+    }
+}
+
+#[cfg(feature = "json")]
+impl ToJson for OpeningBook {
+    fn to_json(&self) -> JsonValue {
+        let mut keys: Vec<_> = self.entries.keys().collect();
+        keys.sort();
+        JsonValue::object([("positions", JsonValue::array(keys.into_iter().map(|key| JsonValue::object([
+            ("key", format!("{:016x}", key).into()),
+            ("moves", self.entries[key].to_json())
+        ]))))])
+    }
+}
+
+#[cfg(feature = "json")]
+impl FromJson for OpeningBook {
+    fn from_json(json: &JsonValue) -> SCResult<Self> {
+        let mut entries = HashMap::new();
+        for position in json.get("positions")?.as_array()? {
+            let raw_key = position.get("key")?.as_str()?;
+            let key = u64::from_str_radix(raw_key, 16).map_err(|e| SCError::parse(format!("Invalid book key {}: {}", raw_key, e)))?;
+            entries.insert(key, Vec::from_json(position.get("moves")?)?);
+        }
+        Ok(Self { entries })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{GameState, Move, PIECE_SHAPES_BY_NAME, Symmetry};
+    #[cfg(feature = "json")]
+    use crate::util::{FromJson, ToJson};
+    use super::OpeningBook;
+
+    #[test]
+    fn test_opening_book() {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_Y"].clone());
+        let moves: Vec<_> = state.possible_moves().collect();
+        let mut book = OpeningBook::new();
+        book.insert(&state, Move::Skip { color: state.current_color() });
+        book.insert(&state, moves[3].clone());
+
+        // Skipping is illegal in the first round, so the next move is preferred
+        assert_eq!(book.lookup(&state), Some(moves[3].clone()));
+        #[cfg(feature = "json")]
+        assert_eq!(OpeningBook::from_json(&book.to_json()).unwrap(), book);
+
+        // Symmetric positions share their entries
+        state.perform_move(moves[3].clone()).unwrap();
+        assert_eq!(book.lookup(&state), None);
+        let reply = state.possible_moves().next().unwrap();
+        book.insert(&state, reply.clone());
+        let mirrored = state.transformed(Symmetry::Transpose);
+        assert_eq!(book.lookup(&mirrored), Some(Symmetry::Transpose.apply_to_move(&reply)));
+    }
+}
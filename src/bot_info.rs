@@ -0,0 +1,80 @@
+use std::fmt;
+#[cfg(feature = "json")]
+use crate::util::{SCResult, FromJson, ToJson, JsonValue};
+
+/// Metadata identifying a bot, e.g. in logs or game records.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BotInfo {
+    /// The bot's name.
+    pub name: String,
+    /// The bot's version.
+    pub version: String,
+    /// The bot's author.
+    pub author: String,
+    /// A short, human-readable description of the
+    /// bot's configuration (e.g. search depth, weights).
+    pub settings_summary: String
+}
+
+impl BotInfo {
+    /// Creates new bot metadata with the given name and version.
+    pub fn new(name: impl Into<String>, version: impl Into<String>) -> Self {
+        Self { name: name.into(), version: version.into(), author: String::new(), settings_summary: String::new() }
+    }
+
+    /// Sets the bot's author.
+    pub fn author(mut self, author: impl Into<String>) -> Self {
+        self.author = author.into();
+        self
+    }
+
+    /// Sets the summary of the bot's configuration.
+    pub fn settings_summary(mut self, settings_summary: impl Into<String>) -> Self {
+        self.settings_summary = settings_summary.into();
+        self
+    }
+}
+
+impl Default for BotInfo {
+    fn default() -> Self {
+        Self::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+    }
+}
+
+/// Formats the identification string, e.g. `name 1.0 by author (depth 2)`.
+impl fmt::Display for BotInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.version)?;
+        if !self.author.is_empty() {
+            write!(f, " by {}", self.author)?;
+        }
+        if !self.settings_summary.is_empty() {
+            write!(f, " ({})", self.settings_summary)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "json")]
+impl ToJson for BotInfo {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object([
+            ("name", self.name.as_str().into()),
+            ("version", self.version.as_str().into()),
+            ("author", self.author.as_str().into()),
+            ("settings_summary", self.settings_summary.as_str().into())
+        ])
+    }
+}
+
+#[cfg(feature = "json")]
+impl FromJson for BotInfo {
+    fn from_json(json: &JsonValue) -> SCResult<Self> {
+        Ok(Self {
+            name: json.get("name")?.as_str()?.to_owned(),
+            version: json.get("version")?.as_str()?.to_owned(),
+            author: json.get("author")?.as_str()?.to_owned(),
+            settings_summary: json.get("settings_summary")?.as_str()?.to_owned()
+        })
+    }
+}
@@ -0,0 +1,225 @@
+//! A local HTTP endpoint serving the current game state as JSON,
+//! for browser-based visualizations and dashboards.
+//!
+//! - `GET /state` responds with the latest state (or `null`).
+//! - `GET /metrics` responds with the `telemetry` metrics
+//!   in the Prometheus text format.
+//! - `GET /events` streams every update as server-sent events,
+//!   which browsers can consume using `EventSource`.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use log::{debug, warn};
+use crate::BotInfo;
+use crate::client::SCClientDelegate;
+use crate::game::{GameState, Move, Team};
+use crate::protocol::GameResult;
+use crate::search::SearchResult;
+use crate::telemetry::Metrics;
+use crate::util::{SCResult, ToJson, XmlNode};
+
+/// The latest state and the connected event stream subscribers.
+#[derive(Default)]
+struct Shared {
+    latest: Option<String>,
+    subscribers: Vec<TcpStream>
+}
+
+/// Serves the published game states over HTTP.
+pub struct StateBroadcaster {
+    shared: Arc<Mutex<Shared>>,
+    local_addr: SocketAddr
+}
+
+impl StateBroadcaster {
+    /// Starts serving on the given address in the background.
+    pub fn bind(addr: impl ToSocketAddrs) -> SCResult<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let local_addr = listener.local_addr()?;
+        let shared = Arc::new(Mutex::new(Shared::default()));
+
+        let accept_shared = shared.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let shared = accept_shared.clone();
+                        thread::spawn(move || {
+                            if let Err(e) = handle(stream, &shared) {
+                                debug!("Could not handle broadcast request: {}", e);
+                            }
+                        });
+                    },
+                    Err(e) => warn!("Could not accept broadcast connection: {}", e)
+                }
+            }
+        });
+
+        Ok(Self { shared, local_addr })
+    }
+
+    /// The address the broadcaster is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Publishes the given state to every subscriber
+    /// and serves it on subsequent requests.
+    pub fn publish(&self, state: &GameState) {
+        let json = state.to_json().to_string();
+        let mut shared = self.shared.lock().unwrap();
+        shared.subscribers.retain_mut(|subscriber| write_event(subscriber, &json).is_ok());
+        shared.latest = Some(json);
+    }
+}
+
+/// Responds to a single request.
+fn handle(mut stream: TcpStream, shared: &Mutex<Shared>) -> SCResult<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Skip the headers
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or("");
+    match path {
+        "/state" => {
+            let body = shared.lock().unwrap().latest.clone().unwrap_or_else(|| "null".to_owned());
+            write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n{}", body.len(), body)?;
+        },
+        "/metrics" => {
+            let body = Metrics::global().render_prometheus();
+            write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body)?;
+        },
+        "/events" => {
+            write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nAccess-Control-Allow-Origin: *\r\n\r\n")?;
+            let mut shared = shared.lock().unwrap();
+            if let Some(latest) = &shared.latest {
+                write_event(&mut stream, latest)?;
+            }
+            shared.subscribers.push(stream);
+        },
+        _ => write!(stream, "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")?
+    }
+    Ok(())
+}
+
+fn write_event(stream: &mut TcpStream, json: &str) -> std::io::Result<()> {
+    write!(stream, "event: state\ndata: {}\n\n", json)?;
+    stream.flush()
+}
+
+/// A delegate that publishes every state update to a
+/// broadcaster and forwards every callback to the
+/// wrapped delegate.
+pub struct BroadcastLogic<D> where D: SCClientDelegate {
+    broadcaster: StateBroadcaster,
+    inner: D
+}
+
+impl<D> BroadcastLogic<D> where D: SCClientDelegate {
+    /// Wraps the given delegate.
+    pub fn new(broadcaster: StateBroadcaster, inner: D) -> Self {
+        Self { broadcaster, inner }
+    }
+
+    /// Fetches the wrapped delegate.
+    pub fn inner(&self) -> &D {
+        &self.inner
+    }
+}
+
+impl<D> SCClientDelegate for BroadcastLogic<D> where D: SCClientDelegate {
+    fn bot_info(&self) -> BotInfo {
+        self.inner.bot_info()
+    }
+
+    fn on_update_state(&mut self, state: &GameState) {
+        self.broadcaster.publish(state);
+        self.inner.on_update_state(state);
+    }
+
+    fn on_game_end(&mut self, result: GameResult) {
+        self.inner.on_game_end(result);
+    }
+
+    fn on_welcome_message(&mut self, color: &Team) {
+        self.inner.on_welcome_message(color);
+    }
+
+    fn on_disconnect(&mut self) {
+        self.inner.on_disconnect();
+    }
+
+    fn on_rejoin(&mut self, success: bool) {
+        self.inner.on_rejoin(success);
+    }
+
+    fn on_search_finished(&mut self, result: &SearchResult) {
+        self.inner.on_search_finished(result);
+    }
+
+    fn on_unknown_data(&mut self, class: &str, node: &XmlNode) {
+        self.inner.on_unknown_data(class, node);
+    }
+
+    fn request_move(&mut self, state: &GameState, my_team: Team) -> Move {
+        self.inner.request_move(state, my_team)
+    }
+
+    fn request_search(&mut self, state: &GameState, my_team: Team) -> SearchResult {
+        self.inner.request_search(state, my_team)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpStream;
+    use crate::game::{GameState, PIECE_SHAPES_BY_NAME};
+    use crate::util::{FromJson, JsonValue};
+    use super::StateBroadcaster;
+
+    fn get(broadcaster: &StateBroadcaster, path: &str) -> TcpStream {
+        let mut stream = TcpStream::connect(broadcaster.local_addr()).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+        stream
+    }
+
+    #[test]
+    fn test_broadcast() {
+        let broadcaster = StateBroadcaster::bind("127.0.0.1:0").unwrap();
+        let mut response = String::new();
+        get(&broadcaster, "/state").read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK") && response.ends_with("\r\n\r\nnull"));
+
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["MONO"].clone());
+        broadcaster.publish(&state);
+        let mut events = BufReader::new(get(&broadcaster, "/events"));
+        let mut next_data = || loop {
+            let mut line = String::new();
+            events.read_line(&mut line).unwrap();
+            if let Some(data) = line.strip_prefix("data: ") {
+                break GameState::from_json(&data.parse::<JsonValue>().unwrap()).unwrap();
+            }
+        };
+        assert_eq!(next_data(), state);
+
+        state.perform_move(state.possible_moves().next().unwrap()).unwrap();
+        broadcaster.publish(&state);
+        assert_eq!(next_data().board, state.board);
+
+        let mut response = String::new();
+        get(&broadcaster, "/metrics").read_to_string(&mut response).unwrap();
+        assert!(response.contains("# TYPE socha_move_time_seconds histogram"));
+
+        let mut response = String::new();
+        get(&broadcaster, "/unknown").read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 404"));
+    }
+}
@@ -1,19 +1,33 @@
-use std::convert::TryFrom;
 use std::net::TcpStream;
 use std::io::{self, BufWriter, BufReader, Read, Write};
-use log::{info, debug, warn, error};
-use xml::reader::{XmlEvent as XmlReadEvent, EventReader};
-use xml::writer::EmitterConfig;
-use crate::game::{GameState, Team, Move};
-use crate::util::{SCResult, XmlNode, FromXmlNode};
-use crate::protocol::{Joined, Left, Room, Data, GameResult};
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
+use log::{Level, info, debug, warn, error};
+use crate::BotInfo;
+use crate::game::{GameState, Team, Move, ValidationMode};
+use crate::util::{SCError, SCResult, Span, XmlNode, FromXmlNode};
+use crate::protocol::{Message, MessageStream, ReceivedMessage, Room, Data, GameResult};
+use crate::search::SearchResult;
+use crate::telemetry::Metrics;
 
-const GAME_TYPE: &str = "swc_2021_blokus";
+/// The game type identifier used when joining a game.
+pub const GAME_TYPE: &str = "swc_2021_blokus";
+
+/// The host the client connects to by default.
+pub const DEFAULT_HOST: &str = "localhost";
+
+/// The port the game server listens on by default.
+pub const DEFAULT_PORT: u16 = 13050;
 
 /// A handler that implements the game player's
 /// behavior, usually employing some custom move
 /// selection strategy.
 pub trait SCClientDelegate {
+    /// Provides metadata identifying the bot. The client
+    /// logs it at startup and game records include it.
+    fn bot_info(&self) -> BotInfo { BotInfo::default() }
+
     /// Invoked whenever the game state updates.
     fn on_update_state(&mut self, _state: &GameState) {}
     
@@ -24,50 +38,605 @@ pub trait SCClientDelegate {
     /// with the player's color.
     fn on_welcome_message(&mut self, _color: &Team) {}
     
+    /// Invoked when the connection to the server has been
+    /// lost unexpectedly, before trying to reconnect.
+    fn on_disconnect(&mut self) {}
+    
+    /// Invoked after trying to rejoin the game
+    /// following a lost connection.
+    fn on_rejoin(&mut self, _success: bool) {}
+    
+    /// Invoked with the result of the search behind every
+    /// move sent, e.g. to display or persist diagnostics.
+    fn on_search_finished(&mut self, _result: &SearchResult) {}
+    
+    /// Invoked when the server sends room data of a class
+    /// this client does not know, which is otherwise ignored.
+    fn on_unknown_data(&mut self, _class: &str, _node: &XmlNode) {}
+    
     /// Requests a move from the delegate. This method
     /// should implement the "main" game logic.
     fn request_move(&mut self, state: &GameState, my_team: Team) -> Move;
+
+    /// Requests a move along with the search that produced it.
+    /// Search-based delegates should override this, the default
+    /// wraps the move from `request_move`.
+    fn request_search(&mut self, state: &GameState, my_team: Team) -> SearchResult {
+        let start = Instant::now();
+        let game_move = self.request_move(state, my_team);
+        SearchResult::unsearched(game_move, start.elapsed())
+    }
+}
+
+/// Forwards to the boxed delegate, e.g. to compose
+/// wrapping delegates at runtime.
+impl<D> SCClientDelegate for Box<D> where D: SCClientDelegate + ?Sized {
+    fn bot_info(&self) -> BotInfo { (**self).bot_info() }
+
+    fn on_update_state(&mut self, state: &GameState) { (**self).on_update_state(state) }
+
+    fn on_game_end(&mut self, result: GameResult) { (**self).on_game_end(result) }
+
+    fn on_welcome_message(&mut self, color: &Team) { (**self).on_welcome_message(color) }
+
+    fn on_disconnect(&mut self) { (**self).on_disconnect() }
+
+    fn on_rejoin(&mut self, success: bool) { (**self).on_rejoin(success) }
+
+    fn on_search_finished(&mut self, result: &SearchResult) { (**self).on_search_finished(result) }
+
+    fn on_unknown_data(&mut self, class: &str, node: &XmlNode) { (**self).on_unknown_data(class, node) }
+
+    fn request_move(&mut self, state: &GameState, my_team: Team) -> Move { (**self).request_move(state, my_team) }
+
+    fn request_search(&mut self, state: &GameState, my_team: Team) -> SearchResult { (**self).request_search(state, my_team) }
+}
+
+/// A hook that observes the exact XML exchanged with the
+/// server, e.g. to diagnose protocol bugs or to archive
+/// session logs for replay.
+pub trait XmlInterceptor {
+    /// Invoked with every message received from the server.
+    fn on_raw_receive(&mut self, _xml: &str) {}
+
+    /// Invoked with everything sent to the server, before it is sent.
+    fn on_raw_send(&mut self, _xml: &str) {}
+}
+
+/// An interceptor that writes the entire traffic to the given
+/// writer (e.g. a file), each message preceded by a comment
+/// denoting its direction.
+pub struct XmlTranscript<W> where W: Write {
+    writer: W
+}
+
+impl<W> XmlTranscript<W> where W: Write {
+    /// Creates a new transcript writing to the given writer.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Writes a message to the transcript.
+    fn log(&mut self, direction: &str, xml: &str) {
+        if let Err(e) = writeln!(self.writer, "<!-- {} -->\n{}", direction, xml).and_then(|_| self.writer.flush()) {
+            warn!("Could not write XML transcript: {}", e);
+        }
+    }
+}
+
+impl<W> XmlInterceptor for XmlTranscript<W> where W: Write {
+    fn on_raw_receive(&mut self, xml: &str) {
+        self.log("received", xml);
+    }
+
+    fn on_raw_send(&mut self, xml: &str) {
+        self.log("sent", xml);
+    }
 }
 
 /// A configuration that determines whether
 /// the reader and/or the writer of a stream
 /// should be swapped by stdio to ease debugging.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
 pub struct DebugMode {
     pub debug_reader: bool,
     pub debug_writer: bool,
 }
 
+/// Determines how often and how fast the client tries to
+/// reconnect (and rejoin its game) after losing the connection.
+/// The delay between the attempts grows exponentially.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ReconnectPolicy {
+    /// The maximum number of consecutive attempts (0 disables reconnecting).
+    pub max_retries: usize,
+    /// The delay before the first attempt.
+    pub initial_delay: Duration,
+    /// The upper bound for the delay between two attempts.
+    pub max_delay: Duration,
+    /// The factor by which the delay grows after each attempt.
+    pub multiplier: f64
+}
+
+impl ReconnectPolicy {
+    /// A policy that never reconnects.
+    pub fn disabled() -> Self {
+        Self { max_retries: 0, ..Self::default() }
+    }
+
+    /// The delay before the given (zero-based) attempt.
+    pub fn delay(&self, attempt: usize) -> Duration {
+        let factor = self.multiplier.powi(attempt.min(i32::MAX as usize) as i32);
+        self.initial_delay.mul_f64(factor).min(self.max_delay)
+    }
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            multiplier: 2.0
+        }
+    }
+}
+
+/// The move sent in place of a move the delegate
+/// did not provide in time.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum FallbackMove {
+    /// The first legal move.
+    #[default]
+    FirstLegal,
+    /// A skip if allowed, otherwise the first legal move.
+    Skip
+}
+
+impl FallbackMove {
+    /// Picks the fallback move in the given state.
+    pub fn pick(self, state: &GameState) -> Move {
+        let skip = Move::Skip { color: state.current_color() };
+        if self == Self::Skip && state.can_skip() {
+            skip
+        } else {
+            state.possible_moves().next().unwrap_or(skip)
+        }
+    }
+}
+
+/// Determines how long the delegate may take to provide a move
+/// before the client sends a fallback move on its own. The
+/// delegate keeps computing on its worker thread in that case,
+/// but its result is discarded.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TimeoutPolicy {
+    /// The time after which the fallback move is sent (`None` disables the watchdog).
+    pub hard_limit: Option<Duration>,
+    /// The move to send instead.
+    pub fallback: FallbackMove
+}
+
+impl TimeoutPolicy {
+    /// A policy that always waits for the delegate.
+    pub fn disabled() -> Self {
+        Self { hard_limit: None, ..Self::default() }
+    }
+}
+
+impl Default for TimeoutPolicy {
+    fn default() -> Self {
+        Self {
+            // The server allows 2 seconds per move
+            hard_limit: Some(Duration::from_millis(1800)),
+            fallback: FallbackMove::default()
+        }
+    }
+}
+
+/// Requests a move from the delegate, passing on the search result.
+pub(crate) fn search_move<D>(delegate: &mut D, state: &GameState, team: Team) -> Move where D: SCClientDelegate + ?Sized {
+    let result = delegate.request_search(state, team);
+    debug!("Search finished: {}", result);
+    delegate.on_search_finished(&result);
+    result.best_move
+}
+
+/// A notification of the delegate that is delivered once
+/// the delegate is no longer busy computing a move.
+type Notification<D> = Box<dyn FnOnce(&mut D) + Send>;
+
+/// Requests a move from the delegate on a worker thread, waiting at
+/// most the given limit (see `SCClient::request_move_with_watchdog`).
+type Watchdog<D> = fn(&SCClient<D>, &Arc<GameState>, Team, Duration) -> Result<Move, String>;
+
+/// Delivers the pending notifications to the delegate, in order.
+fn deliver_pending<D>(delegate: &mut D, pending: &mut Vec<Notification<D>>) {
+    for notification in pending.drain(..) {
+        notification(delegate);
+    }
+}
+
+/// A handle to the latest game state received by an `SCClient`.
+/// Handles are cheap to clone and can be shared across threads,
+/// the states themselves are shared immutably without copying.
+#[derive(Debug, Clone, Default)]
+pub struct StateSnapshots {
+    latest: Arc<Mutex<Option<Arc<GameState>>>>
+}
+
+impl StateSnapshots {
+    /// Fetches the latest game state, if any has been received.
+    pub fn latest(&self) -> Option<Arc<GameState>> {
+        self.latest.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replaces the latest game state.
+    fn publish(&self, state: Arc<GameState>) {
+        *self.latest.lock().unwrap_or_else(|e| e.into_inner()) = Some(state);
+    }
+}
+
+/// Describes how a session with the server ended.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum SessionEnd {
+    /// The server regularly closed the connection.
+    Closed,
+    /// The connection was lost unexpectedly.
+    Dropped
+}
+
+/// Configures and creates an `SCClient`, which is the entry
+/// point for embedding the client in other programs:
+///
+/// ```no_run
+/// # use socha_client_2021::{client::SCClient, logic::RandomLogic};
+/// SCClient::builder(RandomLogic::new())
+///     .host("localhost")
+///     .port(13050)
+///     .reservation("my-reservation")
+///     .build()
+///     .run()
+///     .expect("Error while running client.");
+/// ```
+pub struct SCClientBuilder<D> where D: SCClientDelegate {
+    delegate: D,
+    host: String,
+    port: u16,
+    reservation: Option<String>,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    debug_mode: DebugMode,
+    reconnect_policy: ReconnectPolicy,
+    timeout_policy: TimeoutPolicy,
+    validation_mode: ValidationMode,
+    interceptors: Vec<Box<dyn XmlInterceptor>>
+}
+
+impl<D> SCClientBuilder<D> where D: SCClientDelegate {
+    /// Creates a builder for a client connecting to the default
+    /// host and port and joining any open game.
+    pub fn new(delegate: D) -> Self {
+        Self {
+            delegate,
+            host: DEFAULT_HOST.to_owned(),
+            port: DEFAULT_PORT,
+            reservation: None,
+            read_timeout: None,
+            write_timeout: None,
+            debug_mode: DebugMode::default(),
+            reconnect_policy: ReconnectPolicy::default(),
+            timeout_policy: TimeoutPolicy::default(),
+            validation_mode: ValidationMode::default(),
+            interceptors: Vec::new()
+        }
+    }
+
+    /// Sets the game server's host.
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.host = host.into();
+        self
+    }
+
+    /// Sets the game server's port.
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// Joins the prepared game with the given reservation code
+    /// (instead of any open game).
+    pub fn reservation(mut self, reservation: impl Into<String>) -> Self {
+        self.reservation = Some(reservation.into());
+        self
+    }
+
+    /// Sets the time after which a silent connection counts as lost.
+    /// Note that the server is silent while the opponent is thinking,
+    /// thus this should be well above the time limit per move.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the time after which a blocked write counts as a lost connection.
+    pub fn write_timeout(mut self, timeout: Duration) -> Self {
+        self.write_timeout = Some(timeout);
+        self
+    }
+
+    /// Swaps the reader and/or writer by stdio for debugging.
+    pub fn debug_mode(mut self, debug_mode: DebugMode) -> Self {
+        self.debug_mode = debug_mode;
+        self
+    }
+
+    /// Sets how the client tries to reconnect and rejoin
+    /// its game after the connection has been lost.
+    pub fn reconnect_policy(mut self, reconnect_policy: ReconnectPolicy) -> Self {
+        self.reconnect_policy = reconnect_policy;
+        self
+    }
+
+    /// Sets how long the client waits for the delegate
+    /// to provide a move and what it sends otherwise.
+    pub fn timeout_policy(mut self, timeout_policy: TimeoutPolicy) -> Self {
+        self.timeout_policy = timeout_policy;
+        self
+    }
+
+    /// Sets the validation mode of the states passed to the delegate.
+    pub fn validation_mode(mut self, validation_mode: ValidationMode) -> Self {
+        self.validation_mode = validation_mode;
+        self
+    }
+
+    /// Adds a hook observing the raw XML traffic, e.g. an `XmlTranscript`.
+    pub fn interceptor(mut self, interceptor: impl XmlInterceptor + 'static) -> Self {
+        self.interceptors.push(Box::new(interceptor));
+        self
+    }
+
+    /// Creates the client. The moves are computed on a worker
+    /// thread, so the client can fall back to another move
+    /// once the hard limit of the timeout policy is exceeded.
+    pub fn build(self) -> SCClient<D> where D: Send + 'static {
+        self.build_with(Some(SCClient::request_move_with_watchdog))
+    }
+
+    /// Creates a client that computes the moves on its own thread, which
+    /// does not require the delegate to be `Send`. Since the computation
+    /// cannot be abandoned there, the timeout policy has no hard limit.
+    pub fn build_in_thread(mut self) -> SCClient<D> {
+        self.timeout_policy.hard_limit = None;
+        self.build_with(None)
+    }
+
+    /// Creates the client, computing the moves with the given watchdog if any.
+    fn build_with(self, watchdog: Option<Watchdog<D>>) -> SCClient<D> {
+        SCClient {
+            delegate: Arc::new(Mutex::new(self.delegate)),
+            pending: Arc::new(Mutex::new(Vec::new())),
+            host: self.host,
+            port: self.port,
+            reservation: self.reservation,
+            read_timeout: self.read_timeout,
+            write_timeout: self.write_timeout,
+            debug_mode: self.debug_mode,
+            game_state: None,
+            snapshots: StateSnapshots::default(),
+            room_id: None,
+            game_over: false,
+            rejoining: false,
+            reconnect_policy: self.reconnect_policy,
+            timeout_policy: self.timeout_policy,
+            watchdog,
+            validation_mode: self.validation_mode,
+            interceptors: self.interceptors
+        }
+    }
+}
+
 /// The client which handles XML requests, manages
-/// the game state and invokes the delegate.
+/// the game state and invokes the delegate. It is
+/// created using an `SCClientBuilder`.
 pub struct SCClient<D> where D: SCClientDelegate {
-    delegate: D,
+    /// The delegate, shared with the worker thread computing moves.
+    delegate: Arc<Mutex<D>>,
+    /// The notifications that arrived while the delegate was busy.
+    pending: Arc<Mutex<Vec<Notification<D>>>>,
+    host: String,
+    port: u16,
+    /// The reservation code to join with, if any.
+    reservation: Option<String>,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
     debug_mode: DebugMode,
-    game_state: Option<GameState>,
+    game_state: Option<Arc<GameState>>,
+    /// Publishes the game state to other threads.
+    snapshots: StateSnapshots,
+    /// The room the client is currently playing in.
+    room_id: Option<String>,
+    /// Whether the game result has been received.
+    game_over: bool,
+    /// Whether the client is waiting for the confirmation of a rejoin.
+    rejoining: bool,
+    /// How the client reconnects after a lost connection.
+    reconnect_policy: ReconnectPolicy,
+    /// How long the client waits for moves from the delegate.
+    timeout_policy: TimeoutPolicy,
+    /// Computes moves on a worker thread, if the delegate permits it.
+    watchdog: Option<Watchdog<D>>,
+    /// The validation mode of received states.
+    validation_mode: ValidationMode,
+    /// The hooks observing the raw XML traffic.
+    interceptors: Vec<Box<dyn XmlInterceptor>>,
 }
 
 impl<D> SCClient<D> where D: SCClientDelegate {
-    /// Creates a new client using the specified delegate.
-    pub fn new(delegate: D, debug_mode: DebugMode) -> Self {
-        Self { delegate, debug_mode, game_state: None }
+    /// Creates a builder for a client using the specified delegate.
+    pub fn builder(delegate: D) -> SCClientBuilder<D> {
+        SCClientBuilder::new(delegate)
+    }
+
+    /// Fetches a handle to the latest game state received by the
+    /// client, which can be moved to other threads (e.g. for pondering
+    /// or a parallel search) before running the client.
+    pub fn state_snapshots(&self) -> StateSnapshots {
+        self.snapshots.clone()
+    }
+
+    /// Locks the pending notifications.
+    fn pending(&self) -> MutexGuard<'_, Vec<Notification<D>>> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Locks the delegate, waiting for a running move computation,
+    /// and delivers the pending notifications first.
+    fn delegate(&self) -> MutexGuard<'_, D> {
+        // Lock in the same order as the worker to avoid a deadlock
+        let mut delegate = self.delegate.lock().unwrap_or_else(|e| e.into_inner());
+        deliver_pending(&mut *delegate, &mut self.pending());
+        delegate
+    }
+
+    /// Notifies the delegate without waiting for it. If it is still
+    /// computing a move that timed out, the notification is delivered
+    /// once the computation has finished, so no notification is lost
+    /// and they arrive in order.
+    fn notify(&self, notification: impl FnOnce(&mut D) + Send + 'static) {
+        let mut pending = self.pending();
+        pending.push(Box::new(notification));
+        match self.delegate.try_lock() {
+            Ok(mut delegate) => deliver_pending(&mut *delegate, &mut pending),
+            Err(TryLockError::Poisoned(e)) => deliver_pending(&mut *e.into_inner(), &mut pending),
+            Err(TryLockError::WouldBlock) => debug!("The delegate is still busy with a previous move request, notifying it later")
+        }
+    }
+
+    /// Requests a move from the delegate, falling back
+    /// according to the timeout policy if necessary.
+    fn request_move(&self, state: &Arc<GameState>, team: Team) -> Move {
+        let (limit, watchdog) = match (self.timeout_policy.hard_limit, self.watchdog) {
+            (Some(limit), Some(watchdog)) => (limit, watchdog),
+            _ => return search_move(&mut *self.delegate(), state, team)
+        };
+
+        watchdog(self, state, team, limit).unwrap_or_else(|e| {
+            let fallback = self.timeout_policy.fallback.pick(state);
+            warn!("No move from the delegate within {:?} ({}), sending fallback {:?}", limit, e, fallback);
+            fallback
+        })
+    }
+
+    /// Requests a move from the delegate on a worker thread,
+    /// failing if it does not provide one within the given limit.
+    fn request_move_with_watchdog(&self, state: &Arc<GameState>, team: Team, limit: Duration) -> Result<Move, String> where D: Send + 'static {
+        let (sender, receiver) = mpsc::channel();
+        let delegate = Arc::clone(&self.delegate);
+        let pending = Arc::clone(&self.pending);
+        let worker_state = Arc::clone(state);
+        let spawned = thread::Builder::new().name("request-move".to_owned()).spawn(move || {
+            // Fail fast if the previous computation is still running
+            let mut delegate = match delegate.try_lock() {
+                Ok(delegate) => delegate,
+                Err(TryLockError::Poisoned(e)) => e.into_inner(),
+                Err(TryLockError::WouldBlock) => return
+            };
+            deliver_pending(&mut *delegate, &mut pending.lock().unwrap_or_else(|e| e.into_inner()));
+            // Spans are thread-local, so carry over the turn's fields
+            let _span = Span::enter(Level::Debug, "worker").with("turn", worker_state.turn).with("color", worker_state.current_color());
+            let _ = sender.send(search_move(&mut *delegate, &worker_state, team));
+            // Deliver the notifications that arrived in the meantime before
+            // releasing the delegate, `notify` queues them until then
+            let mut pending = pending.lock().unwrap_or_else(|e| e.into_inner());
+            deliver_pending(&mut *delegate, &mut pending);
+            drop(delegate);
+        });
+
+        match spawned {
+            Ok(_) => receiver.recv_timeout(limit).map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string())
+        }
     }
     
     /// Blocks the thread and begins reading XML messages
-    /// from the provided address via TCP.
-    pub fn run(self, host: &str, port: u16, reservation: Option<&str>) -> SCResult<()> {
-        let address = format!("{}:{}", host, port);
-        let stream = TcpStream::connect(&address)?;
+    /// from the configured address via TCP. If the connection
+    /// is lost mid-game, the client tries to rejoin the game
+    /// using the reservation or, if none was given, the room
+    /// it has been playing in.
+    pub fn run(mut self) -> SCResult<()> {
+        // The join messages carry no display name, the server assigns it
+        info!("Starting {}", self.delegate().bot_info());
+
+        let address = format!("{}:{}", self.host, self.port);
+        let reservation = self.reservation.clone();
+        let join_xml = XmlNode::from(match &reservation {
+            Some(res) => Message::JoinPrepared { reservation_code: res.clone() },
+            None => Message::Join { game_type: GAME_TYPE.to_owned() }
+        }).serialize()?;
+
+        if self.run_session(&address, &join_xml)? == SessionEnd::Closed {
+            return Ok(());
+        }
+
+        let mut attempt = 0;
+        while !self.game_over {
+            if attempt == 0 {
+                warn!("Lost the connection to the server");
+                self.notify(|delegate| delegate.on_disconnect());
+            }
+            if attempt >= self.reconnect_policy.max_retries {
+                break;
+            }
+
+            let rejoin_xml = XmlNode::from(match (&reservation, &self.room_id) {
+                (Some(res), _) => Message::JoinPrepared { reservation_code: res.clone() },
+                (None, Some(room_id)) => Message::JoinRoom { room_id: room_id.clone() },
+                (None, None) => break
+            }).serialize()?;
+
+            let delay = self.reconnect_policy.delay(attempt);
+            attempt += 1;
+            warn!("Trying to rejoin in {:?} (attempt {}/{})...", delay, attempt, self.reconnect_policy.max_retries);
+            thread::sleep(delay);
+
+            self.rejoining = true;
+            match self.run_session(&address, &rejoin_xml) {
+                Ok(SessionEnd::Closed) => return Ok(()),
+                Ok(SessionEnd::Dropped) => if !self.rejoining {
+                    // The rejoin succeeded, so start counting anew
+                    attempt = 0;
+                },
+                Err(e) => warn!("Could not rejoin: {:?}", e)
+            }
+
+            if self.rejoining {
+                self.rejoining = false;
+                self.notify(|delegate| delegate.on_rejoin(false));
+            }
+        }
+
+        if self.game_over {
+            Ok(())
+        } else {
+            Err(SCError::protocol("Lost the connection to the server"))
+        }
+    }
+
+    /// Connects to the server, sends the given join
+    /// message and handles messages until the session ends.
+    fn run_session(&mut self, address: &str, join_xml: &str) -> SCResult<SessionEnd> {
+        let stream = TcpStream::connect(address)?;
+        stream.set_read_timeout(self.read_timeout)?;
+        stream.set_write_timeout(self.write_timeout)?;
         info!("Connected to {}", address);
         
         {
             let mut writer = BufWriter::new(&stream);
-            writer.write("<protocol>".as_bytes())?;
-            
-            let join_xml = match reservation {
-                Some(res) => format!("<joinPrepared reservationCode=\"{}\" />", res),
-                None => format!("<join gameType=\"{}\" />", GAME_TYPE)
-            };
+            self.send(&mut writer, "<protocol>")?;
             info!("Sending join message {}", join_xml);
-            writer.write(join_xml.as_bytes())?;
+            self.send(&mut writer, join_xml)?;
         }
         
         // Begin parsing game messages from the stream.
@@ -77,106 +646,330 @@ impl<D> SCClient<D> where D: SCClientDelegate {
 
         let mode = &self.debug_mode;
         if mode.debug_reader && !mode.debug_writer {
-            self.run_game(io::stdin(), BufWriter::new(stream))?;
+            self.run_game(io::stdin(), BufWriter::new(stream))
         } else if !mode.debug_reader && mode.debug_writer {
-            self.run_game(BufReader::new(stream), io::stdout())?;
+            self.run_game(BufReader::new(stream), io::stdout())
         } else if mode.debug_reader && mode.debug_writer {
-            self.run_game(io::stdin(), io::stdout())?;
+            self.run_game(io::stdin(), io::stdout())
         } else {
             let reader = BufReader::new(stream.try_clone()?);
             let writer = BufWriter::new(stream);
-            self.run_game(reader, writer)?;
+            self.run_game(reader, writer)
         }
-        
-        Ok(())
     }
     
     /// Blocks the thread and parses/handles game messages
     /// from the provided reader.
-    fn run_game<R, W>(mut self, reader: R, writer: W) -> SCResult<()> where R: Read, W: Write {
-        let mut xml_reader = EventReader::new(reader);
-
-        let mut emitter_config = EmitterConfig::new();
-        emitter_config.write_document_declaration = false;
+    fn run_game<R, W>(&mut self, reader: R, mut writer: W) -> SCResult<SessionEnd> where R: Read, W: Write {
+        let mut messages = MessageStream::new(reader);
 
-        let mut xml_writer = emitter_config.create_writer(writer);
-        
-        // Read initial protocol element
-        info!("Waiting for initial <protocol>...");
-        while match xml_reader.next() {
-            Ok(XmlReadEvent::StartElement { name, .. }) => Some(name),
-            _ => None
-        }.filter(|n| n.local_name == "protocol").is_none() {}
-
-        loop {
-            let node = XmlNode::read_from(&mut xml_reader)?;
-            debug!("Got XML node {}", node);
-            
-            match node.name() {
-                // Try parsing as room message (the game is running)
-                "room" => match Room::from_node(&node) {
-                    Ok(room) => match room.data {
-                        Data::WelcomeMessage { team } => {
-                            info!("Got welcome message with team: {:?}", team);
-                            self.delegate.on_welcome_message(&team);
-                        },
-                        Data::Memento { state } => {
-                            info!("Got updated game state");
-                            self.delegate.on_update_state(&state);
-                            self.game_state = Some(state);
-                        },
-                        Data::MoveRequest => {
-                            if let Some(ref state) = self.game_state {
-                                let turn = state.turn;
-                                let team = state.current_team();
-                                info!("Got move request @ turn: {}, team: {:?}", turn, team);
-
-                                let new_move = self.delegate.request_move(state, team);
-                                let move_node = XmlNode::try_from(Room {
-                                    room_id: room.room_id,
-                                    data: Data::Move(new_move)
-                                })?;
-
-                                debug!("Sending move {}", move_node);
-                                move_node.write_to(&mut xml_writer)?;
-                                xml_writer.inner_mut().flush()?;
-                            } else {
-                                error!("Got move request, which cannot be fulfilled since no game state is present!");
-                            }
-                        },
-                        Data::GameResult(result) => {
-                            info!("Got game result: {:?}", result);
-                            self.delegate.on_game_end(result);
-                        },
-                        Data::Error { message } => {
-                            warn!("Got error from server: {}", message);
-                        },
-                        _ => warn!("Could not handle room data: {:?}", room.data)
-                    },
-                    Err(e) => error!("Could not parse node as room: {:?}", e)
-                },
-
-                // Try parsing as 'joined' message
-                "joined" => match Joined::from_node(&node) {
-                    Ok(joined) => info!("Joined room {}", joined.room_id),
-                    Err(e) => error!("Could not parse node as 'joined': {:?}", e)
+        for message in messages.by_ref() {
+            let ReceivedMessage { xml, node } = match message {
+                Ok(message) => message,
+                Err(SCError::Io(e)) => {
+                    error!("Could not read from the server: {}", e);
+                    return Ok(SessionEnd::Dropped);
                 },
+                Err(e) => return Err(e)
+            };
+            for interceptor in &mut self.interceptors {
+                interceptor.on_raw_receive(&xml);
+            }
+            debug!("Got XML node {}", node);
 
-                // Try parsing as 'left' message
-                "left" => match Left::from_node(&node) {
-                    Ok(left) => info!("Left room {}", left.room_id),
-                    Err(e) => error!("Could not parse node as 'left': {:?}", e)
-                },
-                
-                "close" | "sc.protocol.responses.CloseConnection" => {
-                    info!("Closing connection as requested by server...");
-                    break;
+            match self.handle_node(&node, &mut writer) {
+                Ok(true) => {},
+                Ok(false) => return Ok(SessionEnd::Closed),
+                Err(SCError::Io(e)) => {
+                    error!("Could not write to the server: {}", e);
+                    return Ok(SessionEnd::Dropped);
                 },
-                
-                _ => warn!("Unrecognized message: <{}>", node.name())
+                Err(e) => return Err(e)
             }
         }
-        
+
+        if messages.is_closed() {
+            info!("Server closed the protocol stream");
+            Ok(SessionEnd::Closed)
+        } else {
+            info!("Connection closed");
+            Ok(if self.game_over { SessionEnd::Closed } else { SessionEnd::Dropped })
+        }
+    }
+
+    /// Handles a single message from the server and
+    /// returns whether the client should keep running.
+    fn handle_node<W>(&mut self, node: &XmlNode, writer: &mut W) -> SCResult<bool> where W: Write {
+        match Message::from_node(node) {
+            // A room message (the game is running)
+            Ok(Message::Room(room)) => {
+                self.confirm_rejoin();
+                self.handle_room(room, writer)?;
+            },
+
+            Ok(Message::Joined(joined)) => {
+                info!("Joined room {}", joined.room_id);
+                self.room_id = Some(joined.room_id);
+                self.confirm_rejoin();
+            },
+
+            Ok(Message::Left(left)) => info!("Left room {}", left.room_id),
+            
+            Ok(Message::Close) => {
+                info!("Closing connection as requested by server...");
+                return Ok(false);
+            },
+
+            Ok(Message::ErrorPacket { message }) => error!("Server reported an error: {}", message),
+            
+            Ok(message) => warn!("Unexpected message: {:?}", message),
+            Err(e) => error!("Could not parse <{}>: {:?}", node.name(), e)
+        }
+
+        Ok(true)
+    }
+
+    /// Handles a message in the game room.
+    fn handle_room<W>(&mut self, room: Room, writer: &mut W) -> SCResult<()> where W: Write {
+        match room.data {
+            Data::WelcomeMessage { team } => {
+                info!("Got welcome message with team: {:?}", team);
+                self.notify(move |delegate| delegate.on_welcome_message(&team));
+            },
+            Data::Memento { mut state } => {
+                info!("Got updated game state");
+                state.validation_mode = self.validation_mode;
+                if let Some(previous) = &self.game_state {
+                    state.extend_history_from(previous);
+                }
+                let state = Arc::new(*state);
+                let update = Arc::clone(&state);
+                self.notify(move |delegate| delegate.on_update_state(&update));
+                self.snapshots.publish(Arc::clone(&state));
+                self.game_state = Some(state);
+            },
+            Data::MoveRequest => {
+                if let Some(ref state) = self.game_state {
+                    let turn = state.turn;
+                    let team = state.current_team();
+                    info!("Got move request @ turn: {}, team: {:?}", turn, team);
+
+                    let span = Span::enter(Level::Info, "turn").with("turn", turn).with("round", state.round).with("color", state.current_color()).with("team", team);
+                    let start = Instant::now();
+                    let new_move = self.request_move(state, team);
+                    Metrics::global().move_time.observe_since(start);
+                    span.record("move", &new_move);
+                    let move_node = XmlNode::from(Room {
+                        room_id: room.room_id,
+                        data: Data::Move(new_move)
+                    });
+
+                    debug!("Sending move {}", move_node);
+                    let move_xml = move_node.serialize()?;
+                    self.send(writer, &move_xml)?;
+                } else {
+                    error!("Got move request, which cannot be fulfilled since no game state is present!");
+                }
+            },
+            Data::GameResult(result) => {
+                info!("Got game result: {:?}", result);
+                info!("Performance: {}", Metrics::global().summary());
+                self.game_over = true;
+                self.delegate().on_game_end(result);
+            },
+            Data::Error { message } => {
+                warn!("Got error from server: {}", message);
+            },
+            Data::Unknown { class, node } => {
+                warn!("Ignoring room data of unknown class {}", class);
+                self.notify(move |delegate| delegate.on_unknown_data(&class, &node));
+            },
+            _ => warn!("Could not handle room data: {:?}", room.data)
+        }
+
         Ok(())
     }
+
+    /// Sends raw XML to the server.
+    fn send<W>(&mut self, writer: &mut W, xml: &str) -> SCResult<()> where W: Write {
+        for interceptor in &mut self.interceptors {
+            interceptor.on_raw_send(xml);
+        }
+        writer.write_all(xml.as_bytes())?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Notifies the delegate if a pending rejoin succeeded.
+    fn confirm_rejoin(&mut self) {
+        if self.rejoining {
+            info!("Successfully rejoined the game");
+            self.rejoining = false;
+            self.notify(|delegate| delegate.on_rejoin(true));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, io::Cursor, rc::Rc, sync::{Arc, Mutex}, thread, time::{Duration, Instant}};
+    use crate::game::{GameState, Move, PIECE_SHAPES_BY_NAME, Team, ValidationMode};
+    use crate::util::XmlNode;
+    use super::{FallbackMove, SCClient, SCClientDelegate, SessionEnd, TimeoutPolicy, XmlInterceptor};
+
+    /// Takes far too long to pick a skip.
+    struct Sleeper;
+
+    impl SCClientDelegate for Sleeper {
+        fn request_move(&mut self, state: &GameState, _my_team: Team) -> Move {
+            thread::sleep(Duration::from_millis(500));
+            Move::Skip { color: state.current_color() }
+        }
+    }
+
+    /// Records the received messages.
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl XmlInterceptor for Recorder {
+        fn on_raw_receive(&mut self, xml: &str) {
+            self.0.lock().unwrap().push(xml.to_owned());
+        }
+    }
+
+    #[test]
+    fn test_interceptor() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let mut client = SCClient::builder(Sleeper).interceptor(Recorder(Arc::clone(&received))).build();
+        let transcript = "<protocol>\n  <joined roomId=\"abc\" />\n  <sc.protocol.responses.CloseConnection/>\n</protocol>";
+
+        let end = client.run_game(Cursor::new(transcript), Vec::new()).unwrap();
+        assert_eq!(end, SessionEnd::Closed);
+        assert_eq!(client.room_id.as_deref(), Some("abc"));
+        assert_eq!(*received.lock().unwrap(), vec!["<joined roomId=\"abc\" />", "<sc.protocol.responses.CloseConnection/>"]);
+    }
+
+    /// Records the classes of unknown room data.
+    struct Tolerant(Arc<Mutex<Vec<String>>>);
+
+    impl SCClientDelegate for Tolerant {
+        fn on_unknown_data(&mut self, class: &str, _node: &XmlNode) {
+            self.0.lock().unwrap().push(class.to_owned());
+        }
+
+        fn request_move(&mut self, state: &GameState, _my_team: Team) -> Move {
+            Move::Skip { color: state.current_color() }
+        }
+    }
+
+    #[test]
+    fn test_unknown_data() {
+        let classes = Arc::new(Mutex::new(Vec::new()));
+        let mut client = SCClient::builder(Tolerant(Arc::clone(&classes))).build();
+        let transcript = concat!(
+            "<protocol>\n",
+            "  <room roomId=\"abc\" extra=\"1\"><data class=\"sc.plugin2022.Hint\"><text>Hi</text></data></room>\n",
+            "  <joined roomId=\"abc\" />\n",
+            "  <sc.protocol.responses.CloseConnection/>\n",
+            "</protocol>"
+        );
+
+        let end = client.run_game(Cursor::new(transcript), Vec::new()).unwrap();
+        assert_eq!(end, SessionEnd::Closed);
+        assert_eq!(client.room_id.as_deref(), Some("abc"));
+        assert_eq!(*classes.lock().unwrap(), vec!["sc.plugin2022.Hint"]);
+    }
+
+    #[test]
+    fn test_builder() {
+        let mut client = SCClient::builder(Sleeper)
+            .host("example.com")
+            .port(1234)
+            .reservation("r1")
+            .read_timeout(Duration::from_secs(30))
+            .validation_mode(ValidationMode::Never)
+            .build();
+        assert_eq!((client.host.as_str(), client.port), ("example.com", 1234));
+        assert_eq!(client.reservation.as_deref(), Some("r1"));
+        assert_eq!(client.read_timeout, Some(Duration::from_secs(30)));
+        assert_eq!(client.write_timeout, None);
+
+        let state = XmlNode::from(GameState::new(PIECE_SHAPES_BY_NAME["MONO"].clone())).serialize().unwrap();
+        let transcript = format!("<protocol><room roomId=\"abc\"><data class=\"memento\">{}</data></room></protocol>", state);
+        let snapshots = client.state_snapshots();
+        assert!(snapshots.latest().is_none());
+        client.run_game(Cursor::new(transcript), Vec::new()).unwrap();
+        let latest = thread::spawn(move || snapshots.latest()).join().unwrap().unwrap();
+        assert!(Arc::ptr_eq(&latest, client.game_state.as_ref().unwrap()));
+        assert_eq!(latest.validation_mode, ValidationMode::Never);
+    }
+
+    #[test]
+    fn test_timeout_fallback() {
+        let client = SCClient::builder(Sleeper).timeout_policy(TimeoutPolicy {
+            hard_limit: Some(Duration::from_millis(50)),
+            fallback: FallbackMove::FirstLegal
+        }).build();
+        let state = Arc::new(GameState::new(PIECE_SHAPES_BY_NAME["MONO"].clone()));
+        let first_legal = state.possible_moves().next().unwrap();
+
+        let start = Instant::now();
+        assert_eq!(client.request_move(&state, Team::One), first_legal);
+        // The delegate is still busy, so the next request falls back immediately
+        assert_eq!(client.request_move(&state, Team::One), first_legal);
+        assert!(start.elapsed() < Duration::from_millis(400));
+    }
+
+    /// Records its hooks and takes far too long to pick a skip.
+    struct BusyRecorder(Arc<Mutex<Vec<&'static str>>>);
+
+    impl SCClientDelegate for BusyRecorder {
+        fn on_update_state(&mut self, _state: &GameState) {
+            self.0.lock().unwrap().push("update");
+        }
+
+        fn request_move(&mut self, state: &GameState, _my_team: Team) -> Move {
+            thread::sleep(Duration::from_millis(200));
+            self.0.lock().unwrap().push("move");
+            Move::Skip { color: state.current_color() }
+        }
+    }
+
+    #[test]
+    fn test_notifications_while_busy() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let client = SCClient::builder(BusyRecorder(Arc::clone(&events))).timeout_policy(TimeoutPolicy {
+            hard_limit: Some(Duration::from_millis(20)),
+            fallback: FallbackMove::FirstLegal
+        }).build();
+        let state = Arc::new(GameState::new(PIECE_SHAPES_BY_NAME["MONO"].clone()));
+
+        client.request_move(&state, Team::One);
+        for _ in 0..2 {
+            let update = Arc::clone(&state);
+            client.notify(move |delegate| delegate.on_update_state(&update));
+        }
+        // The updates are delivered once the worker releases the delegate
+        drop(client.delegate());
+        assert_eq!(*events.lock().unwrap(), vec!["move", "update", "update"]);
+    }
+
+    /// Counts its move requests, which keeps it on one thread.
+    struct Counter(Rc<Cell<usize>>);
+
+    impl SCClientDelegate for Counter {
+        fn request_move(&mut self, state: &GameState, _my_team: Team) -> Move {
+            self.0.set(self.0.get() + 1);
+            Move::Skip { color: state.current_color() }
+        }
+    }
+
+    #[test]
+    fn test_in_thread_client() {
+        let requests = Rc::new(Cell::new(0));
+        let client = SCClient::builder(Counter(Rc::clone(&requests))).build_in_thread();
+        assert_eq!(client.timeout_policy.hard_limit, None);
+        let state = Arc::new(GameState::new(PIECE_SHAPES_BY_NAME["MONO"].clone()));
+        assert_eq!(client.request_move(&state, Team::One), Move::Skip { color: state.current_color() });
+        assert_eq!(requests.get(), 1);
+    }
 }
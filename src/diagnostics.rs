@@ -0,0 +1,72 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+use crate::client::GAME_TYPE;
+use crate::game::{GameState, PieceShapeKind};
+use crate::rules::RULES_VERSION;
+
+/// The library used for (de)serializing the XML protocol.
+const PROTOCOL_BACKEND: &str = "xml-rs";
+/// The optional features this crate was compiled with.
+const FEATURES: &[&str] = &[
+    #[cfg(feature = "broadcast")]
+    "broadcast",
+    #[cfg(feature = "tui")]
+    "tui",
+];
+/// How long the move generation self-test should run.
+const SELF_TEST_DURATION: Duration = Duration::from_millis(200);
+
+/// Information about the crate's build and performance,
+/// intended to be attached to bug reports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostics {
+    pub crate_version: &'static str,
+    pub features: Vec<&'static str>,
+    pub debug_build: bool,
+    pub rules_version: &'static str,
+    pub game_type: &'static str,
+    pub protocol_backend: &'static str,
+    /// The number of moves generated per second on a reference position.
+    pub moves_per_second: f64
+}
+
+/// Collects diagnostic information, including the
+/// results of a short performance self-test.
+pub fn diagnostics() -> Diagnostics {
+    Diagnostics {
+        crate_version: env!("CARGO_PKG_VERSION"),
+        features: FEATURES.to_vec(),
+        debug_build: cfg!(debug_assertions),
+        rules_version: RULES_VERSION,
+        game_type: GAME_TYPE,
+        protocol_backend: PROTOCOL_BACKEND,
+        moves_per_second: measure_move_generation()
+    }
+}
+
+/// Measures the move generation throughput on the
+/// position after every color has placed its first piece.
+fn measure_move_generation() -> f64 {
+    let mut state = GameState::new(PieceShapeKind::PentoY.shape().clone());
+    for _ in 0..4 {
+        let first_move = state.possible_moves().next().expect("No first move found");
+        state.perform_move(first_move).expect("Could not perform first move");
+    }
+
+    let start = Instant::now();
+    let mut count = 0;
+    while start.elapsed() < SELF_TEST_DURATION {
+        count += state.possible_moves().count();
+    }
+    count as f64 / start.elapsed().as_secs_f64()
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "socha-client-2021 {} ({} build)", self.crate_version, if self.debug_build { "debug" } else { "release" })?;
+        writeln!(f, "Features: {}", if self.features.is_empty() { "none".to_owned() } else { self.features.join(", ") })?;
+        writeln!(f, "Rules: {} ({})", self.rules_version, self.game_type)?;
+        writeln!(f, "Protocol backend: {}", self.protocol_backend)?;
+        write!(f, "Move generation: {:.0} moves/s", self.moves_per_second)
+    }
+}
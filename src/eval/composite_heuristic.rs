@@ -0,0 +1,106 @@
+use std::cmp::Ordering;
+use crate::game::{GameState, Move, Team};
+use super::{EvalBreakdown, Heuristic};
+
+/// A named, weighted term of a composite heuristic.
+struct Term {
+    name: String,
+    weight: f64,
+    heuristic: Box<dyn Heuristic>
+}
+
+/// A heuristic composed of a weighted sum of named terms.
+#[derive(Default)]
+pub struct CompositeHeuristic {
+    terms: Vec<Term>
+}
+
+impl CompositeHeuristic {
+    /// Creates a new composite heuristic without any terms.
+    pub fn new() -> Self {
+        Self { terms: Vec::new() }
+    }
+
+    /// Adds the given term.
+    pub fn term(mut self, name: impl Into<String>, weight: f64, heuristic: impl Heuristic + 'static) -> Self {
+        self.terms.push(Term { name: name.into(), weight, heuristic: Box::new(heuristic) });
+        self
+    }
+
+    /// Evaluates the given state term-by-term.
+    pub fn explain(&self, state: &GameState, team: Team) -> EvalBreakdown {
+        EvalBreakdown {
+            terms: self.terms.iter()
+                .map(|t| (t.name.clone(), t.heuristic.evaluate(state, team), t.weight))
+                .collect()
+        }
+    }
+
+    /// Evaluates the states after each of the possible moves and
+    /// returns (at most) the given number of best moves, together
+    /// with their breakdowns, in descending order. The first entry
+    /// is thus the move a greedy player would choose.
+    pub fn explain_best_moves(&self, state: &GameState, team: Team, count: usize) -> Vec<(Move, EvalBreakdown)> {
+        let mut explained: Vec<_> = state.possible_moves()
+            .filter_map(|m| {
+                let breakdown = self.explain(&state.after_move(m.clone()).ok()?, team);
+                Some((m, breakdown))
+            })
+            .collect();
+        explained.sort_by(|(_, b1), (_, b2)| b2.total().partial_cmp(&b1.total()).unwrap_or(Ordering::Equal));
+        explained.truncate(count);
+        explained
+    }
+}
+
+impl Heuristic for CompositeHeuristic {
+    fn evaluate(&self, state: &GameState, team: Team) -> f64 {
+        self.terms.iter().map(|t| t.weight * t.heuristic.evaluate(state, team)).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::eval::{Heuristic, PointDifference};
+    use crate::game::{GameState, PIECE_SHAPES_BY_NAME, Team};
+    use super::CompositeHeuristic;
+
+    fn heuristic() -> CompositeHeuristic {
+        CompositeHeuristic::new()
+            .term("points", 2.0, PointDifference)
+            .term("mobility", 0.5, |state: &GameState, team: Team| state.possible_moves_for_team(team).count() as f64)
+    }
+
+    #[test]
+    fn test_explain() {
+        let heuristic = heuristic();
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_Y"].clone());
+        let first_move = state.possible_moves().next().unwrap();
+        state.perform_move(first_move).unwrap();
+
+        let breakdown = heuristic.explain(&state, Team::One);
+        let names: Vec<_> = breakdown.terms.iter().map(|(name, _, weight)| (name.as_str(), *weight)).collect();
+        assert_eq!(names, [("points", 2.0), ("mobility", 0.5)]);
+        assert_eq!(breakdown.value_of("points"), Some(PointDifference.evaluate(&state, Team::One)));
+        assert_eq!(breakdown.total(), heuristic.evaluate(&state, Team::One));
+    }
+
+    #[test]
+    fn test_explain_best_moves() {
+        let heuristic = heuristic();
+        let state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_Y"].clone());
+
+        let best = heuristic.explain_best_moves(&state, Team::One, 3);
+        assert_eq!(best.len(), 3);
+        for (game_move, breakdown) in &best {
+            let after = state.after_move(game_move.clone()).unwrap();
+            assert_eq!(breakdown.total(), heuristic.evaluate(&after, Team::One));
+        }
+        assert!(best.windows(2).all(|pair| pair[0].1.total() >= pair[1].1.total()));
+
+        let best_value = state.possible_moves()
+            .map(|m| heuristic.evaluate(&state.after_move(m).unwrap(), Team::One))
+            .fold(f64::NEG_INFINITY, f64::max);
+        assert_eq!(best[0].1.total(), best_value);
+    }
+}
@@ -0,0 +1,31 @@
+use std::fmt;
+
+/// A per-term explanation of a composed evaluation,
+/// useful for finding out which term is responsible
+/// for a (questionable) decision.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EvalBreakdown {
+    /// The evaluated terms as (name, value, weight) triples.
+    pub terms: Vec<(String, f64, f64)>
+}
+
+impl EvalBreakdown {
+    /// The weighted sum of all terms.
+    pub fn total(&self) -> f64 {
+        self.terms.iter().map(|(_, value, weight)| value * weight).sum()
+    }
+
+    /// Fetches the unweighted value of the term with the given name.
+    pub fn value_of(&self, name: &str) -> Option<f64> {
+        self.terms.iter().find(|(n, _, _)| n == name).map(|&(_, value, _)| value)
+    }
+}
+
+impl fmt::Display for EvalBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value, weight) in &self.terms {
+            writeln!(f, "{}: {:.3} * {:.3} = {:.3}", name, value, weight, value * weight)?;
+        }
+        write!(f, "total: {:.3}", self.total())
+    }
+}
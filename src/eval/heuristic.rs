@@ -0,0 +1,15 @@
+use crate::game::{GameState, Team};
+
+/// A function that statically evaluates a game state
+/// from the perspective of a team. Higher values are
+/// better for the given team.
+pub trait Heuristic {
+    /// Evaluates the given state for the given team.
+    fn evaluate(&self, state: &GameState, team: Team) -> f64;
+}
+
+impl<F> Heuristic for F where F: Fn(&GameState, Team) -> f64 {
+    fn evaluate(&self, state: &GameState, team: Team) -> f64 {
+        self(state, team)
+    }
+}
@@ -0,0 +1,13 @@
+//! Evaluation functions for game states.
+
+mod composite_heuristic;
+mod eval_breakdown;
+mod heuristic;
+mod placement_preview;
+mod point_difference;
+
+pub use composite_heuristic::*;
+pub use eval_breakdown::*;
+pub use heuristic::*;
+pub use placement_preview::*;
+pub use point_difference::*;
@@ -0,0 +1,77 @@
+use std::{cmp::Ordering, collections::HashMap};
+use crate::game::{GameState, Move, Piece, PieceShape, Vec2};
+use super::Heuristic;
+
+/// A placement together with the heuristic's score
+/// for the state after placing it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankedPlacement {
+    pub piece: Piece,
+    pub score: f64
+}
+
+/// Caches the valid placements of the current color,
+/// indexed by shape and covered board cell. This is
+/// intended for interactive GUIs, e.g. for showing a
+/// 'ghost' preview of the best placement of a dragged
+/// piece covering the hovered cell.
+pub struct PlacementPreview<H> where H: Heuristic {
+    state: GameState,
+    heuristic: H,
+    cache: HashMap<PieceShape, HashMap<Vec2, Vec<RankedPlacement>>>
+}
+
+impl<H> PlacementPreview<H> where H: Heuristic {
+    /// Creates a new preview for the current color of the given state.
+    pub fn new(state: GameState, heuristic: H) -> Self {
+        Self { state, heuristic, cache: HashMap::new() }
+    }
+
+    /// The state the placements are computed for.
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    /// Replaces the state, invalidating the cache.
+    pub fn set_state(&mut self, state: GameState) {
+        self.state = state;
+        self.cache.clear();
+    }
+
+    /// Fetches the valid (transformed) placements of the given
+    /// shape that cover the given cell, best placements first.
+    pub fn placements_at(&mut self, shape: &PieceShape, cell: Vec2) -> &[RankedPlacement] {
+        if !self.cache.contains_key(shape) {
+            let by_cell = self.rank_placements(shape);
+            self.cache.insert(shape.clone(), by_cell);
+        }
+        self.cache[shape].get(&cell).map(|p| p.as_slice()).unwrap_or(&[])
+    }
+
+    /// Fetches the best placement of the given shape covering the given cell.
+    pub fn best_at(&mut self, shape: &PieceShape, cell: Vec2) -> Option<&RankedPlacement> {
+        self.placements_at(shape, cell).first()
+    }
+
+    /// Scores every placement of the shape and indexes them by covered cell.
+    fn rank_placements(&self, shape: &PieceShape) -> HashMap<Vec2, Vec<RankedPlacement>> {
+        let team = self.state.current_team();
+        let mut by_cell = HashMap::<Vec2, Vec<RankedPlacement>>::new();
+
+        for piece in self.state.possible_placements(shape) {
+            let score = match self.state.after_move(Move::Set { piece: piece.clone() }) {
+                Ok(next) => self.heuristic.evaluate(&next, team),
+                Err(_) => continue
+            };
+            for cell in piece.coordinates() {
+                by_cell.entry(cell).or_default().push(RankedPlacement { piece: piece.clone(), score });
+            }
+        }
+
+        for placements in by_cell.values_mut() {
+            placements.sort_by(|p1, p2| p2.score.partial_cmp(&p1.score).unwrap_or(Ordering::Equal));
+        }
+
+        by_cell
+    }
+}
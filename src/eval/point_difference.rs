@@ -0,0 +1,13 @@
+use crate::game::{GameState, Team};
+use super::Heuristic;
+
+/// Evaluates a state by the difference between
+/// the own and the opponent team's points.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct PointDifference;
+
+impl Heuristic for PointDifference {
+    fn evaluate(&self, state: &GameState, team: Team) -> f64 {
+        (state.points_of_team(team) - state.points_of_team(team.opponent())) as f64
+    }
+}
@@ -0,0 +1,223 @@
+//! A C ABI for the rules engine, e.g. to drive self-play from
+//! Python or Julia. Build it as a shared library with
+//!
+//! ```text
+//! cargo rustc --lib --release --features ffi --crate-type cdylib
+//! ```
+//!
+//! States are opaque pointers that have to be released with
+//! `sc_state_free`. Colors are passed as indices into the turn
+//! order Blue, Yellow, Red, Green and moves in the packed `u32`
+//! encoding of `Move::encode`, which always refers to the current color.
+
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::ptr;
+use crate::game::{COLORS, Color, GameState, Move, PIECE_SHAPES};
+
+/// The packed encoding of a skip.
+pub const SC_SKIP: u32 = Move::ENCODED_SKIP;
+
+/// The operation succeeded.
+pub const SC_OK: i32 = 0;
+/// A pointer argument was null.
+pub const SC_NULL_POINTER: i32 = -1;
+/// The packed move or the color index is invalid.
+pub const SC_INVALID_ENCODING: i32 = -2;
+/// The move is not legal in the state.
+pub const SC_ILLEGAL_MOVE: i32 = -3;
+
+fn color_index(color: Color) -> u32 {
+    COLORS.iter().position(|&c| c == color).map_or(u32::MAX, |i| i as u32)
+}
+
+/// Creates the initial state with the given start piece (an index
+/// into `PIECE_SHAPES`). Yields null if the index is out of range.
+#[no_mangle]
+pub extern "C" fn sc_state_new(start_piece: u32) -> *mut GameState {
+    match PIECE_SHAPES.get(start_piece as usize) {
+        Some(shape) => Box::into_raw(Box::new(GameState::new(shape.clone()))),
+        None => ptr::null_mut()
+    }
+}
+
+/// Parses a state from JSON (as written by `GameState::save`) or from
+/// the XML of a memento. Yields null if the text cannot be parsed.
+///
+/// # Safety
+/// The text has to be a valid, null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn sc_state_parse(text: *const c_char) -> *mut GameState {
+    if text.is_null() {
+        return ptr::null_mut();
+    }
+    match CStr::from_ptr(text).to_str().ok().and_then(|text| text.parse::<GameState>().ok()) {
+        Some(state) => Box::into_raw(Box::new(state)),
+        None => ptr::null_mut()
+    }
+}
+
+/// Copies the state, e.g. to explore several continuations.
+///
+/// # Safety
+/// The state has to be null or a pointer obtained from this module that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn sc_state_clone(state: *const GameState) -> *mut GameState {
+    match state.as_ref() {
+        Some(state) => Box::into_raw(Box::new(state.clone())),
+        None => ptr::null_mut()
+    }
+}
+
+/// Releases the state.
+///
+/// # Safety
+/// The state has to be null or a pointer obtained from this module that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn sc_state_free(state: *mut GameState) {
+    if !state.is_null() {
+        drop(Box::from_raw(state));
+    }
+}
+
+/// The index of the color to move (0 = Blue, ..., 3 = Green).
+///
+/// # Safety
+/// The state has to be a pointer obtained from this module that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn sc_state_current_color(state: *const GameState) -> u32 {
+    state.as_ref().map_or(u32::MAX, |state| color_index(state.current_color()))
+}
+
+/// The number of moves performed so far.
+///
+/// # Safety
+/// The state has to be a pointer obtained from this module that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn sc_state_turn(state: *const GameState) -> u32 {
+    state.as_ref().map_or(0, |state| state.turn)
+}
+
+/// The current round.
+///
+/// # Safety
+/// The state has to be a pointer obtained from this module that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn sc_state_round(state: *const GameState) -> u32 {
+    state.as_ref().map_or(0, |state| state.round)
+}
+
+/// Whether the game is over, i.e. the round limit has been
+/// exceeded or no color can place another piece.
+///
+/// # Safety
+/// The state has to be a pointer obtained from this module that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn sc_state_is_over(state: *const GameState) -> bool {
+    state.as_ref().is_none_or(GameState::is_game_over)
+}
+
+/// Writes the packed legal moves of the current color into the
+/// buffer, at most `capacity` of them, and returns the total number
+/// of legal moves. Passing a capacity of 0 only counts them.
+///
+/// # Safety
+/// The state has to be a pointer obtained from this module that has not been
+/// freed and the buffer has to be null or valid for `capacity` writes.
+#[no_mangle]
+pub unsafe extern "C" fn sc_state_legal_moves(state: *const GameState, buffer: *mut u32, capacity: usize) -> usize {
+    let state = match state.as_ref() {
+        Some(state) => state,
+        None => return 0
+    };
+    let mut count = 0;
+    for game_move in state.possible_moves() {
+        if count < capacity && !buffer.is_null() {
+            *buffer.add(count) = game_move.encode();
+        }
+        count += 1;
+    }
+    count
+}
+
+/// Validates and performs the packed move for the current color.
+/// The state is left untouched if the move is not legal.
+///
+/// # Safety
+/// The state has to be a pointer obtained from this module that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn sc_state_apply_move(state: *mut GameState, packed: u32) -> i32 {
+    let state = match state.as_mut() {
+        Some(state) => state,
+        None => return SC_NULL_POINTER
+    };
+    let game_move = match Move::decode(packed, state.current_color()) {
+        Some(game_move) => game_move,
+        None => return SC_INVALID_ENCODING
+    };
+    match state.perform_move_checked(game_move) {
+        Ok(()) => SC_OK,
+        Err(_) => SC_ILLEGAL_MOVE
+    }
+}
+
+/// Writes the current points of the given color into `points`.
+///
+/// # Safety
+/// The state has to be a pointer obtained from this module that
+/// has not been freed and `points` has to be valid for a write.
+#[no_mangle]
+pub unsafe extern "C" fn sc_state_points(state: *const GameState, color: u32, points: *mut i32) -> i32 {
+    let (state, points) = match (state.as_ref(), points.as_mut()) {
+        (Some(state), Some(points)) => (state, points),
+        _ => return SC_NULL_POINTER
+    };
+    match COLORS.get(color as usize) {
+        Some(&color) => {
+            *points = state.points_of_color(color);
+            SC_OK
+        },
+        None => SC_INVALID_ENCODING
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+    use std::ptr;
+    use crate::util::ToJson;
+    use super::{SC_ILLEGAL_MOVE, SC_INVALID_ENCODING, SC_OK, SC_SKIP, sc_state_apply_move, sc_state_clone, sc_state_current_color, sc_state_free, sc_state_is_over, sc_state_legal_moves, sc_state_new, sc_state_parse, sc_state_points, sc_state_turn};
+
+    #[test]
+    fn test_self_play() {
+        unsafe {
+            assert!(sc_state_new(21).is_null());
+            let state = sc_state_new(20);
+            let mut moves = vec![0; 4096];
+            while !sc_state_is_over(state) {
+                let count = sc_state_legal_moves(state, ptr::null_mut(), 0);
+                assert_eq!(sc_state_legal_moves(state, moves.as_mut_ptr(), moves.len()), count);
+                let packed = if count > 0 { moves[count / 2] } else { SC_SKIP };
+                assert_eq!(sc_state_apply_move(state, packed), SC_OK);
+            }
+            assert!(sc_state_turn(state) > 4);
+            assert_eq!(sc_state_apply_move(state, u32::MAX), SC_INVALID_ENCODING);
+
+            let first = sc_state_new(20);
+            assert_eq!(sc_state_current_color(first), 0);
+            assert_eq!(sc_state_apply_move(first, SC_SKIP), SC_ILLEGAL_MOVE);
+            let copy = sc_state_clone(first);
+            let mut points = -1;
+            assert_eq!(sc_state_points(copy, 0, &mut points), SC_OK);
+            assert_eq!(points, 0);
+            assert_eq!(sc_state_points(copy, 4, &mut points), SC_INVALID_ENCODING);
+
+            let json = CString::new((*state).to_json().to_string()).unwrap();
+            let parsed = sc_state_parse(json.as_ptr());
+            assert_eq!(*parsed, *state);
+            for state in [state, first, copy, parsed] {
+                sc_state_free(state);
+            }
+        }
+    }
+}
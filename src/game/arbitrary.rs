@@ -0,0 +1,179 @@
+use std::{fmt, panic::{self, AssertUnwindSafe}};
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+use crate::rules::BOARD_SIZE;
+use super::{Board, COLORS, Color, GameState, PIECE_SHAPES, Piece, PieceShape, ROTATIONS, Rotation, Vec2};
+
+/// A type whose values can be generated randomly,
+/// e.g. for property-based tests.
+pub trait Arbitrary: Sized {
+    /// Generates a random value.
+    fn arbitrary(rng: &mut StdRng) -> Self;
+}
+
+/// Checks the given property for the given number of random values.
+/// Every case is generated from its own seed, which the panic message
+/// names on failure together with the value, so failing cases can be
+/// reproduced using `StdRng::seed_from_u64`.
+pub fn for_all<T>(cases: u64, seed: u64, property: impl Fn(T)) where T: Arbitrary + fmt::Debug + Clone {
+    for case in 0..cases {
+        let case_seed = seed.wrapping_add(case);
+        let value = T::arbitrary(&mut StdRng::seed_from_u64(case_seed));
+        if let Err(e) = panic::catch_unwind(AssertUnwindSafe(|| property(value.clone()))) {
+            let message = e.downcast_ref::<String>().cloned()
+                .or_else(|| e.downcast_ref::<&str>().map(|s| s.to_string()))
+                .unwrap_or_default();
+            panic!("Property failed for case seed {}: {}\nValue: {:?}", case_seed, message, value);
+        }
+    }
+}
+
+impl Arbitrary for Color {
+    fn arbitrary(rng: &mut StdRng) -> Self {
+        *COLORS.choose(rng).unwrap()
+    }
+}
+
+impl Arbitrary for Rotation {
+    fn arbitrary(rng: &mut StdRng) -> Self {
+        *ROTATIONS.choose(rng).unwrap()
+    }
+}
+
+impl Arbitrary for Vec2 {
+    /// Generates a position on the board.
+    fn arbitrary(rng: &mut StdRng) -> Self {
+        Vec2::new(rng.gen_range(0..BOARD_SIZE as i32), rng.gen_range(0..BOARD_SIZE as i32))
+    }
+}
+
+impl Arbitrary for PieceShape {
+    fn arbitrary(rng: &mut StdRng) -> Self {
+        PIECE_SHAPES.choose(rng).unwrap().clone()
+    }
+}
+
+impl Arbitrary for Piece {
+    /// Generates a transformed piece that lies within the board.
+    fn arbitrary(rng: &mut StdRng) -> Self {
+        let kind = PieceShape::arbitrary(rng);
+        let (rotation, is_flipped) = (Rotation::arbitrary(rng), rng.gen());
+        let size = kind.transform(rotation, is_flipped).coordinates().fold(Vec2::zero(), Vec2::max);
+        let position = Vec2::new(rng.gen_range(0..BOARD_SIZE as i32 - size.x), rng.gen_range(0..BOARD_SIZE as i32 - size.y));
+        Piece { kind, rotation, is_flipped, color: Color::arbitrary(rng), position }
+    }
+}
+
+impl Arbitrary for Board {
+    /// Generates a board with randomly colored fields,
+    /// which does not necessarily follow the rules.
+    fn arbitrary(rng: &mut StdRng) -> Self {
+        let density = rng.gen_range(0.0..0.6);
+        let mut board = Board::new();
+        for position in Board::positions() {
+            if rng.gen_bool(density) {
+                board.set(position, Color::arbitrary(rng));
+            }
+        }
+        board
+    }
+}
+
+impl Arbitrary for GameState {
+    /// Generates a legal state by playing up to 60 random legal moves.
+    fn arbitrary(rng: &mut StdRng) -> Self {
+        let start_piece = loop {
+            // The X-shaped pentomino cannot be placed in a corner
+            let shape = PieceShape::arbitrary(rng);
+            if shape.name() != "PENTO_X" {
+                break shape;
+            }
+        };
+        let move_count = rng.gen_range(0..=60);
+        GameState::random_legal(start_piece, move_count, rng)
+    }
+}
+
+impl GameState {
+    /// Creates a state by playing up to the given number of
+    /// random legal moves from the start, stopping early if the
+    /// round limit is reached or no color can place any more pieces.
+    pub fn random_legal(start_piece: PieceShape, move_count: usize, rng: &mut impl Rng) -> Self {
+        let mut state = GameState::new(start_piece);
+        for _ in 0..move_count {
+            if state.rules.is_past_round_limit(state.round) {
+                break;
+            }
+            let moves: Vec<_> = state.possible_moves().collect();
+            match moves.choose(rng) {
+                Some(game_move) => state.perform_move(game_move.clone()).expect("Generated an invalid move"),
+                None => break
+            }
+        }
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{Board, GameState, Move, Piece, Symmetry, SYMMETRIES};
+    #[cfg(feature = "json")]
+    use crate::util::{FromJson, ToJson};
+    use super::for_all;
+
+    #[test]
+    fn test_possible_moves_are_valid() {
+        for_all(20, 1, |state: GameState| {
+            for game_move in state.possible_moves() {
+                assert!(state.validate_move(&game_move).is_ok(), "{:?} is invalid", game_move);
+            }
+        });
+    }
+
+    #[test]
+    fn test_undo_restores_state() {
+        for_all(20, 2, |state: GameState| {
+            let mut after = state.clone();
+            let mut undos = Vec::new();
+            for _ in 0..8 {
+                match after.possible_moves().next() {
+                    Some(game_move) => undos.push(after.perform_move_undoable(game_move).unwrap()),
+                    None => break
+                }
+            }
+            for undo in undos.into_iter().rev() {
+                after.undo_move(undo);
+            }
+            assert!(after == state, "Undoing does not restore the state");
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_json_round_trip() {
+        for_all(20, 3, |state: GameState| {
+            assert!(GameState::from_json(&state.to_json()).unwrap() == state);
+        });
+    }
+
+    #[test]
+    fn test_symmetries_are_invertible() {
+        for_all(50, 4, |board: Board| {
+            for symmetry in SYMMETRIES {
+                assert_eq!(board.transformed(symmetry).transformed(symmetry.inverse()), board);
+            }
+            assert_eq!(board.transformed(Symmetry::Identity), board);
+        });
+        for_all(50, 5, |piece: Piece| {
+            for symmetry in SYMMETRIES {
+                let Move::Set { piece: transformed } = symmetry.apply_to_move(&Move::Set { piece: piece.clone() }) else { unreachable!() };
+                assert_eq!(transformed.coordinates().count(), piece.coordinates().count());
+            }
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Property failed for case seed 7")]
+    fn test_failure_reports_seed() {
+        for_all(1, 7, |_: Board| panic!("Always fails"));
+    }
+}
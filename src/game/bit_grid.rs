@@ -0,0 +1,352 @@
+use std::{fmt, iter::FromIterator, ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, Not, Sub}};
+use super::Vec2;
+
+/// A fixed-size W x H grid of bits, i.e. a set of coordinates,
+/// with fast set operations. Each row is stored in a `u32`,
+/// with the bit at index x representing the column x:
+///
+/// ```text
+///  row 0: bit 0 (x = 0), bit 1 (x = 1), ..., bit W - 1
+///  row 1: ...
+/// ```
+///
+/// Piece shapes are stored in 5x5 grids, the board in a
+/// 20x20 grid per color.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid<const W: usize, const H: usize> {
+    rows: [u32; H]
+}
+
+impl<const W: usize, const H: usize> BitGrid<W, H> {
+    /// Fails to compile for grids whose rows do not fit into a `u32`.
+    const VALID_WIDTH: () = assert!(W <= 32, "BitGrid rows have to fit into 32 bits!");
+    /// The bits of a full row.
+    pub(crate) const ROW_MASK: u32 = if W >= 32 { u32::MAX } else { (1 << W) - 1 };
+
+    /// Creates an empty grid.
+    #[allow(clippy::let_unit_value)]
+    pub fn new() -> Self {
+        let _ = Self::VALID_WIDTH;
+        Self { rows: [0; H] }
+    }
+
+    /// Creates a grid containing every cell.
+    pub fn full() -> Self {
+        Self::new().not()
+    }
+
+    /// Creates a grid from its rows. Bits outside the width are dropped.
+    pub fn from_rows(rows: [u32; H]) -> Self {
+        let mut grid = Self::new();
+        for (row, bits) in grid.rows.iter_mut().zip(rows) {
+            *row = bits & Self::ROW_MASK;
+        }
+        grid
+    }
+
+    /// The rows of this grid.
+    pub fn rows(&self) -> &[u32; H] {
+        &self.rows
+    }
+
+    /// Checks whether the given coordinates lie within the grid.
+    pub fn is_in_bounds(coordinates: Vec2) -> bool {
+           coordinates.x >= 0
+        && coordinates.y >= 0
+        && coordinates.x < W as i32
+        && coordinates.y < H as i32
+    }
+
+    /// Checks whether the grid contains the given coordinates.
+    pub fn contains(&self, coordinates: Vec2) -> bool {
+        Self::is_in_bounds(coordinates) && (self.rows[coordinates.y as usize] >> coordinates.x) & 1 == 1
+    }
+
+    /// Inserts the given (in-bounds) coordinates into the grid.
+    pub fn insert(&mut self, coordinates: Vec2) {
+        assert!(Self::is_in_bounds(coordinates), "{} is out of the grid's bounds!", coordinates);
+        self.rows[coordinates.y as usize] |= 1 << coordinates.x;
+    }
+
+    /// Removes the given coordinates from the grid.
+    pub fn remove(&mut self, coordinates: Vec2) {
+        if Self::is_in_bounds(coordinates) {
+            self.rows[coordinates.y as usize] &= !(1 << coordinates.x);
+        }
+    }
+
+    /// Inserts or removes the given coordinates.
+    pub fn set(&mut self, coordinates: Vec2, value: bool) {
+        if value {
+            self.insert(coordinates);
+        } else {
+            self.remove(coordinates);
+        }
+    }
+
+    /// The number of cells in the grid.
+    pub fn count(&self) -> usize {
+        self.rows.iter().map(|r| r.count_ones() as usize).sum()
+    }
+
+    /// Checks whether the grid contains no cells.
+    pub fn is_empty(&self) -> bool {
+        self.rows.iter().all(|&r| r == 0)
+    }
+
+    /// The smallest rectangle containing every cell as its
+    /// minimum and maximum (inclusive) corner, if any.
+    pub fn bounds(&self) -> Option<(Vec2, Vec2)> {
+        let min_y = self.rows.iter().position(|&r| r != 0)?;
+        let max_y = self.rows.iter().rposition(|&r| r != 0)?;
+        let (min_x, max_x) = self.rows.iter()
+            .filter(|&&r| r != 0)
+            .fold((u32::MAX, 0), |(min, max), r| (min.min(r.trailing_zeros()), max.max(31 - r.leading_zeros())));
+        Some((Vec2::new(min_x as i32, min_y as i32), Vec2::new(max_x as i32, max_y as i32)))
+    }
+
+    /// The cells contained in either grid.
+    pub fn union(&self, other: &Self) -> Self {
+        *self | *other
+    }
+
+    /// The cells contained in both grids.
+    pub fn intersection(&self, other: &Self) -> Self {
+        *self & *other
+    }
+
+    /// The cells contained in this grid but not in the other one.
+    pub fn difference(&self, other: &Self) -> Self {
+        *self - *other
+    }
+
+    /// Checks whether both grids share any cell.
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.rows.iter().zip(other.rows.iter()).any(|(a, b)| a & b != 0)
+    }
+
+    /// Moves every cell by the given offset, dropping
+    /// those that end up outside the grid.
+    pub fn shift(&self, dx: i32, dy: i32) -> Self {
+        let mut shifted = Self::new();
+        for (y, &row) in self.rows.iter().enumerate() {
+            let target = y as i32 + dy;
+            if target >= 0 && target < H as i32 {
+                let bits = if dx >= 0 {
+                    row.checked_shl(dx as u32).unwrap_or(0)
+                } else {
+                    row.checked_shr(dx.unsigned_abs()).unwrap_or(0)
+                };
+                shifted.rows[target as usize] = bits & Self::ROW_MASK;
+            }
+        }
+        shifted
+    }
+
+    /// Adds the cells sharing an edge with the grid's cells.
+    pub fn dilate(&self) -> Self {
+        *self | self.shift(1, 0) | self.shift(-1, 0) | self.shift(0, 1) | self.shift(0, -1)
+    }
+
+    /// Adds the cells sharing a corner with the grid's cells.
+    pub fn dilate_diagonal(&self) -> Self {
+        *self | self.shift(1, 1) | self.shift(-1, 1) | self.shift(1, -1) | self.shift(-1, -1)
+    }
+
+    /// Repeatedly adds the cells of the given region sharing an edge
+    /// with the grid's cells, i.e. finds the connected components of the
+    /// region containing the grid's cells.
+    pub fn flood_fill(&self, within: &Self) -> Self {
+        let mut filled = *self & *within;
+        loop {
+            let next = filled.dilate() & *within;
+            if next == filled {
+                return filled;
+            }
+            filled = next;
+        }
+    }
+
+    /// Copies the cells into a grid of another size,
+    /// dropping those that do not fit.
+    pub fn resize<const W2: usize, const H2: usize>(&self) -> BitGrid<W2, H2> {
+        let mut resized = BitGrid::new();
+        for (row, &bits) in resized.rows.iter_mut().zip(self.rows.iter()) {
+            *row = bits & BitGrid::<W2, H2>::ROW_MASK;
+        }
+        resized
+    }
+
+    /// Iterates over the cells, row by row.
+    pub fn iter(&self) -> BitGridIterator<W, H> {
+        BitGridIterator { rows: self.rows, y: 0 }
+    }
+}
+
+impl<const W: usize, const H: usize> Default for BitGrid<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize, const H: usize> BitOr for BitGrid<W, H> {
+    type Output = Self;
+
+    fn bitor(mut self, rhs: Self) -> Self {
+        self |= rhs;
+        self
+    }
+}
+
+impl<const W: usize, const H: usize> BitOrAssign for BitGrid<W, H> {
+    fn bitor_assign(&mut self, rhs: Self) {
+        for (a, b) in self.rows.iter_mut().zip(rhs.rows) {
+            *a |= b;
+        }
+    }
+}
+
+impl<const W: usize, const H: usize> BitAnd for BitGrid<W, H> {
+    type Output = Self;
+
+    fn bitand(mut self, rhs: Self) -> Self {
+        self &= rhs;
+        self
+    }
+}
+
+impl<const W: usize, const H: usize> BitAndAssign for BitGrid<W, H> {
+    fn bitand_assign(&mut self, rhs: Self) {
+        for (a, b) in self.rows.iter_mut().zip(rhs.rows) {
+            *a &= b;
+        }
+    }
+}
+
+impl<const W: usize, const H: usize> BitXor for BitGrid<W, H> {
+    type Output = Self;
+
+    fn bitxor(mut self, rhs: Self) -> Self {
+        for (a, b) in self.rows.iter_mut().zip(rhs.rows) {
+            *a ^= b;
+        }
+        self
+    }
+}
+
+impl<const W: usize, const H: usize> Sub for BitGrid<W, H> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self & !rhs
+    }
+}
+
+impl<const W: usize, const H: usize> Not for BitGrid<W, H> {
+    type Output = Self;
+
+    fn not(mut self) -> Self {
+        for row in self.rows.iter_mut() {
+            *row = !*row & Self::ROW_MASK;
+        }
+        self
+    }
+}
+
+impl<const W: usize, const H: usize> FromIterator<Vec2> for BitGrid<W, H> {
+    fn from_iter<I>(iter: I) -> Self where I: IntoIterator<Item=Vec2> {
+        let mut grid = Self::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<const W: usize, const H: usize> Extend<Vec2> for BitGrid<W, H> {
+    fn extend<I>(&mut self, iter: I) where I: IntoIterator<Item=Vec2> {
+        for coordinates in iter {
+            self.insert(coordinates);
+        }
+    }
+}
+
+impl<const W: usize, const H: usize> fmt::Display for BitGrid<W, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in 0..H as i32 {
+            for x in 0..W as i32 {
+                write!(f, "{}", if self.contains(Vec2::new(x, y)) { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<const W: usize, const H: usize> fmt::Debug for BitGrid<W, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Iterates over the cells of a grid, row by row.
+pub struct BitGridIterator<const W: usize, const H: usize> {
+    rows: [u32; H],
+    y: usize
+}
+
+impl<const W: usize, const H: usize> Iterator for BitGridIterator<W, H> {
+    type Item = Vec2;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.y < H {
+            let row = &mut self.rows[self.y];
+            if *row != 0 {
+                let x = row.trailing_zeros();
+                *row &= *row - 1;
+                return Some(Vec2::new(x as i32, self.y as i32));
+            }
+            self.y += 1;
+        }
+
+        None
+    }
+}
+
+impl<const W: usize, const H: usize> IntoIterator for BitGrid<W, H> {
+    type Item = Vec2;
+    type IntoIter = BitGridIterator<W, H>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::Vec2;
+    use super::BitGrid;
+
+    #[test]
+    fn test_bit_grid() {
+        let grid: BitGrid<5, 5> = [Vec2::new(0, 0), Vec2::new(4, 4), Vec2::new(2, 1)].into_iter().collect();
+        assert_eq!(grid.count(), 3);
+        assert_eq!(grid.iter().collect::<Vec<_>>(), vec![Vec2::new(0, 0), Vec2::new(2, 1), Vec2::new(4, 4)]);
+        assert!(!grid.contains(Vec2::new(-1, 0)));
+        assert_eq!((!grid).count(), 22);
+        assert_eq!(BitGrid::<20, 20>::full().count(), 400);
+
+        // Shifting drops cells leaving the grid
+        let shifted = grid.shift(1, -1);
+        assert_eq!(shifted.iter().collect::<Vec<_>>(), vec![Vec2::new(3, 0)]);
+
+        let single: BitGrid<5, 5> = [Vec2::new(0, 0)].into_iter().collect();
+        assert_eq!(single.dilate().iter().collect::<Vec<_>>(), vec![Vec2::new(0, 0), Vec2::new(1, 0), Vec2::new(0, 1)]);
+        assert_eq!(single.dilate_diagonal() - single, [Vec2::new(1, 1)].into_iter().collect());
+        assert_eq!(grid.intersection(&single), single);
+        assert_eq!(grid.difference(&single).count(), 2);
+        assert_eq!(grid.resize::<20, 20>().iter().collect::<Vec<_>>(), grid.iter().collect::<Vec<_>>());
+        assert_eq!(grid.resize::<3, 3>().count(), 2);
+        assert_eq!(grid.bounds(), Some((Vec2::new(0, 0), Vec2::new(4, 4))));
+        assert_eq!(shifted.bounds(), Some((Vec2::new(3, 0), Vec2::new(3, 0))));
+        assert_eq!(BitGrid::<5, 5>::new().bounds(), None);
+    }
+}
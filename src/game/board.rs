@@ -1,32 +1,126 @@
+use std::iter::FromIterator;
 use crate::util::{SCResult, FromXmlNode, XmlNode};
-use super::{CORNERS, Color, Vec2, Corner, Field, Piece};
+#[cfg(feature = "json")]
+use crate::util::{FromJson, ToJson, JsonValue};
+use crate::rules::BOARD_SIZE;
+use super::{BitGrid, COLOR_COUNT, COLORS, CORNERS, Color, Vec2, Corner, Field, Piece, SYMMETRIES, Symmetry};
 
-pub const BOARD_SIZE: usize = 20;
+/// A set of positions on the board.
+pub type BoardMask = BitGrid<BOARD_SIZE, BOARD_SIZE>;
 
-/// The game board is a 20x20 grid of fields with colors.
+/// The game board of the official rules, a 20x20 grid.
+pub type Board = SizedBoard<BOARD_SIZE>;
+
+/// A game board, i.e. a N x N grid of fields with colors.
+/// Boards of other sizes than `BOARD_SIZE` are used for
+/// variants of the rules and for small test positions.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Board {
-    // TODO: More efficient representation, e.g. using a 2D matrix of colors
-    fields: Vec<Field>
+pub struct SizedBoard<const N: usize> {
+    /// The fields occupied by each color, in the order of `COLORS`.
+    masks: [BitGrid<N, N>; COLOR_COUNT]
 }
 
-impl Board {
+impl<const N: usize> SizedBoard<N> {
     /// Creates an empty board.
     pub fn new() -> Self {
-        Self { fields: Vec::new() }
+        Self { masks: [BitGrid::new(); COLOR_COUNT] }
+    }
+
+    /// Fetches the index of a color's mask.
+    fn mask_index(color: Color) -> Option<usize> {
+        COLORS.iter().position(|&c| c == color)
+    }
+
+    /// Applies the given symmetry to every field.
+    pub fn transformed(&self, symmetry: Symmetry) -> Self {
+        self.occupied_fields()
+            .map(|field| Field { position: symmetry.apply_in(N, field.position), content: field.content })
+            .collect()
+    }
+
+    /// Iterates over the 8 symmetric images of the board.
+    pub fn symmetries(&self) -> impl Iterator<Item=(Symmetry, Self)> + '_ {
+        SYMMETRIES.iter().map(move |&symmetry| (symmetry, self.transformed(symmetry)))
+    }
+
+    /// Finds the symmetry mapping the board to its canonical image,
+    /// i.e. the one with the smallest masks (compared row by row).
+    /// Among equal images, the earliest symmetry in `SYMMETRIES` wins.
+    pub fn canonical_symmetry(&self) -> Symmetry {
+        self.symmetries()
+            .min_by_key(|(_, board)| board.masks.map(|mask| *mask.rows()))
+            .map(|(symmetry, _)| symmetry)
+            .unwrap_or(Symmetry::Identity)
     }
 
     /// Fetches the number of occupied fields.
     pub fn count_obstructed(&self) -> usize {
-        self.fields.iter().filter(|f| f.content != Color::None).count()
+        self.occupied_fields().count()
+    }
+
+    /// Iterates over every position on the board, row by row.
+    pub fn positions() -> impl Iterator<Item=Vec2> {
+        (0..N as i32).flat_map(|y| (0..N as i32).map(move |x| Vec2::new(x, y)))
+    }
+
+    /// Iterates over the occupied fields, color by color.
+    pub fn occupied_fields(&self) -> impl Iterator<Item=Field> + '_ {
+        COLORS.iter().flat_map(move |&color| self.fields_of_color(color).map(move |position| Field { position, content: color }))
+    }
+
+    /// Iterates over the positions occupied by the given color, row by row.
+    pub fn fields_of_color(&self, color: Color) -> impl Iterator<Item=Vec2> + '_ {
+        Self::mask_index(color).into_iter().flat_map(move |i| self.masks[i].iter())
+    }
+
+    /// Iterates over the unoccupied positions, row by row.
+    pub fn empty_fields(&self) -> impl Iterator<Item=Vec2> + '_ {
+        (!self.occupancy_mask()).into_iter()
+    }
+
+    /// The positions occupied by the given color as a bit mask,
+    /// e.g. for custom bitwise evaluation. The bit x of the row y
+    /// (see `BitGrid::rows`) represents the position (x, y).
+    pub fn color_mask(&self, color: Color) -> BitGrid<N, N> {
+        Self::mask_index(color).map(|i| self.masks[i]).unwrap_or_default()
+    }
+
+    /// The positions occupied by any color as a bit mask.
+    pub fn occupancy_mask(&self) -> BitGrid<N, N> {
+        self.masks.iter().fold(BitGrid::new(), |acc, &m| acc | m)
+    }
+
+    /// Finds the edge-connected areas of unoccupied positions, ordered
+    /// by their first position (row by row).
+    pub fn empty_region_masks(&self) -> Vec<BitGrid<N, N>> {
+        let mut remaining = !self.occupancy_mask();
+        let mut regions = Vec::new();
+        while let Some(seed) = remaining.iter().next() {
+            let region = BitGrid::from_iter([seed]).flood_fill(&remaining);
+            remaining = remaining - region;
+            regions.push(region);
+        }
+        regions
+    }
+
+    /// Finds the edge-connected areas of unoccupied positions, each
+    /// listed row by row.
+    pub fn empty_regions(&self) -> Vec<Vec<Vec2>> {
+        self.empty_region_masks().into_iter().map(|region| region.iter().collect()).collect()
+    }
+
+    /// Fetches the fields that differ in the other board,
+    /// with their contents in the other board (which is
+    /// `Color::None` for fields that were cleared).
+    pub fn diff(&self, other: &Self) -> Vec<Field> {
+        let changed = self.masks.iter().zip(other.masks.iter())
+            .fold(BitGrid::<N, N>::new(), |changed, (&mask, &other_mask)| changed | (mask ^ other_mask));
+        changed.into_iter().map(|position| Field { position, content: other.get(position) }).collect()
     }
 
     /// Checks whether the given coordinates are in the board's bounds.
     pub fn is_in_bounds(coordinates: Vec2) -> bool {
-           coordinates.x >= 0
-        && coordinates.y >= 0
-        && coordinates.x < BOARD_SIZE as i32
-        && coordinates.y < BOARD_SIZE as i32
+        BitGrid::<N, N>::is_in_bounds(coordinates)
     }
 
     /// Fetches the board's corners.
@@ -38,9 +132,9 @@ impl Board {
     pub fn corner_position(corner: Corner) -> Vec2 {
         match corner {
             Corner::TopLeft => Vec2::new(0, 0),
-            Corner::BottomLeft => Vec2::new(0, BOARD_SIZE as i32 - 1),
-            Corner::TopRight => Vec2::new(BOARD_SIZE as i32 - 1, 0),
-            Corner::BottomRight => Vec2::new(BOARD_SIZE as i32 - 1, BOARD_SIZE as i32 - 1)
+            Corner::BottomLeft => Vec2::new(0, N as i32 - 1),
+            Corner::TopRight => Vec2::new(N as i32 - 1, 0),
+            Corner::BottomRight => Vec2::new(N as i32 - 1, N as i32 - 1)
         }
     }
 
@@ -55,6 +149,11 @@ impl Board {
         }
     }
 
+    /// The board's corners as a mask.
+    pub fn corner_mask() -> BitGrid<N, N> {
+        Self::corner_positions().collect()
+    }
+
     /// Checks whether a coordinate is on a corner.
     pub fn is_on_corner(position: Vec2) -> bool {
         Self::corner_positions().any(|p| p == position)
@@ -62,16 +161,17 @@ impl Board {
 
     /// Fetches the color at the given position.
     pub fn get(&self, position: Vec2) -> Color {
-        // TODO: This is very inefficient and would be much better handled using a matrix
-        self.fields.iter().find(|f| f.position == position).map(|f| f.content).unwrap_or_default()
+        COLORS.iter().zip(self.masks.iter()).find(|(_, m)| m.contains(position)).map(|(&c, _)| c).unwrap_or_default()
     }
 
-    /// Places the color at the given position.
+    /// Places the color at the given position. Positions
+    /// outside of the board are ignored.
     pub fn set(&mut self, position: Vec2, color: Color) {
-        // TODO: This is very inefficient and would be much better handled using a matrix
-        match self.fields.iter_mut().find(|f| f.position == position) {
-            Some(field) => field.content = color,
-            None => self.fields.push(Field { position, content: color })
+        if !Self::is_in_bounds(position) {
+            return;
+        }
+        for (i, mask) in self.masks.iter_mut().enumerate() {
+            mask.set(position, Self::mask_index(color) == Some(i));
         }
     }
 
@@ -84,34 +184,140 @@ impl Board {
 
     /// Checks whether the given position is obstructed.
     pub fn is_obstructed(&self, position: Vec2) -> bool {
-        self.fields.iter().any(|f| f.position == position && f.content != Color::None)
+        self.masks.iter().any(|m| m.contains(position))
     }
 
     /// Checks whether the position touches another border of same color.
     pub fn borders_on_color(&self, position: Vec2, color: Color) -> bool {
-        [
-            Vec2::new(1, 0),
-            Vec2::new(0, 1),
-            Vec2::new(-1, 0),
-            Vec2::new(0, -1)
-        ].iter().any(|&o| self.get(position + o) == color)
+        position.orthogonal_neighbors().any(|p| self.get(p) == color)
     }
 
     /// Checks whether the position touches another corner of same color.
     pub fn corners_on_color(&self, position: Vec2, color: Color) -> bool {
-        [
-            Vec2::new(1, 1),
-            Vec2::new(1, 1),
-            Vec2::new(-1, 1),
-            Vec2::new(1, -1)
-        ].iter().any(|&o| self.get(position + o) == color)
+        position.diagonal_neighbors().any(|p| self.get(p) == color)
+    }
+}
+
+impl<const N: usize> Default for SizedBoard<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> FromIterator<Field> for SizedBoard<N> {
+    fn from_iter<I>(fields: I) -> Self where I: IntoIterator<Item=Field> {
+        let mut board = Self::new();
+        for field in fields {
+            board.set(field.position, field.content);
+        }
+        board
     }
 }
 
 impl FromXmlNode for Board {
     fn from_node(node: &XmlNode) -> SCResult<Self> {
-        Ok(Self {
-            fields: node.childs_by_name("field").map(Field::from_node).collect::<Result<_, _>>()?
-        })
+        node.parse_children("field")
+    }
+}
+
+impl From<Board> for XmlNode {
+    fn from(board: Board) -> Self {
+        XmlNode::new("board")
+            .childs(board.occupied_fields())
+            .build()
+    }
+}
+
+#[cfg(feature = "json")]
+impl ToJson for Board {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object([("fields", JsonValue::array(self.occupied_fields().map(|f| f.to_json())))])
+    }
+}
+
+#[cfg(feature = "json")]
+impl FromJson for Board {
+    fn from_json(json: &JsonValue) -> SCResult<Self> {
+        Ok(Vec::<Field>::from_json(json.get("fields")?)?.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{BOARD_SIZE, Color, Field, Vec2};
+    use super::Board;
+
+    #[test]
+    fn test_field_iterators() {
+        let mut board = Board::new();
+        board.set(Vec2::new(0, 0), Color::Blue);
+        board.set(Vec2::new(1, 1), Color::Blue);
+        board.set(Vec2::new(19, 0), Color::Red);
+        board.set(Vec2::new(19, 0), Color::None);
+
+        assert_eq!(Board::positions().count(), BOARD_SIZE * BOARD_SIZE);
+        assert_eq!(board.occupied_fields().count(), 2);
+        assert_eq!(board.fields_of_color(Color::Blue).collect::<Vec<_>>(), vec![Vec2::new(0, 0), Vec2::new(1, 1)]);
+        assert_eq!(board.fields_of_color(Color::Red).count(), 0);
+        assert_eq!(board.empty_fields().count(), BOARD_SIZE * BOARD_SIZE - 2);
+        assert_eq!(board.empty_fields().next(), Some(Vec2::new(1, 0)));
+
+        assert_eq!(board.color_mask(Color::Blue).rows()[..2], [0b01, 0b10]);
+        assert!(board.color_mask(Color::Red).is_empty());
+        assert!(board.color_mask(Color::None).is_empty());
+        board.set(Vec2::new(19, 1), Color::Green);
+        assert_eq!(board.occupancy_mask().rows()[1], 0b10 | 1 << 19);
+    }
+
+    #[test]
+    fn test_empty_regions() {
+        let mut board = Board::new();
+        assert_eq!(board.empty_regions().len(), 1);
+
+        // Wall off the top left 2x2 corner and the single cell (19, 19)
+        for position in [Vec2::new(2, 0), Vec2::new(2, 1), Vec2::new(0, 2), Vec2::new(1, 2), Vec2::new(2, 2)] {
+            board.set(position, Color::Blue);
+        }
+        board.set(Vec2::new(18, 19), Color::Red);
+        board.set(Vec2::new(19, 18), Color::Green);
+
+        let regions = board.empty_regions();
+        assert_eq!(regions.len(), 3);
+        assert_eq!(regions[0], vec![Vec2::new(0, 0), Vec2::new(1, 0), Vec2::new(0, 1), Vec2::new(1, 1)]);
+        assert_eq!(regions[1].len(), BOARD_SIZE * BOARD_SIZE - 4 - 5 - 2 - 1);
+        assert_eq!(regions[2], vec![Vec2::new(19, 19)]);
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut board = Board::new();
+        board.set(Vec2::new(0, 0), Color::Blue);
+        board.set(Vec2::new(3, 2), Color::Red);
+
+        let mut other = board.clone();
+        assert!(board.diff(&other).is_empty());
+        other.set(Vec2::new(4, 1), Color::Yellow);
+        other.set(Vec2::new(3, 2), Color::Green);
+        other.set(Vec2::new(0, 0), Color::None);
+        assert_eq!(board.diff(&other), vec![
+            Field { position: Vec2::new(0, 0), content: Color::None },
+            Field { position: Vec2::new(4, 1), content: Color::Yellow },
+            Field { position: Vec2::new(3, 2), content: Color::Green }
+        ]);
+    }
+
+    #[test]
+    fn test_neighbors_on_color() {
+        let mut board = Board::new();
+        board.set(Vec2::new(5, 5), Color::Blue);
+
+        for position in Vec2::new(5, 5).diagonal_neighbors() {
+            assert!(board.corners_on_color(position, Color::Blue), "{} should touch the corner", position);
+            assert!(!board.borders_on_color(position, Color::Blue));
+        }
+        for position in Vec2::new(5, 5).orthogonal_neighbors() {
+            assert!(board.borders_on_color(position, Color::Blue), "{} should touch the border", position);
+            assert!(!board.corners_on_color(position, Color::Blue));
+        }
     }
 }
@@ -0,0 +1,124 @@
+use std::{ops::Deref, sync::OnceLock};
+use crate::util::SCResult;
+use super::{COLOR_COUNT, COLORS, Color, GameState, Move};
+
+/// A game state that lazily caches expensive derived data,
+/// so heuristics querying it repeatedly in the same node do
+/// not recompute it. The state is only mutable through this
+/// wrapper's methods, which invalidate the caches.
+#[derive(Debug, Clone)]
+pub struct CachedState {
+    state: GameState,
+    possible_moves: OnceLock<Vec<Move>>,
+    mobility: [OnceLock<usize>; COLOR_COUNT],
+    points: [OnceLock<i32>; COLOR_COUNT]
+}
+
+impl CachedState {
+    /// Wraps the given state.
+    pub fn new(state: GameState) -> Self {
+        Self { state, possible_moves: OnceLock::new(), mobility: Default::default(), points: Default::default() }
+    }
+
+    /// Fetches the wrapped state.
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    /// Unwraps the state.
+    pub fn into_inner(self) -> GameState {
+        self.state
+    }
+
+    fn color_index(color: Color) -> usize {
+        COLORS.iter().position(|&c| c == color).expect("Cannot cache data of color 'none'!")
+    }
+
+    /// The possible moves of the current color.
+    pub fn possible_moves(&self) -> &[Move] {
+        self.possible_moves.get_or_init(|| self.state.possible_moves().collect())
+    }
+
+    /// The number of placements available to the given color.
+    pub fn mobility(&self, color: Color) -> usize {
+        *self.mobility[Self::color_index(color)].get_or_init(|| self.state.mobility(color))
+    }
+
+    /// Checks whether the given color cannot place any piece.
+    pub fn is_blocked(&self, color: Color) -> bool {
+        self.mobility(color) == 0
+    }
+
+    /// The colors in the game that cannot place any (more) pieces.
+    pub fn blocked_colors(&self) -> Vec<Color> {
+        self.state.valid_colors.iter().copied().filter(|&color| self.is_blocked(color)).collect()
+    }
+
+    /// The points of the given color.
+    pub fn points_of_color(&self, color: Color) -> i32 {
+        *self.points[Self::color_index(color)].get_or_init(|| self.state.points_of_color(color))
+    }
+
+    /// Performs the given move and invalidates the caches.
+    pub fn perform_move(&mut self, game_move: Move) -> SCResult<()> {
+        let result = self.state.perform_move(game_move);
+        self.invalidate();
+        result
+    }
+
+    /// Modifies the wrapped state and invalidates the caches.
+    pub fn modify<R>(&mut self, f: impl FnOnce(&mut GameState) -> R) -> R {
+        let result = f(&mut self.state);
+        self.invalidate();
+        result
+    }
+
+    /// Fetches the state after the given move.
+    pub fn after_move(&self, game_move: Move) -> SCResult<CachedState> {
+        self.state.after_move(game_move).map(Self::new)
+    }
+
+    fn invalidate(&mut self) {
+        self.possible_moves = OnceLock::new();
+        self.mobility = Default::default();
+        self.points = Default::default();
+    }
+}
+
+impl Deref for CachedState {
+    type Target = GameState;
+
+    fn deref(&self) -> &GameState {
+        &self.state
+    }
+}
+
+impl From<GameState> for CachedState {
+    fn from(state: GameState) -> Self {
+        Self::new(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{COLORS, Color, GameState, PIECE_SHAPES_BY_NAME};
+    use super::CachedState;
+
+    #[test]
+    fn test_invalidation() {
+        let mut cached = CachedState::new(GameState::new(PIECE_SHAPES_BY_NAME["PENTO_W"].clone()));
+        for _ in 0..8 {
+            assert_eq!(cached.possible_moves(), cached.state().possible_moves().collect::<Vec<_>>());
+            for &color in &COLORS {
+                assert_eq!(cached.mobility(color), cached.state().mobility(color));
+                assert_eq!(cached.points_of_color(color), cached.state().points_of_color(color));
+            }
+            let game_move = cached.possible_moves()[0].clone();
+            cached.perform_move(game_move).unwrap();
+        }
+
+        cached.mobility(cached.current_color());
+        cached.modify(|state| state.blue_shapes.clear());
+        assert!(cached.is_blocked(Color::Blue));
+    }
+}
@@ -1,12 +1,16 @@
 use std::{fmt, str::FromStr};
 use crate::util::{SCResult, SCError, FromXmlNode, XmlNode};
+#[cfg(feature = "json")]
+use crate::util::{FromJson, ToJson, JsonValue};
 use super::Team;
 
 pub const COLOR_COUNT: usize = 4;
+pub const COLORS: [Color; COLOR_COUNT] = [Color::Blue, Color::Yellow, Color::Red, Color::Green];
 
 /// A color in the game.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Color {
+    #[default]
     None,
     Blue,
     Yellow,
@@ -38,12 +42,6 @@ impl Color {
     }
 }
 
-impl Default for Color {
-    fn default() -> Self {
-        Self::None
-    }
-}
-
 impl FromStr for Color {
     type Err = SCError;
 
@@ -53,7 +51,7 @@ impl FromStr for Color {
             "YELLOW" => Ok(Self::Yellow),
             "RED" => Ok(Self::Red),
             "GREEN" => Ok(Self::Green),
-            _ => Err(format!("Color not parse color {}", raw).into())
+            _ => Err(SCError::parse(format!("Color not parse color {}", raw)))
         }
     }
 }
@@ -72,6 +70,20 @@ impl fmt::Display for Color {
 
 impl FromXmlNode for Color {
     fn from_node(node: &XmlNode) -> SCResult<Self> {
-        node.content().parse()
+        node.parse_content()
+    }
+}
+
+#[cfg(feature = "json")]
+impl ToJson for Color {
+    fn to_json(&self) -> JsonValue {
+        self.to_string().into()
+    }
+}
+
+#[cfg(feature = "json")]
+impl FromJson for Color {
+    fn from_json(json: &JsonValue) -> SCResult<Self> {
+        json.as_str()?.parse()
     }
 }
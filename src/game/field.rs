@@ -1,8 +1,10 @@
 use crate::util::{SCResult, FromXmlNode, XmlNode};
+#[cfg(feature = "json")]
+use crate::util::{FromJson, ToJson, JsonValue};
 use super::{Color, Vec2};
 
 /// A field on the board holding a color.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Field {
     pub position: Vec2,
     pub content: Color
@@ -12,10 +14,37 @@ impl FromXmlNode for Field {
     fn from_node(node: &XmlNode) -> SCResult<Self> {
         Ok(Self {
             position: Vec2::new(
-                node.attribute("x")?.parse()?,
-                node.attribute("y")?.parse()?
+                node.parse_attribute("x")?,
+                node.parse_attribute("y")?
             ),
-            content: node.attribute("content")?.parse()?
+            content: node.parse_attribute("content")?
+        })
+    }
+}
+
+impl From<Field> for XmlNode {
+    fn from(field: Field) -> Self {
+        XmlNode::new("field")
+            .attribute("x", field.position.x)
+            .attribute("y", field.position.y)
+            .attribute("content", field.content)
+            .build()
+    }
+}
+
+#[cfg(feature = "json")]
+impl ToJson for Field {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object([("position", self.position.to_json()), ("content", self.content.to_json())])
+    }
+}
+
+#[cfg(feature = "json")]
+impl FromJson for Field {
+    fn from_json(json: &JsonValue) -> SCResult<Self> {
+        Ok(Self {
+            position: Vec2::from_json(json.get("position")?)?,
+            content: Color::from_json(json.get("content")?)?
         })
     }
 }
@@ -0,0 +1,37 @@
+use std::fmt;
+
+/// The number of rounds considered the opening.
+pub const OPENING_ROUNDS: u32 = 4;
+
+/// The number of final rounds that are always considered the endgame.
+pub const ENDGAME_ROUNDS: u32 = 5;
+
+/// The average number of undeployed pieces per color that can
+/// still move at or below which the game is in the endgame.
+pub const ENDGAME_PIECES: usize = 7;
+
+/// The number of placements at or below which a color that can
+/// still move is considered nearly out of moves.
+pub const ENDGAME_MOBILITY: usize = 10;
+
+/// A coarse phase of the game, e.g. for switching evaluation
+/// weights or the search depth.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum GamePhase {
+    /// The first rounds, in which the colors expand from their corners.
+    Opening,
+    /// The colors compete for space in the center.
+    Midgame,
+    /// Few pieces or placements are left, so the game tree is small.
+    Endgame
+}
+
+impl fmt::Display for GamePhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Opening => write!(f, "OPENING"),
+            Self::Midgame => write!(f, "MIDGAME"),
+            Self::Endgame => write!(f, "ENDGAME")
+        }
+    }
+}
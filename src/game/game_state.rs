@@ -1,11 +1,25 @@
-use std::{collections::{HashMap, HashSet}, iter::once};
-use crate::util::{SCResult, FromXmlNode, XmlNode};
-use super::{BOARD_SIZE, Board, CORNERS, Color, Move, PIECE_SHAPES, PIECE_SHAPES_BY_NAME, Piece, PieceShape, Player, Team, Vec2, COLOR_COUNT};
+use std::{collections::{HashMap, HashSet}, hash::Hasher, iter::once, str::FromStr};
+#[cfg(feature = "json")]
+use std::{fs, path::Path};
+use crate::rules::{BOARD_SIZE, RuleSet};
+use super::{ENDGAME_MOBILITY, ENDGAME_PIECES, ENDGAME_ROUNDS, OPENING_ROUNDS};
+use crate::util::{FnvHasher, SCError, SCResult, RuleViolationKind, FromXmlNode, XmlNode};
+#[cfg(feature = "json")]
+use crate::util::{FromJson, ToJson, JsonValue};
+#[cfg(feature = "json")]
+use super::Board;
+use super::{BitGrid, CORNERS, Color, GamePhase, Move, MoveUndo, PIECE_SHAPES, Piece, PieceShape, PieceShapeKind, Player, Rotation, SizedBoard, Symmetry, Team, ValidationMode, Vec2, Zobrist, COLOR_COUNT, COLORS};
 
-/// A snapshot of the game's state. It holds the
-/// information needed to compute the next move.
+/// A snapshot of the game's state under the official rules.
+pub type GameState = SizedGameState<BOARD_SIZE>;
+
+/// A snapshot of the game's state on a N x N board. It holds
+/// the information needed to compute the next move. Other board
+/// sizes and rule sets than the official ones are meant for
+/// variants and small test positions, the protocol and the
+/// snapshot formats only support `GameState`.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct GameState {
+pub struct SizedGameState<const N: usize> {
     /// The number of already committed moves.
     pub turn: u32,
     /// The number of rounds.
@@ -15,7 +29,7 @@ pub struct GameState {
     /// The second team's player.
     pub second: Player,
     /// The current game board.
-    pub board: Board,
+    pub board: SizedBoard<N>,
     /// The piece that has to be placed in the first round.
     pub start_piece: PieceShape,
     /// The team that begins the game.
@@ -31,21 +45,30 @@ pub struct GameState {
     /// The undeployed red shapes.
     pub red_shapes: HashSet<PieceShape>,
     /// The undeployed green shapes.
-    pub green_shapes: HashSet<PieceShape>
+    pub green_shapes: HashSet<PieceShape>,
+    /// Determines whether `perform_move` validates moves.
+    pub validation_mode: ValidationMode,
+    /// The variant of the rules played by.
+    pub rules: RuleSet,
+    /// The moves performed on this state along with their turn and color,
+    /// in order. The protocol does not transmit them, so states received
+    /// from the server only know the moves from `extend_history_from`.
+    history: Vec<(u32, Color, Move)>,
+    /// The cells adjacent to each color by corner only, in the order of
+    /// `COLORS`. Kept up to date by the moves, see `refresh_anchors`.
+    anchors: [BitGrid<N, N>; COLOR_COUNT]
 }
 
-const SUM_MAX_SQUARES: i32 = 89;
-
-impl GameState {
+impl<const N: usize> SizedGameState<N> {
     /// Creates a brand-new game state with blue as the starting color
     /// and team one as the starting team. Mostly for debugging purposes.
     pub fn new(start_piece: PieceShape) -> Self {
-        GameState {
+        Self {
             turn: 0,
             round: 1,
             first: Player { team: Team::One, display_name: "Alice".to_owned() },
             second: Player { team: Team::Two, display_name: "Bob".to_owned() },
-            board: Board::new(),
+            board: SizedBoard::new(),
             start_piece,
             start_team: Team::One,
             valid_colors: vec![Color::Blue, Color::Yellow, Color::Red, Color::Green],
@@ -53,13 +76,125 @@ impl GameState {
             blue_shapes: PIECE_SHAPES.iter().cloned().collect(),
             yellow_shapes: PIECE_SHAPES.iter().cloned().collect(),
             red_shapes: PIECE_SHAPES.iter().cloned().collect(),
-            green_shapes: PIECE_SHAPES.iter().cloned().collect()
+            green_shapes: PIECE_SHAPES.iter().cloned().collect(),
+            validation_mode: ValidationMode::default(),
+            rules: RuleSet::default(),
+            history: Vec::new(),
+            anchors: [BitGrid::new(); COLOR_COUNT]
+        }
+    }
+
+    /// Fetches the cells the first piece of every color has to
+    /// cover one of, i.e. the start cells of the rules or else
+    /// the corners of the board.
+    pub fn start_cells(&self) -> BitGrid<N, N> {
+        match self.rules.start_cells {
+            Some(cells) => cells.iter().copied().filter(|&c| SizedBoard::<N>::is_in_bounds(c)).collect(),
+            None => SizedBoard::<N>::corner_mask()
+        }
+    }
+
+    /// Fetches the cells a new piece of the given color has to cover
+    /// one of, i.e. the free start cells (usually the corners of the
+    /// board) in the color's first move and the free cells touching
+    /// the color by corner, but not by edge, afterwards.
+    pub fn anchors(&self, color: Color) -> BitGrid<N, N> {
+        if self.is_first_move_of(color) {
+            self.start_cells() - self.board.occupancy_mask()
+        } else {
+            COLORS.iter().position(|&c| c == color).map(|i| self.anchors[i]).unwrap_or_default()
         }
     }
 
-    /// Fetches the current color.
+    /// Counts the anchors of the given color, a
+    /// popular heuristic for its future mobility.
+    pub fn corner_count(&self, color: Color) -> usize {
+        self.anchors(color).count()
+    }
+
+    /// Estimates the empty space the given color can still reach, i.e.
+    /// the free cells not touching the color by edge that are 8-connected
+    /// to one of its anchors through such cells. This is an upper bound,
+    /// as it ignores the remaining pieces and the opponents' future moves.
+    pub fn reachable_area(&self, color: Color) -> usize {
+        if !self.is_color_active(color) {
+            return 0;
+        }
+        let own = self.board.color_mask(color);
+        let available = !(self.board.occupancy_mask() | own.dilate());
+        let mut reached = self.anchors(color) & available;
+        loop {
+            let next = (reached.dilate() | reached.dilate_diagonal()) & available;
+            if next == reached {
+                return reached.count();
+            }
+            reached = next;
+        }
+    }
+
+    /// Recomputes the anchors from the board. The moves keep them
+    /// up to date, thus this is only needed after modifying the
+    /// board directly.
+    pub fn refresh_anchors(&mut self) {
+        let occupancy = self.board.occupancy_mask();
+        for (anchors, &color) in self.anchors.iter_mut().zip(COLORS.iter()) {
+            let own = self.board.color_mask(color);
+            *anchors = own.dilate_diagonal() - own.dilate() - occupancy;
+        }
+    }
+
+    /// Returns this state with its anchors computed from the board.
+    fn with_refreshed_anchors(mut self) -> Self {
+        self.refresh_anchors();
+        self
+    }
+
+    /// Fetches the current color. The colors take turns in the
+    /// rotation Blue → Yellow → Red → Green, in which the turns of
+    /// colors that have dropped out are skipped (as by the server).
+    /// Thus this is the color at the turn's position in the rotation,
+    /// unless that color has dropped out since the last move, in which
+    /// case it is the next color still in the game.
     pub fn current_color(&self) -> Color {
-        self.valid_colors[self.turn as usize % COLOR_COUNT]
+        COLORS[(self.turn + self.skipped_turns()) as usize % COLOR_COUNT]
+    }
+
+    /// The number of turns of colors that have dropped out
+    /// before the current color's turn.
+    fn skipped_turns(&self) -> u32 {
+        let start = self.turn as usize % COLOR_COUNT;
+        (0..COLOR_COUNT).position(|i| self.is_color_active(COLORS[(start + i) % COLOR_COUNT])).unwrap_or(0) as u32
+    }
+
+    /// Checks whether the given color is still in the game.
+    pub fn is_color_active(&self, color: Color) -> bool {
+        self.valid_colors.contains(&color)
+    }
+
+    /// The colors still in the game in the order they
+    /// will move, beginning with the current color.
+    pub fn active_colors(&self) -> impl Iterator<Item=Color> + '_ {
+        let current = self.turn as usize % COLOR_COUNT;
+        (0..COLOR_COUNT).map(move |i| COLORS[(current + i) % COLOR_COUNT]).filter(move |&c| self.is_color_active(c))
+    }
+
+    /// The first color still in the game that follows the given
+    /// one in the rotation. This is the color itself if it is the
+    /// only one left and `None` if the game has ended.
+    pub fn next_color_after(&self, color: Color) -> Option<Color> {
+        let index = COLORS.iter().position(|&c| c == color)?;
+        (1..=COLOR_COUNT).map(|i| COLORS[(index + i) % COLOR_COUNT]).find(|&c| self.is_color_active(c))
+    }
+
+    /// The number of moves by other colors until it is the given
+    /// color's turn (0 if it is the current color). Colors that have
+    /// dropped out do not move, since their turns are skipped. Yields
+    /// `None` if the given color has dropped out itself.
+    pub fn turns_until(&self, color: Color) -> Option<u32> {
+        if !self.is_color_active(color) {
+            return None;
+        }
+        Some(self.active_colors().take_while(|&c| c != color).count() as u32)
     }
 
     /// Fetches the current team.
@@ -103,45 +238,222 @@ impl GameState {
 
     /// Computes the points from the given, undeployed piece shapes.
     pub fn get_points_from_undeployed(undeployed: HashSet<PieceShape>, mono_last: bool) -> i32 {
-        // If all pieces were placed
-        if undeployed.is_empty() {
-            // Return sum of all squares plus 15 bonus points.
-            // If the Monomino was the last placed piece, add another 5 points
-            SUM_MAX_SQUARES + 15 + if mono_last { 5 } else { 0 }
-        } else {
-            // One point per piece placed
-            let placed_points: i32 = undeployed.iter().map(|p| p.coordinates().count() as i32).sum();
-            SUM_MAX_SQUARES - placed_points
-        }
+        RuleSet::OFFICIAL.points_from_undeployed(&undeployed, mono_last)
+    }
+
+    /// Computes the current points of the given color.
+    pub fn points_of_color(&self, color: Color) -> i32 {
+        let mono_last = self.last_move_mono.get(&color).cloned().unwrap_or(false);
+        self.rules.points_from_undeployed(self.undeployed_shapes_of_color(color), mono_last)
+    }
+
+    /// Computes the current points of the given team.
+    pub fn points_of_team(&self, team: Team) -> i32 {
+        team.colors().iter().map(|&c| self.points_of_color(c)).sum()
     }
 
     /// Whether the game state is in the first round.
     pub fn is_first_move(&self) -> bool {
-        self.undeployed_shapes_of_color(self.current_color()).count() == PIECE_SHAPES.len()
+        self.is_first_move_of(self.current_color())
+    }
+
+    /// Whether the given color has not placed any piece yet.
+    pub fn is_first_move_of(&self, color: Color) -> bool {
+        self.undeployed_shapes_of_color(color).count() == PIECE_SHAPES.len()
     }
 
-    /// Performs the given move.
+    /// Performs the given move, validating it first if
+    /// the state's validation mode says so. The state is
+    /// left untouched if the move is found to be invalid.
     pub fn perform_move(&mut self, game_move: Move) -> SCResult<()> {
-        #[cfg(debug_assertions)]
-        self.validate_move_color(&game_move)?;
+        if self.validation_mode.is_enabled() {
+            self.perform_move_checked(game_move)
+        } else {
+            self.perform_move_unchecked(game_move)
+        }
+    }
+
+    /// Validates and performs the given move, regardless
+    /// of the validation mode.
+    pub fn perform_move_checked(&mut self, game_move: Move) -> SCResult<()> {
+        self.validate_move(&game_move)?;
+        self.perform_move_unchecked(game_move)
+    }
 
+    /// Performs the given move WITHOUT validating it, regardless
+    /// of the validation mode. Performing an invalid move leaves
+    /// the state inconsistent.
+    pub fn perform_move_unchecked(&mut self, game_move: Move) -> SCResult<()> {
+        // Catch up on the turns of colors that dropped out since the last move
+        self.advance_turns(self.skipped_turns());
+        let entry = (self.turn, game_move.color(), game_move.clone());
         match game_move {
-            Move::Set { piece } => self.perform_set_move(piece),
-            Move::Skip { .. } => self.perform_skip_move()
+            Move::Set { piece } => self.perform_set_move(piece)?,
+            Move::Skip { .. } => self.perform_skip_move()?
+        }
+        self.history.push(entry);
+        Ok(())
+    }
+
+    /// Performs the given move like `perform_move` and returns
+    /// the information needed to take it back using `undo_move`.
+    pub fn perform_move_undoable(&mut self, game_move: Move) -> SCResult<MoveUndo<N>> {
+        let last_move_mono = self.last_move_mono.get(&game_move.color()).copied();
+        let undo = MoveUndo { game_move: game_move.clone(), turn: self.turn, round: self.round, anchors: self.anchors, last_move_mono };
+        self.perform_move(game_move)?;
+        Ok(undo)
+    }
+
+    /// Takes back the last move performed using `perform_move_undoable`.
+    /// Moves have to be taken back in the reverse order.
+    pub fn undo_move(&mut self, undo: MoveUndo<N>) {
+        if let Move::Set { piece } = &undo.game_move {
+            for coordinates in piece.coordinates() {
+                self.board.set(coordinates, Color::None);
+            }
+            self.undeployed_shapes_of_color_mut(piece.color).insert(piece.kind.clone());
+            match undo.last_move_mono {
+                Some(mono) => self.last_move_mono.insert(piece.color, mono),
+                None => self.last_move_mono.remove(&piece.color)
+            };
+        }
+        self.turn = undo.turn;
+        self.round = undo.round;
+        self.anchors = undo.anchors;
+        self.history.pop();
+    }
+
+    /// The moves performed on this state along with their turn
+    /// and color, in order. See `extend_history_from` for states
+    /// received from the server.
+    pub fn history(&self) -> &[(u32, Color, Move)] {
+        &self.history
+    }
+
+    /// The last move in the history.
+    pub fn last_move(&self) -> Option<&Move> {
+        self.history.last().map(|(_, _, game_move)| game_move)
+    }
+
+    /// The last move of the given color in the history,
+    /// e.g. to detect whether an opponent has skipped.
+    pub fn last_move_of(&self, color: Color) -> Option<&Move> {
+        self.history.iter().rev().find(|&&(_, c, _)| c == color).map(|(_, _, game_move)| game_move)
+    }
+
+    /// Continues the history of the given previous state, e.g. the
+    /// preceding memento, with the move inferred between the two.
+    /// Returns whether the move could be inferred, otherwise the
+    /// history is left untouched.
+    pub fn extend_history_from(&mut self, previous: &Self) -> bool {
+        match previous.infer_move(self) {
+            Some(game_move) => {
+                let mut history = previous.history.clone();
+                history.push((previous.turn, game_move.color(), game_move));
+                self.history = history;
+                true
+            },
+            None => false
         }
     }
 
     /// Fetches the state after the given move.
-    pub fn after_move(&self, game_move: Move) -> SCResult<GameState> {
+    pub fn after_move(&self, game_move: Move) -> SCResult<Self> {
         let mut s = self.clone();
         s.perform_move(game_move)?;
         Ok(s)
     }
 
+    /// Infers the move leading from this state to the given one,
+    /// e.g. from consecutive mementos. Returns `None` if the next
+    /// state does not follow from a single move of the current color
+    /// (followed only by the skipped turns of colors that dropped out).
+    /// Symmetric pieces are inferred with their canonical rotation,
+    /// which may differ from the (equivalent) one actually sent.
+    pub fn infer_move(&self, next: &Self) -> Option<Move> {
+        // The turns of colors that have dropped out may be skipped in between
+        let turn = self.turn + self.skipped_turns();
+        if next.turn <= turn || next.turn > turn + COLOR_COUNT as u32 {
+            return None;
+        }
+        // Only the turns of colors that have dropped out may be passed over
+        if (turn + 1..next.turn).any(|t| next.is_color_active(COLORS[t as usize % COLOR_COUNT])) {
+            return None;
+        }
+
+        let color = self.current_color();
+        let changed = self.board.diff(&next.board);
+        if changed.is_empty() {
+            return Some(Move::Skip { color });
+        }
+        if changed.iter().any(|field| field.content != color) {
+            return None;
+        }
+
+        let piece = Piece::from_cells(color, changed.iter().map(|field| field.position))?;
+        let game_move = Move::Set { piece };
+        self.validate_move(&game_move).ok()?;
+        Some(game_move)
+    }
+
+    /// Checks whether the given move is valid in this state.
+    pub fn validate_move(&self, game_move: &Move) -> SCResult<()> {
+        self.validate_move_color(game_move)?;
+        match game_move {
+            Move::Set { piece } => self.validate_set_move(piece),
+            Move::Skip { .. } => self.validate_skip()
+        }
+    }
+
+    /// Checks many moves at once, e.g. to mask the illegal ones among
+    /// candidates from an external move generator. Equivalent to calling
+    /// `validate_move` on each, but set moves of the current color are
+    /// checked against masks computed only once. The errors are the same.
+    pub fn validate_moves<'a>(&self, moves: impl Iterator<Item=&'a Move>) -> Vec<SCResult<()>> {
+        let color = self.current_color();
+        let placeable = self.placeable_shapes(color).iter().fold(0u32, |kinds, shape| kinds | 1 << shape.kind() as usize);
+        let forbidden = self.board.occupancy_mask() | self.board.color_mask(color).dilate();
+        let anchors = self.anchors(color);
+        // Otherwise every move is rejected by `validate_move`
+        let in_game = !self.valid_colors.is_empty() && self.is_color_active(color);
+
+        moves.map(|game_move| match game_move {
+            Move::Set { piece } if in_game && piece.color == color && placeable & 1 << piece.kind.kind() as usize != 0 => {
+                let shape = piece.shape();
+                let bb = shape.bounding_box();
+                let Vec2 { x, y } = piece.position;
+                let max = N as i32 - 1;
+                let legal = x >= 0 && y >= 0 && x + bb.x <= max && y + bb.y <= max && {
+                    let (mut blocked, mut touching) = (0, 0);
+                    for (dy, &row) in shape.mask().rows().iter().enumerate().take(bb.y as usize + 1) {
+                        let target = y as usize + dy;
+                        blocked |= forbidden.rows()[target] & row << x;
+                        touching |= anchors.rows()[target] & row << x;
+                    }
+                    touching != 0 && blocked == 0
+                };
+                if legal {
+                    Ok(())
+                } else {
+                    // Take the slow path to report the exact violation
+                    self.validate_move(game_move)
+                }
+            },
+            _ => self.validate_move(game_move)
+        }).collect()
+    }
+
+    /// Checks whether the current color may skip.
+    pub fn can_skip(&self) -> bool {
+        self.validate_skip().is_ok()
+    }
+
     /// Checks whether the given move has the right color.
     fn validate_move_color(&self, game_move: &Move) -> SCResult<()> {
-        if game_move.color() != self.current_color() {
-            Err(format!("Move color {} does not match game state color {}!", game_move.color(), self.current_color()).into())
+        if self.valid_colors.is_empty() {
+            Err(SCError::rule_violation(RuleViolationKind::GameOver, "Game has already ended, cannot move!"))
+        } else if game_move.color() != self.current_color() {
+            Err(SCError::rule_violation(RuleViolationKind::WrongColor, format!("Move color {} does not match game state color {}!", game_move.color(), self.current_color())))
         } else {
             Ok(())
         }
@@ -149,77 +461,103 @@ impl GameState {
 
     /// Checks whether the given shape is valid.
     fn validate_shape(&self, shape: &PieceShape, color: Color) -> SCResult<()> {
-        if self.is_first_move() {
+        if self.is_first_move_of(color) {
             if shape != &self.start_piece {
-                return Err(format!("{} is not the (requested) first shape", shape).into())
+                return Err(SCError::rule_violation(RuleViolationKind::NotStartPiece, format!("{} is not the (requested) first shape", shape)))
             }
         } else if !self.undeployed_shapes_of_color(color).any(|p| p == shape) {
-            return Err(format!("Piece {} has already been placed before!", shape).into())
+            return Err(SCError::rule_violation(RuleViolationKind::AlreadyPlaced, format!("Piece {} has already been placed before!", shape)))
         }
 
         Ok(())
     }
 
-    /// Checks whether the given set move is valid.
-    fn validate_set_move(&self, piece: &Piece) -> SCResult<()> {
+    /// Checks whether the given piece could be placed, regardless
+    /// of whether it is the piece color's turn.
+    pub fn validate_set_move(&self, piece: &Piece) -> SCResult<()> {
+        if !self.is_color_active(piece.color) {
+            return Err(SCError::rule_violation(RuleViolationKind::ColorNotInGame, format!("{} is no longer in the game, cannot place a piece!", piece.color)));
+        }
         self.validate_shape(&piece.kind, piece.color)?;
 
         for coordinates in piece.coordinates() {
-            if !Board::is_in_bounds(coordinates) {
-                return Err(format!("Target position of the set move {} is not in the board's bounds!", coordinates).into());
+            if !SizedBoard::<N>::is_in_bounds(coordinates) {
+                return Err(SCError::rule_violation(RuleViolationKind::OutOfBounds, format!("Target position of the set move {} is not in the board's bounds!", coordinates)));
             }
 
             if self.board.is_obstructed(coordinates) {
-                return Err(format!("Target position of the set move {} is obstructed!", coordinates).into());
+                return Err(SCError::rule_violation(RuleViolationKind::Obstructed, format!("Target position of the set move {} is obstructed!", coordinates)));
             }
 
             if self.board.borders_on_color(coordinates, piece.color) {
-                return Err(format!("Target position of the set move {} already borders on {}!", coordinates, piece.color).into());
+                return Err(SCError::rule_violation(RuleViolationKind::BordersOwnColor, format!("Target position of the set move {} already borders on {}!", coordinates, piece.color)));
             }
         }
 
-        if self.is_first_move() {
+        if self.is_first_move_of(piece.color) {
             // Check whether it is placed correctly in a corner
-            if !piece.coordinates().any(|p| Board::is_on_corner(p)) {
-                return Err("The piece from the set move is not located in a corner!".into());
+            let start_cells = self.start_cells();
+            if !piece.coordinates().any(|p| start_cells.contains(p)) {
+                return Err(SCError::rule_violation(RuleViolationKind::NotInCorner, "The piece from the set move is not located in a corner!"));
             }
         } else {
             // Check whether the piece is connected to at least one tile of the same color by corner
             if !piece.coordinates().any(|p| self.board.corners_on_color(p, piece.color)) {
-                return Err(format!("The piece {:?} shares no corner with another piece of same color!", piece).into());
+                return Err(SCError::rule_violation(RuleViolationKind::NoCornerContact, format!("The piece {:?} shares no corner with another piece of same color!", piece)));
             }
         }
 
         Ok(())
     }
 
+    /// Advances the given number of turns and then skips the turns
+    /// of colors that have dropped out, so the current color is still
+    /// in the game. A new round begins whenever every color has had
+    /// its turn.
     pub fn try_advance(&mut self, turns: u32) -> SCResult<()> {
         if self.valid_colors.is_empty() {
-            return Err("Game has already ended, cannot advance!".into());
+            return Err(SCError::rule_violation(RuleViolationKind::GameOver, "Game has already ended, cannot advance!"));
         }
 
-        // TODO: This doesn't seem correct, but matches the implementation of https://github.com/software-challenge/backend/blob/97d185660754ffba4bd4444f3f39ae350f1d053e/plugin/src/shared/sc/plugin2021/GameState.kt#L114-L123
-        // Perhaps we should divide AFTER the turns have been added, then simply assign instead of add-assign the round?
-        self.round += turns / self.valid_colors.len() as u32;
-        self.turn += turns;
-
+        self.advance_turns(turns);
+        self.advance_turns(self.skipped_turns());
         Ok(())
     }
 
+    /// Advances the given number of turns, regardless of the colors.
+    fn advance_turns(&mut self, turns: u32) {
+        // The backend this was originally ported from added `turns / valid_colors.len()`
+        // to the round (https://github.com/software-challenge/backend/blob/97d185660754ffba4bd4444f3f39ae350f1d053e/plugin/src/shared/sc/plugin2021/GameState.kt#L114-L123),
+        // which never advances the round when moves are performed one at a time. Instead,
+        // the round is counted from the turns as `1 + turn / 4` (with turns counting the
+        // dropped out colors, too), following the rule that a round consists of one turn
+        // per color. This has not been validated against a game exported from the backend.
+        let colors = COLOR_COUNT as u32;
+        self.round += (self.turn % colors + turns) / colors;
+        self.turn += turns;
+    }
+
     /// Performs the given set move.
     fn perform_set_move(&mut self, piece: Piece) -> SCResult<()> {
-        #[cfg(debug_assertions)]
-        self.validate_set_move(&piece)?;
-
         self.board.place(&piece);
 
+        let placed: BitGrid<N, N> = piece.coordinates().collect();
+        let occupancy = self.board.occupancy_mask();
+        for (anchors, &color) in self.anchors.iter_mut().zip(COLORS.iter()) {
+            if color == piece.color {
+                *anchors = (*anchors | placed.dilate_diagonal()) - self.board.color_mask(color).dilate() - occupancy;
+            } else {
+                *anchors = *anchors - placed;
+            }
+        }
+
         let undeployed = self.undeployed_shapes_of_color_mut(piece.color);
-        undeployed.remove(&piece.shape());
+        undeployed.remove(&piece.kind);
         // TODO: Track deployed shapes
         
         // If this was the last piece for this color, remove it from the turn queue
         if undeployed.is_empty() {
-            self.last_move_mono.insert(piece.color, piece.kind == PIECE_SHAPES_BY_NAME["MONO"]);
+            self.last_move_mono.insert(piece.color, piece.kind.kind() == PieceShapeKind::Mono);
         }
 
         self.try_advance(1)?;
@@ -228,108 +566,444 @@ impl GameState {
 
     /// Performs the given skip move
     fn perform_skip_move(&mut self) -> SCResult<()> {
-        if self.is_first_move() {
-            return Err("Cannot skip the first round!".into());
-        }
-
         self.try_advance(1)?;
         Ok(())
     }
 
+    /// Checks whether the current color may skip, i.e. whether
+    /// it is still in the game and has already placed a piece.
     fn validate_skip(&self) -> SCResult<()> {
-        self.clone().try_advance(1)
+        self.validate_skip_of(self.current_color())
+    }
+
+    /// Checks whether the given color could skip, regardless
+    /// of whether it is the color's turn.
+    fn validate_skip_of(&self, color: Color) -> SCResult<()> {
+        if self.valid_colors.is_empty() {
+            return Err(SCError::rule_violation(RuleViolationKind::GameOver, "Game has already ended, cannot skip!"));
+        }
+        if !self.is_color_active(color) {
+            return Err(SCError::rule_violation(RuleViolationKind::ColorNotInGame, format!("{} is no longer in the game, cannot skip!", color)));
+        }
+        if self.is_first_move_of(color) {
+            return Err(SCError::rule_violation(RuleViolationKind::SkipInFirstRound, "Cannot skip the first round!"));
+        }
+
+        Ok(())
     }
 
     /// Fetches the possible moves
     pub fn possible_moves(&self) -> impl Iterator<Item=Move> {
-        if self.is_first_move() {
-            self.possible_first_moves()
+        self.possible_moves_for(self.current_color())
+    }
+
+    /// Fetches the moves the given color could make if it
+    /// were its turn, e.g. for computing opponent mobility.
+    /// Colors that have dropped out have no moves.
+    pub fn possible_moves_for(&self, color: Color) -> impl Iterator<Item=Move> {
+        let moves = if !self.is_color_active(color) {
+            Vec::new()
+        } else if self.is_first_move_of(color) {
+            self.possible_first_moves(color)
                 .collect::<Vec<_>>()
-                .into_iter()
         } else {
-            self.possible_usual_set_moves()
-                .chain(once(Move::Skip { color: self.current_color() }).filter(|_| self.validate_skip().is_ok()))
+            self.possible_usual_set_moves(color)
+                .chain(once(Move::Skip { color }).filter(|_| self.validate_skip_of(color).is_ok()))
                 .collect::<Vec<_>>()
-                .into_iter()
-        }
+        };
+        moves.into_iter()
     }
 
-    /// Fetches the possible non-start moves
-    fn possible_usual_set_moves(&self) -> impl Iterator<Item=Move> {
-        let color = self.current_color();
-        self.undeployed_shapes_of_color(color)
-            .flat_map(|kind| {
-                let bb = kind.bounding_box();
-                let placable = Vec2::both(BOARD_SIZE as i32 - 1) - bb;
-                kind.transformations()
-                    .flat_map(|(rotation, is_flipped)| placable
-                        .into_iter()
-                        .map(move |position| Piece {
-                            kind: kind.clone(),
-                            rotation,
-                            is_flipped,
-                            color,
-                            position
-                        })
-                    )
-                    .filter(|piece| self.validate_set_move(piece).is_ok())
-                    .map(|piece| Move::Set { piece })
-                    .collect::<Vec<_>>()
-            })
+    /// Fetches the moves of both of the given team's colors,
+    /// as if it were their turn.
+    pub fn possible_moves_for_team(&self, team: Team) -> impl Iterator<Item=Move> + '_ {
+        team.colors().iter().flat_map(move |&color| self.possible_moves_for(color))
+    }
+
+    /// Fetches the possible non-start moves, ordered by
+    /// shape to keep the order independent of the set's hashing.
+    fn possible_usual_set_moves(&self, color: Color) -> impl Iterator<Item=Move> {
+        let mut shapes: Vec<_> = self.undeployed_shapes_of_color(color).collect();
+        shapes.sort_by_key(|shape| shape.kind());
+        shapes.into_iter()
+            .flat_map(|kind| self.possible_placements_for(color, kind))
+            .map(|piece| Move::Set { piece })
             .collect::<Vec<_>>()
             .into_iter()
     }
 
     /// Fetches the possible start moves
-    fn possible_first_moves(&self) -> impl Iterator<Item=Move> {
-        let kind = self.start_piece.clone();
-        let color = self.current_color();
-        kind
-            .transformations()
-            .flat_map(|(rotation, is_flipped)| {
-                let k = kind.clone();
-                CORNERS
-                    .iter()
-                    .map(move |&corner| Piece {
-                        kind: k.clone(),
-                        rotation,
-                        is_flipped,
-                        color,
-                        position: Board::align(k.transform(rotation, is_flipped).bounding_box(), corner)
-                    })
-                    .filter(|piece| self.validate_set_move(piece).is_ok())
-                    .map(|piece| Move::Set { piece })
-            })
+    fn possible_first_moves(&self, color: Color) -> impl Iterator<Item=Move> {
+        self.possible_placements_for(color, &self.start_piece)
+            .map(|piece| Move::Set { piece })
             .collect::<Vec<_>>()
             .into_iter()
     }
+
+    /// Applies the given symmetry to the board and the history.
+    /// Since every other part of the state is unaffected by the
+    /// board's orientation, the resulting state is equivalent.
+    pub fn transformed(&self, symmetry: Symmetry) -> Self {
+        Self {
+            board: self.board.transformed(symmetry),
+            history: self.history.iter().map(|(turn, color, game_move)| (*turn, *color, symmetry.apply_to_move_in(N, game_move))).collect(),
+            ..self.clone()
+        }.with_refreshed_anchors()
+    }
+
+    /// Finds the canonical representative among the symmetric images
+    /// of this state along with the symmetry mapping this state to it.
+    /// Moves can be mapped back using the symmetry's inverse.
+    pub fn canonical_form(&self) -> (Self, Symmetry) {
+        let symmetry = self.board.canonical_symmetry();
+        (self.transformed(symmetry), symmetry)
+    }
+
+    /// Computes a stable hash identifying the position, i.e. the
+    /// start piece, the turn and the board.
+    pub fn position_hash(&self) -> u64 {
+        let mut hasher = FnvHasher::new();
+        hasher.write(self.start_piece.name().as_bytes());
+        hasher.write_u32(self.turn);
+        for &color in &COLORS {
+            for &row in self.board.color_mask(color).rows() {
+                hasher.write_u32(row);
+            }
+        }
+        hasher.finish()
+    }
+
+    /// Computes the Zobrist hash of the board and the turn,
+    /// which can be updated incrementally using `Zobrist::after_move`.
+    pub fn zobrist_hash(&self) -> u64 {
+        Zobrist::hash(self)
+    }
+
+    /// Computes a hash that is equal for symmetric positions.
+    pub fn canonical_hash(&self) -> u64 {
+        self.canonical_form().0.position_hash()
+    }
+
+    /// Fetches the valid placements of the given shape for the current color.
+    pub fn possible_placements<'a>(&'a self, kind: &'a PieceShape) -> impl Iterator<Item=Piece> + 'a {
+        self.possible_placements_for(self.current_color(), kind)
+    }
+
+    /// Fetches the valid placements of the given shape for the
+    /// given color, regardless of whether it is the color's turn.
+    pub fn possible_placements_for<'a>(&'a self, color: Color, kind: &'a PieceShape) -> impl Iterator<Item=Piece> + 'a {
+        self.placement_positions(color, kind)
+            .map(move |(rotation, is_flipped, position)| Piece {
+                kind: kind.clone(),
+                rotation,
+                is_flipped,
+                color,
+                position
+            })
+    }
+
+    /// Counts the valid placements of the given color, i.e. its
+    /// set moves, without generating the moves themselves.
+    pub fn mobility(&self, color: Color) -> usize {
+        self.placeable_shapes(color).iter()
+            .flat_map(|kind| self.placement_rows(color, kind))
+            .map(|(_, _, _, columns)| columns.count_ones() as usize)
+            .sum()
+    }
+
+    /// Checks whether the given color cannot place any (more) pieces.
+    pub fn is_blocked(&self, color: Color) -> bool {
+        !self.placeable_shapes(color).iter().any(|kind| self.placement_rows(color, kind).next().is_some())
+    }
+
+    /// Fetches the colors in the game that cannot place any (more) pieces.
+    pub fn blocked_colors(&self) -> Vec<Color> {
+        self.valid_colors.iter().copied().filter(|&color| self.is_blocked(color)).collect()
+    }
+
+    /// Whether the game is over, i.e. the round limit has been
+    /// exceeded or no color in the game can place another piece.
+    pub fn is_game_over(&self) -> bool {
+        self.rules.is_past_round_limit(self.round) || self.valid_colors.iter().all(|&color| self.is_blocked(color))
+    }
+
+    /// Fetches the colors that can only skip for the rest of the game,
+    /// i.e. those that dropped out or cannot place any (more) pieces.
+    /// Since placing pieces only takes away space, a color that is
+    /// out of moves stays so.
+    pub fn colors_out_of_moves(&self) -> Vec<Color> {
+        COLORS.iter().copied().filter(|&color| !self.is_color_active(color) || self.is_blocked(color)).collect()
+    }
+
+    /// Classifies the position into a phase of the game. The endgame
+    /// begins with the final rounds, once the colors that can still
+    /// move have few pieces left on average, once half of the colors
+    /// are out of moves or once one of the others is nearly out of
+    /// moves (not counting first moves). The opening spans the
+    /// first rounds before that.
+    pub fn phase(&self) -> GamePhase {
+        let out_of_moves = self.colors_out_of_moves();
+        let moving: Vec<_> = COLORS.iter().copied().filter(|c| !out_of_moves.contains(c)).collect();
+        let pieces = moving.iter().map(|&c| self.undeployed_shapes_of_color(c).count()).sum::<usize>();
+
+        if moving.len() * 2 <= COLOR_COUNT
+            || self.round + ENDGAME_ROUNDS > self.rules.max_round
+            || pieces <= ENDGAME_PIECES * moving.len()
+            || moving.iter().any(|&c| !self.is_first_move_of(c) && self.mobility(c) <= ENDGAME_MOBILITY) {
+            GamePhase::Endgame
+        } else if self.round <= OPENING_ROUNDS {
+            GamePhase::Opening
+        } else {
+            GamePhase::Midgame
+        }
+    }
+
+    /// Fetches the shapes the given color may place next.
+    fn placeable_shapes(&self, color: Color) -> Vec<&PieceShape> {
+        if self.is_first_move_of(color) {
+            vec![&self.start_piece]
+        } else {
+            self.undeployed_shapes_of_color(color).collect()
+        }
+    }
+
+    /// Enumerates the transformations and positions at which the given
+    /// shape can be placed. Equivalent to validate_set_move, but using masks.
+    fn placement_positions<'a>(&'a self, color: Color, kind: &'a PieceShape) -> impl Iterator<Item=(Rotation, bool, Vec2)> + 'a {
+        self.placement_rows(color, kind).flat_map(|(rotation, is_flipped, y, columns)| {
+            BitGrid::<N, 1>::from_rows([columns]).into_iter().map(move |p| (rotation, is_flipped, Vec2::new(p.x, y)))
+        })
+    }
+
+    /// Enumerates the valid placements of the given shape row by row,
+    /// as the transformation, the y-coordinate and a bitset of the
+    /// valid x-coordinates. Only non-empty rows are yielded.
+    fn placement_rows<'a>(&'a self, color: Color, kind: &'a PieceShape) -> impl Iterator<Item=(Rotation, bool, i32, u32)> + 'a {
+        let is_first_move = self.is_first_move_of(color);
+        let is_valid_shape = self.validate_shape(kind, color).is_ok();
+
+        let forbidden = self.board.occupancy_mask() | self.board.color_mask(color).dilate();
+        // Every placement has to cover at least one of these cells
+        let anchors = self.anchors(color);
+        let bounds = anchors.bounds().filter(|_| is_valid_shape);
+
+        bounds.into_iter().flat_map(move |(min, max)| kind.unique_transformations().flat_map(move |(rotation, is_flipped)| {
+            let shape = kind.transform(rotation, is_flipped);
+            let bb = shape.bounding_box();
+            let cells: Vec<_> = shape.coordinates().collect();
+            let rows: Vec<_> = if is_first_move && self.rules.start_cells.is_none() {
+                // Preserve the order of the corners
+                let mask: BitGrid<N, N> = shape.mask().resize();
+                CORNERS.iter()
+                    .map(|&corner| SizedBoard::<N>::align(bb, corner))
+                    .filter(|position| {
+                        let placed = mask.shift(position.x, position.y);
+                        placed.intersects(&anchors) && !placed.intersects(&forbidden)
+                    })
+                    .map(|position| (position.y, 1 << position.x))
+                    .collect()
+            } else {
+                // Check every x-coordinate in a row at once: a position is
+                // blocked if any cell would be forbidden, it touches an
+                // anchor if any cell would be an anchor
+                let columns = BitGrid::<N, N>::ROW_MASK >> bb.x;
+                ((min.y - bb.y).max(0)..=max.y.min(N as i32 - 1 - bb.y))
+                    .map(|y| {
+                        let (mut blocked, mut touching) = (0, 0);
+                        for cell in &cells {
+                            let row = (y + cell.y) as usize;
+                            blocked |= forbidden.rows()[row] >> cell.x;
+                            touching |= anchors.rows()[row] >> cell.x;
+                        }
+                        (y, touching & !blocked & columns)
+                    })
+                    .filter(|&(_, bits)| bits != 0)
+                    .collect()
+            };
+            rows.into_iter().map(move |(y, columns)| (rotation, is_flipped, y, columns))
+        }))
+    }
+}
+
+#[cfg(feature = "json")]
+impl GameState {
+    /// Loads a snapshot from the given file, either in JSON (as written
+    /// by `save`) or in XML (e.g. a memento state from a transcript).
+    pub fn load(path: impl AsRef<Path>) -> SCResult<Self> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    /// Saves a snapshot to the given file as JSON, which
+    /// (unlike the XML format) preserves the entire state.
+    pub fn save(&self, path: impl AsRef<Path>) -> SCResult<()> {
+        fs::write(path, self.to_json().pretty(2))?;
+        Ok(())
+    }
+
+    fn parse_json(s: &str) -> SCResult<Self> {
+        Self::from_json(&s.parse()?)
+    }
+
+}
+
+#[cfg(not(feature = "json"))]
+impl GameState {
+    fn parse_json(_s: &str) -> SCResult<Self> {
+        Err(SCError::parse("Parsing JSON snapshots requires the json feature"))
+    }
+}
+
+/// Parses a snapshot in JSON or XML, which makes it
+/// easy to embed positions in tests using `include_str!`.
+impl FromStr for GameState {
+    type Err = SCError;
+
+    fn from_str(s: &str) -> SCResult<Self> {
+        if s.trim_start().starts_with('<') {
+            Self::from_node(&s.parse()?)
+        } else {
+            Self::parse_json(s)
+        }
+    }
 }
 
 impl FromXmlNode for GameState {
     fn from_node(node: &XmlNode) -> SCResult<Self> {
         Ok(Self {
-            turn: node.attribute("turn")?.parse()?,
-            round: node.attribute("round")?.parse()?,
-            first: Player::from_node(node.child_by_name("first")?)?,
-            second: Player::from_node(node.child_by_name("second")?)?,
-            board: Board::from_node(node.child_by_name("board")?)?,
-            start_piece: node.attribute("startPiece")?.parse()?,
-            start_team: Team::from_node(node.child_by_name("startTeam")?)?,
-            valid_colors: node.child_by_name("validColors")?.childs_by_name("color").map(Color::from_node).collect::<Result<_, _>>()?,
-            last_move_mono: HashMap::new(), // TODO
-            blue_shapes: node.child_by_name("blueShapes")?.childs_by_name("shape").map(PieceShape::from_node).collect::<Result<_, _>>()?,
-            yellow_shapes: node.child_by_name("yellowShapes")?.childs_by_name("shape").map(PieceShape::from_node).collect::<Result<_, _>>()?,
-            red_shapes: node.child_by_name("redShapes")?.childs_by_name("shape").map(PieceShape::from_node).collect::<Result<_, _>>()?,
-            green_shapes: node.child_by_name("greenShapes")?.childs_by_name("shape").map(PieceShape::from_node).collect::<Result<_, _>>()?
-        })
+            turn: node.parse_attribute("turn")?,
+            round: node.parse_attribute("round")?,
+            first: node.parse_child("first")?,
+            second: node.parse_child("second")?,
+            board: node.parse_child("board")?,
+            start_piece: node.parse_attribute("startPiece")?,
+            start_team: node.parse_child("startTeam")?,
+            valid_colors: node.child_by_name("validColors")?.parse_children("color")?,
+            last_move_mono: node.childs_by_name("lastMoveMono").flat_map(|m| m.childs_by_name("entry"))
+                .map(|entry| Ok((entry.parse_child("color")?, entry.child_by_name("boolean")?.parse_content()?)))
+                .collect::<SCResult<_>>()?,
+            blue_shapes: node.child_by_name("blueShapes")?.parse_children("shape")?,
+            yellow_shapes: node.child_by_name("yellowShapes")?.parse_children("shape")?,
+            red_shapes: node.child_by_name("redShapes")?.parse_children("shape")?,
+            green_shapes: node.child_by_name("greenShapes")?.parse_children("shape")?,
+            validation_mode: ValidationMode::default(),
+            rules: RuleSet::default(),
+            history: Vec::new(),
+            anchors: [BitGrid::new(); COLOR_COUNT]
+        }.with_refreshed_anchors())
+    }
+}
+
+impl From<GameState> for XmlNode {
+    fn from(state: GameState) -> Self {
+        let shapes = |name: &str, color| {
+            let mut shapes: Vec<_> = state.undeployed_shapes_of_color(color).map(|s| s.to_string()).collect();
+            shapes.sort();
+            XmlNode::new(name)
+                .childs(shapes.into_iter().map(|s| XmlNode::new("shape").content(s)))
+                .build()
+        };
+        let shape_nodes = [
+            shapes("blueShapes", Color::Blue),
+            shapes("yellowShapes", Color::Yellow),
+            shapes("redShapes", Color::Red),
+            shapes("greenShapes", Color::Green)
+        ];
+        let mut last_move_mono: Vec<_> = state.last_move_mono.into_iter().collect();
+        last_move_mono.sort_by_key(|&(c, _)| COLORS.iter().position(|&x| x == c));
+        XmlNode::new("state")
+            .attribute("class", "state")
+            .attribute("turn", state.turn)
+            .attribute("round", state.round)
+            .attribute("startPiece", &state.start_piece)
+            .child(XmlNode::new("startColor").content(COLORS[0]))
+            .child(XmlNode::new("startTeam").attribute("class", "team").content(state.start_team))
+            .child(state.board)
+            .childs(shape_nodes)
+            .child(XmlNode::new("lastMoveMono")
+                .attribute("class", "linked-hash-map")
+                .childs(last_move_mono.into_iter().map(|(color, mono)| XmlNode::new("entry")
+                    .child(XmlNode::new("color").content(color))
+                    .child(XmlNode::new("boolean").content(mono)))))
+            .child(XmlNode::new("validColors")
+                .childs(state.valid_colors.iter().map(|c| XmlNode::new("color").content(c))))
+            .child(XmlNode::from(state.first).renamed("first"))
+            .child(XmlNode::from(state.second).renamed("second"))
+            .build()
+    }
+}
+
+#[cfg(feature = "json")]
+impl ToJson for GameState {
+    fn to_json(&self) -> JsonValue {
+        let shapes = |color| {
+            let mut shapes: Vec<_> = self.undeployed_shapes_of_color(color).map(PieceShape::to_json).collect();
+            shapes.sort_by_key(|s| s.to_string());
+            JsonValue::Array(shapes)
+        };
+        JsonValue::object([
+            ("turn", self.turn.into()),
+            ("round", self.round.into()),
+            ("first", self.first.to_json()),
+            ("second", self.second.to_json()),
+            ("board", self.board.to_json()),
+            ("start_piece", self.start_piece.to_json()),
+            ("start_team", self.start_team.to_json()),
+            ("valid_colors", JsonValue::array(self.valid_colors.iter().map(Color::to_json))),
+            ("last_move_mono", JsonValue::object(self.last_move_mono.iter().map(|(c, &m)| (c.to_string(), m.into())))),
+            ("blue_shapes", shapes(Color::Blue)),
+            ("yellow_shapes", shapes(Color::Yellow)),
+            ("red_shapes", shapes(Color::Red)),
+            ("green_shapes", shapes(Color::Green)),
+            ("history", JsonValue::array(self.history.iter().map(|(turn, color, game_move)| JsonValue::object([
+                ("turn", (*turn).into()),
+                ("color", color.to_json()),
+                ("move", game_move.to_json())
+            ]))))
+        ])
+    }
+}
+
+#[cfg(feature = "json")]
+impl FromJson for GameState {
+    fn from_json(json: &JsonValue) -> SCResult<Self> {
+        let shapes = |key| -> SCResult<HashSet<PieceShape>> {
+            json.get(key)?.as_array()?.iter().map(PieceShape::from_json).collect()
+        };
+        Ok(Self {
+            turn: json.get("turn")?.as_u32()?,
+            round: json.get("round")?.as_u32()?,
+            first: Player::from_json(json.get("first")?)?,
+            second: Player::from_json(json.get("second")?)?,
+            board: Board::from_json(json.get("board")?)?,
+            start_piece: PieceShape::from_json(json.get("start_piece")?)?,
+            start_team: Team::from_json(json.get("start_team")?)?,
+            valid_colors: Vec::from_json(json.get("valid_colors")?)?,
+            last_move_mono: json.get("last_move_mono")?.as_object()?.iter()
+                .map(|(c, m)| Ok((c.parse()?, m.as_bool()?)))
+                .collect::<SCResult<_>>()?,
+            blue_shapes: shapes("blue_shapes")?,
+            yellow_shapes: shapes("yellow_shapes")?,
+            red_shapes: shapes("red_shapes")?,
+            green_shapes: shapes("green_shapes")?,
+            validation_mode: ValidationMode::default(),
+            rules: RuleSet::default(),
+            history: json.opt("history").map(|h| h.as_array()?.iter()
+                .map(|entry| Ok((entry.get("turn")?.as_u32()?, Color::from_json(entry.get("color")?)?, Move::from_json(entry.get("move")?)?)))
+                .collect::<SCResult<_>>()).transpose()?.unwrap_or_default(),
+            anchors: [BitGrid::new(); COLOR_COUNT]
+        }.with_refreshed_anchors())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::game::{Color, Move, PIECE_SHAPES_BY_NAME, Team};
+    use crate::game::{Board, Color, GamePhase, MAX_ROUND, Move, OPENING_ROUNDS, PIECE_SHAPES_BY_NAME, Piece, PieceShape, Rotation, Symmetry, Team, Vec2};
+    use crate::rules::RuleSet;
+    #[cfg(feature = "json")]
+    use crate::util::{FromJson, ToJson, JsonValue};
+    use crate::util::{RuleViolationKind, XmlNode};
 
-    use super::GameState;
+    use super::{GameState, SizedGameState};
 
     #[test]
     fn test_game_state() {
@@ -345,7 +1019,7 @@ mod tests {
 
         {
             let possible_moves: Vec<_> = state.possible_moves().collect();
-            let possible_first_moves: Vec<_> = state.possible_first_moves().collect();
+            let possible_first_moves: Vec<_> = state.possible_first_moves(Color::Blue).collect();
 
             assert!(!possible_moves.is_empty());
             assert_eq!(possible_moves, possible_first_moves);
@@ -389,4 +1063,374 @@ mod tests {
             assert!(!possible_moves.is_empty());
         }
     }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_json_round_trip() {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_Y"].clone());
+        let first_move = state.possible_moves().next().unwrap();
+        state.perform_move(first_move).unwrap();
+
+        let json: JsonValue = state.to_json().to_string().parse().unwrap();
+        assert_eq!(GameState::from_json(&json).unwrap(), state);
+
+        let out_of_range = json.to_string().replacen("\"turn\":1", "\"turn\":4294967297", 1);
+        assert_ne!(out_of_range, json.to_string());
+        assert!(GameState::from_json(&out_of_range.parse().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_move_validation() {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_Y"].clone());
+        let skip = Move::Skip { color: Color::Blue };
+
+        assert!(!state.can_skip());
+        assert_eq!(state.validate_move(&skip).unwrap_err().rule_violation_kind(), Some(RuleViolationKind::SkipInFirstRound));
+        assert!(state.perform_move(skip).is_err());
+        assert_eq!(state.turn, 0);
+
+        let first_move = state.possible_moves().next().unwrap();
+        assert!(state.validate_move(&first_move).is_ok());
+        state.perform_move(first_move.clone()).unwrap();
+
+        // The move has the wrong color now and the corner is obstructed
+        assert_eq!(state.validate_move(&first_move).unwrap_err().rule_violation_kind(), Some(RuleViolationKind::WrongColor));
+        if let Move::Set { piece } = &first_move {
+            assert_eq!(state.validate_set_move(piece).unwrap_err().rule_violation_kind(), Some(RuleViolationKind::AlreadyPlaced));
+        }
+        for _ in 0..3 {
+            let first_move = state.possible_moves().next().unwrap();
+            state.perform_move(first_move).unwrap();
+        }
+        assert!(state.can_skip());
+    }
+
+    #[test]
+    fn test_validate_moves() {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_L"].clone());
+        for _ in 0..8 {
+            let game_move = state.possible_moves().next().unwrap();
+            state.perform_move(game_move).unwrap();
+        }
+
+        let mut candidates = vec![Move::Skip { color: Color::Blue }, Move::Skip { color: Color::Red }];
+        for name in ["PENTO_L", "TETRO_T", "DOMINO"] {
+            let kind = PIECE_SHAPES_BY_NAME[name].clone();
+            for (rotation, is_flipped) in kind.transformations() {
+                for x in -2..20 {
+                    for y in -2..20 {
+                        for color in [Color::Blue, Color::Yellow] {
+                            let position = Vec2::new(x, y);
+                            candidates.push(Move::Set { piece: Piece { kind: kind.clone(), rotation, is_flipped, color, position } });
+                        }
+                    }
+                }
+            }
+        }
+
+        let check = |state: &GameState| {
+            let results = state.validate_moves(candidates.iter());
+            assert_eq!(results.len(), candidates.len());
+            for (candidate, result) in candidates.iter().zip(&results) {
+                let expected = state.validate_move(candidate);
+                assert_eq!(result.as_ref().map(|_| ()).map_err(|e| e.to_string()), expected.map_err(|e| e.to_string()), "{:?}", candidate);
+            }
+            results.iter().any(|r| r.is_ok())
+        };
+        assert!(check(&state));
+
+        // Blue drops out, so its moves are rejected
+        assert_eq!(state.current_color(), Color::Blue);
+        state.valid_colors.retain(|&c| c != Color::Blue);
+        assert_eq!(state.current_color(), Color::Yellow);
+        check(&state);
+
+        // Nobody can move once the game is over
+        state.valid_colors.clear();
+        assert!(!check(&state));
+    }
+
+    #[test]
+    fn test_anchors_and_undo() {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_V"].clone());
+        let initial = state.clone();
+        assert_eq!(state.corner_count(Color::Blue), 4);
+
+        let mut undos = Vec::new();
+        for i in 0..24 {
+            let moves: Vec<_> = state.possible_moves().collect();
+            let game_move = moves[(i * 7) % moves.len()].clone();
+            undos.push(state.perform_move_undoable(game_move).unwrap());
+
+            // The incrementally updated anchors match the recomputed ones
+            let mut refreshed = state.clone();
+            refreshed.refresh_anchors();
+            assert!(refreshed == state, "Anchors differ after {:?}", state.turn);
+        }
+
+        while let Some(undo) = undos.pop() {
+            state.undo_move(undo);
+        }
+        assert!(state == initial, "Undoing every move does not restore the state");
+    }
+
+    #[test]
+    fn test_history() {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_V"].clone());
+        assert_eq!(state.last_move(), None);
+        let mut moves = Vec::new();
+        for _ in 0..5 {
+            let game_move = state.possible_moves().next().unwrap();
+            moves.push((state.turn, state.current_color(), game_move.clone()));
+            state.perform_move(game_move).unwrap();
+        }
+        let skip = Move::Skip { color: state.current_color() };
+        state.perform_move(skip.clone()).unwrap();
+        assert_eq!(&state.history()[..5], &moves[..]);
+        assert_eq!(state.last_move(), Some(&skip));
+        assert_eq!(state.last_move_of(Color::Yellow), Some(&skip));
+        assert_eq!(state.last_move_of(Color::Red), Some(&moves[2].2));
+
+        // Received states start without a history
+        let received = |state: GameState| XmlNode::from(state).serialize().unwrap().parse::<GameState>().unwrap();
+        let mut next = received(state.after_move(state.possible_moves().next().unwrap()).unwrap());
+        assert!(next.history().is_empty());
+        assert!(next.extend_history_from(&state));
+        assert_eq!(next.history().len(), 7);
+        assert!(!state.clone().extend_history_from(&next));
+    }
+
+    #[test]
+    fn test_snapshots() {
+        let state: GameState = include_str!("../../fixtures/positions/midgame.xml").parse().unwrap();
+        assert_eq!((state.turn, state.start_piece.name()), (40, "PENTO_L"));
+
+        #[cfg(feature = "json")]
+        {
+            let path = std::env::temp_dir().join(format!("socha-snapshot-{}.json", std::process::id()));
+            state.save(&path).unwrap();
+            let loaded = GameState::load(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+            assert!(loaded == state);
+        }
+    }
+
+    #[test]
+    fn test_reachable_area() {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_V"].clone());
+        assert_eq!(state.reachable_area(Color::Blue), 400);
+
+        let game_move = state.possible_moves().next().unwrap();
+        let color = game_move.color();
+        state.perform_move(game_move).unwrap();
+        let own = state.board.color_mask(color);
+        assert_eq!(state.reachable_area(color), 400 - own.dilate().count());
+        assert!(state.reachable_area(state.current_color()) < 400);
+
+        state.valid_colors.retain(|&c| c != color);
+        assert_eq!(state.reachable_area(color), 0);
+    }
+
+    #[test]
+    fn test_color_activity() {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_V"].clone());
+        assert!(state.active_colors().eq([Color::Blue, Color::Yellow, Color::Red, Color::Green]));
+        assert_eq!(state.next_color_after(Color::Green), Some(Color::Blue));
+        assert_eq!(state.turns_until(Color::Red), Some(2));
+
+        state.perform_move(state.possible_moves().next().unwrap()).unwrap();
+        state.valid_colors.retain(|&c| c != Color::Red);
+        assert!(!state.is_color_active(Color::Red));
+        assert!(state.active_colors().eq([Color::Yellow, Color::Green, Color::Blue]));
+        assert_eq!(state.next_color_after(Color::Yellow), Some(Color::Green));
+        assert_eq!(state.turns_until(Color::Blue), Some(2));
+        assert_eq!(state.turns_until(Color::Red), None);
+
+        state.valid_colors = vec![Color::Green];
+        assert_eq!(state.next_color_after(Color::Green), Some(Color::Green));
+        state.valid_colors.clear();
+        assert_eq!(state.next_color_after(Color::Green), None);
+        assert_eq!(state.validate_move(&Move::Skip { color: Color::Green }).unwrap_err().rule_violation_kind(), Some(RuleViolationKind::GameOver));
+    }
+
+    #[test]
+    fn test_move_after_drop_out() {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_V"].clone());
+        state.perform_move(state.possible_moves().next().unwrap()).unwrap();
+
+        // Yellow drops out while it is its turn
+        let yellow_move = state.possible_moves().next().unwrap();
+        state.valid_colors.retain(|&c| c != Color::Yellow);
+        assert_eq!(state.current_color(), Color::Red);
+        assert_eq!(state.active_colors().next(), Some(Color::Red));
+        assert_eq!(state.turns_until(Color::Red), Some(0));
+        assert_eq!(state.possible_moves_for(Color::Yellow).count(), 0);
+        assert!(state.possible_moves().all(|m| m.color() == Color::Red));
+
+        assert_eq!(yellow_move.color(), Color::Yellow);
+        assert!(state.validate_move(&yellow_move).is_err());
+        if let Move::Set { piece } = &yellow_move {
+            assert_eq!(state.validate_set_move(piece).unwrap_err().rule_violation_kind(), Some(RuleViolationKind::ColorNotInGame));
+        }
+
+        let previous = state.clone();
+        state.perform_move(state.possible_moves().next().unwrap()).unwrap();
+        assert_eq!(state.history().last().map(|&(turn, color, _)| (turn, color)), Some((2, Color::Red)));
+        assert_eq!((state.turn, state.current_color()), (3, Color::Green));
+        assert!(previous.infer_move(&state).is_some());
+
+        // Blue drops out, so its turn is skipped after Green's move
+        state.valid_colors.retain(|&c| c != Color::Blue);
+        state.perform_move(state.possible_moves().next().unwrap()).unwrap();
+        assert_eq!((state.turn, state.round, state.current_color()), (6, 2, Color::Red));
+        assert_eq!(state.turns_until(Color::Green), Some(1));
+    }
+
+    #[test]
+    fn test_infer_move() {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_L"].clone());
+        for i in 0..12 {
+            let moves: Vec<_> = state.possible_moves().collect();
+            let next = state.after_move(moves[(i * 5) % moves.len()].clone()).unwrap();
+            let inferred = state.infer_move(&next).unwrap();
+            assert_eq!(state.after_move(inferred).unwrap().board, next.board);
+            state = next;
+        }
+
+        let skip = Move::Skip { color: state.current_color() };
+        let next = state.after_move(skip.clone()).unwrap();
+        assert_eq!(state.infer_move(&next), Some(skip));
+
+        let after_next = next.after_move(next.possible_moves().next().unwrap()).unwrap();
+        assert_eq!(state.infer_move(&after_next), None);
+
+        // A set move followed by a skip is not a single move
+        let set_move = state.possible_moves().find(|m| matches!(m, Move::Set { .. })).unwrap();
+        let next = state.after_move(set_move.clone()).unwrap();
+        let after_skip = next.after_move(Move::Skip { color: next.current_color() }).unwrap();
+        assert_eq!(state.infer_move(&after_skip), None);
+
+        // ...unless the skipped color has dropped out
+        let mut dropped_out = next.clone();
+        dropped_out.valid_colors.retain(|&c| c != next.current_color());
+        dropped_out.turn += 1;
+        assert_eq!(state.infer_move(&dropped_out), Some(set_move));
+    }
+
+    #[test]
+    fn test_moves_for_other_colors() {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_Y"].clone());
+        let first_move = state.possible_moves().next().unwrap();
+        state.perform_move(first_move).unwrap();
+
+        assert_eq!(state.possible_moves_for(Color::Yellow).collect::<Vec<_>>(), state.possible_moves().collect::<Vec<_>>());
+        let blue_moves: Vec<_> = state.possible_moves_for(Color::Blue).collect();
+        assert!(blue_moves.contains(&Move::Skip { color: Color::Blue }));
+        for game_move in &blue_moves {
+            assert_eq!(game_move.color(), Color::Blue);
+            if let Move::Set { piece } = game_move {
+                assert!(state.validate_set_move(piece).is_ok());
+            }
+        }
+        assert_eq!(state.possible_moves_for_team(Team::One).count(), blue_moves.len() + state.possible_moves_for(Color::Red).count());
+        assert_eq!(state.mobility(Color::Blue), blue_moves.len() - 1);
+        assert!(state.blocked_colors().is_empty());
+        assert!(!state.is_game_over());
+
+        // Yellow has nothing left to place
+        state.yellow_shapes.clear();
+        assert_eq!(state.mobility(Color::Yellow), 0);
+        assert_eq!(state.blocked_colors(), vec![Color::Yellow]);
+        assert!(!state.is_game_over());
+
+        state.blue_shapes.clear();
+        state.red_shapes.clear();
+        state.green_shapes.clear();
+        assert!(state.is_game_over());
+    }
+
+    #[test]
+    fn test_moves_are_distinct() {
+        let state: GameState = include_str!("../../fixtures/positions/midgame.xml").parse().unwrap();
+        let moves: Vec<_> = state.possible_moves().collect();
+        let mut placements: Vec<Vec<_>> = moves.iter().filter_map(|m| match m {
+            Move::Set { piece } => {
+                assert_eq!(piece.canonicalize(), *piece);
+                let mut cells: Vec<_> = piece.coordinates().map(|c| (c.x, c.y)).collect();
+                cells.sort();
+                Some(cells)
+            },
+            Move::Skip { .. } => None
+        }).collect();
+        let count = placements.len();
+        placements.sort();
+        placements.dedup();
+        assert_eq!(placements.len(), count);
+    }
+
+    #[test]
+    fn test_phase() {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["MONO"].clone());
+        assert_eq!(state.phase(), GamePhase::Opening);
+        assert!(state.colors_out_of_moves().is_empty());
+        for _ in 0..(4 * OPENING_ROUNDS) {
+            state.perform_move(state.possible_moves().next().unwrap()).unwrap();
+        }
+        assert_eq!(state.phase(), GamePhase::Midgame);
+
+        // Yellow has nothing left to place and green dropped out
+        state.yellow_shapes.clear();
+        state.valid_colors.retain(|&c| c != Color::Green);
+        assert_eq!(state.colors_out_of_moves(), vec![Color::Yellow, Color::Green]);
+        assert_eq!(state.phase(), GamePhase::Endgame);
+
+        let mut late: GameState = include_str!("../../fixtures/positions/midgame.xml").parse().unwrap();
+        late.round = MAX_ROUND - 1;
+        assert_eq!(late.phase(), GamePhase::Endgame);
+        assert!(GamePhase::Opening < GamePhase::Endgame);
+    }
+
+    #[test]
+    fn test_small_board() {
+        let mut state = SizedGameState::<6>::new(PIECE_SHAPES_BY_NAME["TETRO_L"].clone());
+        for corner in [Vec2::new(0, 0), Vec2::new(5, 0), Vec2::new(5, 5), Vec2::new(0, 5)] {
+            let moves: Vec<_> = state.possible_moves().collect();
+            assert!(!moves.is_empty());
+            for game_move in &moves {
+                if let Move::Set { piece } = game_move {
+                    assert!(piece.coordinates().all(|p| p.x < 6 && p.y < 6), "{:?} is out of bounds", piece);
+                }
+            }
+            let game_move = moves.into_iter().find(|m| matches!(m, Move::Set { piece } if piece.coordinates().any(|p| p == corner))).unwrap();
+            state.perform_move(game_move).unwrap();
+        }
+        assert_eq!(state.board.count_obstructed(), 16);
+        assert!(state.transformed(Symmetry::Rotate90).board.occupied_fields().all(|f| f.position.x < 6 && f.position.y < 6));
+        assert!(state.possible_moves().all(|m| state.validate_move(&m).is_ok()));
+    }
+
+    #[test]
+    fn test_rule_set() {
+        static START_CELLS: [Vec2; 2] = [Vec2::new(4, 4), Vec2::new(9, 9)];
+        let mut state = SizedGameState::<14>::new(PIECE_SHAPES_BY_NAME["MONO"].clone());
+        state.rules = RuleSet { start_cells: Some(&START_CELLS), max_round: 10, ..RuleSet::default() };
+
+        let corner = Move::Set { piece: Piece { kind: state.start_piece.clone(), rotation: Rotation::None, is_flipped: false, color: Color::Blue, position: Vec2::new(0, 0) } };
+        assert_eq!(state.validate_move(&corner).unwrap_err().rule_violation_kind(), Some(RuleViolationKind::NotInCorner));
+        let moves: Vec<_> = state.possible_moves().collect();
+        assert_eq!(moves.len(), 2);
+        state.perform_move(moves[0].clone()).unwrap();
+        assert_eq!(state.possible_moves().count(), 1);
+
+        state.round = 7;
+        assert_eq!(state.phase(), GamePhase::Endgame);
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<GameState>();
+        assert_send_sync::<Board>();
+        assert_send_sync::<PieceShape>();
+    }
 }
@@ -1,25 +1,50 @@
+mod arbitrary;
+mod bit_grid;
 mod board;
+mod cached_state;
 mod color;
 mod corner;
 mod field;
+mod game_phase;
 mod game_state;
 mod r#move;
+mod move_undo;
+mod perft;
+mod piece_builder;
 mod piece_shape;
+mod piece_shape_kind;
 mod piece;
 mod player;
 mod rotation;
+mod symmetry;
 mod team;
+mod validation_mode;
 mod vec2;
+mod zobrist;
 
+pub use arbitrary::*;
+pub use bit_grid::*;
 pub use board::*;
+pub use cached_state::*;
 pub use color::*;
 pub use corner::*;
 pub use field::*;
+pub use game_phase::*;
 pub use game_state::*;
 pub use r#move::*;
+pub use move_undo::*;
+pub use perft::*;
+pub use piece_builder::*;
 pub use piece_shape::*;
+pub use piece_shape_kind::*;
 pub use piece::*;
 pub use player::*;
 pub use rotation::*;
+pub use symmetry::*;
 pub use team::*;
+pub use validation_mode::*;
 pub use vec2::*;
+pub use zobrist::*;
+
+// The rule parameters used to be defined here
+pub use crate::rules::{BOARD_SIZE, MAX_ROUND};
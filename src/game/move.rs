@@ -1,8 +1,12 @@
-use crate::util::XmlNode;
-use super::{Color, Piece};
+use std::{convert::TryFrom, fmt};
+use crate::rules::BOARD_SIZE;
+use crate::util::{SCError, SCResult, FromXmlNode, XmlNode};
+#[cfg(feature = "json")]
+use crate::util::{FromJson, ToJson, JsonValue};
+use super::{Color, Piece, PIECE_SHAPE_KINDS, Rotation, Vec2};
 
 /// A move in the game.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Move {
     /// A move that skips a round.
     Skip { color: Color },
@@ -11,12 +15,88 @@ pub enum Move {
 }
 
 impl Move {
+    /// The encoding of a skip.
+    pub const ENCODED_SKIP: u32 = 1 << 31;
+
     pub fn color(&self) -> Color {
         match self {
             Self::Skip { color } => *color,
             Self::Set { piece } => piece.color
         }
     }
+
+    /// Packs the move into a compact, canonical `u32`, i.e. moves
+    /// placing a piece on the same fields are encoded equally, since
+    /// the piece's canonical transformation is encoded. The color is
+    /// not part of the encoding, since it is usually implied by the
+    /// state the move is performed in. The layout is:
+    ///
+    /// ```text
+    ///  bit  31:     skip flag (all other bits are 0 for a skip)
+    ///  bits 13-17:  shape index (as in PIECE_SHAPE_KINDS)
+    ///  bits 11-12:  rotation (0 = none, 1 = right, 2 = mirror, 3 = left)
+    ///  bit  10:     flip flag
+    ///  bits 5-9:    y-coordinate
+    ///  bits 0-4:    x-coordinate
+    /// ```
+    pub fn encode(&self) -> u32 {
+        match self {
+            Self::Skip { .. } => Self::ENCODED_SKIP,
+            Self::Set { piece } => {
+                let piece = piece.canonicalize();
+                (piece.kind.kind() as u32) << 13
+                    | (i32::from(piece.rotation) as u32) << 11
+                    | (piece.is_flipped as u32) << 10
+                    | (piece.position.y as u32 & 0x1F) << 5
+                    | (piece.position.x as u32 & 0x1F)
+            }
+        }
+    }
+
+    /// Unpacks a move of the given color from the encoding
+    /// described in `encode`, yielding `None` if it is invalid.
+    pub fn decode(encoded: u32, color: Color) -> Option<Self> {
+        if encoded == Self::ENCODED_SKIP {
+            return Some(Self::Skip { color });
+        }
+        if encoded >> 18 != 0 {
+            return None;
+        }
+        let kind = *PIECE_SHAPE_KINDS.get((encoded >> 13) as usize)?;
+        let rotation = Rotation::try_from(((encoded >> 11) & 0x3) as i32).ok()?;
+        let position = Vec2::new((encoded & 0x1F) as i32, ((encoded >> 5) & 0x1F) as i32);
+        if position.x >= BOARD_SIZE as i32 || position.y >= BOARD_SIZE as i32 {
+            return None;
+        }
+        let piece = Piece { kind: kind.shape().clone(), rotation, is_flipped: encoded & (1 << 10) != 0, color, position };
+        Some(Self::Set { piece })
+    }
+}
+
+/// Formats the move compactly, e.g. `BLUE PENTO_V (RIGHT, flipped) at (3, 4)`.
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Skip { color } => write!(f, "{} skips", color),
+            Self::Set { piece } => {
+                write!(f, "{} {} ({}", piece.color, piece.kind, piece.rotation)?;
+                if piece.is_flipped {
+                    write!(f, ", flipped")?;
+                }
+                write!(f, ") at {}", piece.position)
+            }
+        }
+    }
+}
+
+impl FromXmlNode for Move {
+    fn from_node(node: &XmlNode) -> SCResult<Self> {
+        match node.attribute("class")? {
+            "sc.plugin2021.SetMove" => Ok(Self::Set { piece: node.parse_child("piece")? }),
+            "sc.plugin2021.SkipMove" => Ok(Self::Skip { color: node.parse_child("color")? }),
+            class => Err(SCError::parse(format!("Unrecognized move class: {}", class)))
+        }
+    }
 }
 
 impl From<Move> for XmlNode {
@@ -28,10 +108,65 @@ impl From<Move> for XmlNode {
                 .build(),
             Move::Skip { color } => XmlNode::new("data")
                 .attribute("class", "sc.plugin2021.SkipMove")
-                .child(XmlNode::new("color")
-                    .content(color.to_string().as_str())
-                    .build())
+                .child(XmlNode::new("color").content(color))
                 .build()
         }
     }
 }
+
+#[cfg(feature = "json")]
+impl ToJson for Move {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            Self::Skip { color } => JsonValue::object([("Skip", JsonValue::object([("color", color.to_json())]))]),
+            Self::Set { piece } => JsonValue::object([("Set", JsonValue::object([("piece", piece.to_json())]))])
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl FromJson for Move {
+    fn from_json(json: &JsonValue) -> SCResult<Self> {
+        if let Some(skip) = json.opt("Skip") {
+            Ok(Self::Skip { color: Color::from_json(skip.get("color")?)? })
+        } else if let Some(set) = json.opt("Set") {
+            Ok(Self::Set { piece: Piece::from_json(set.get("piece")?)? })
+        } else {
+            Err(SCError::parse(format!("Could not parse move {}", json)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{Color, GameState, Move, Piece, PIECE_SHAPES_BY_NAME, Rotation, Vec2};
+
+    #[test]
+    fn test_encoding() {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_L"].clone());
+        for _ in 0..8 {
+            for game_move in state.possible_moves() {
+                assert_eq!(Move::decode(game_move.encode(), game_move.color()), Some(game_move));
+            }
+            state.perform_move(state.possible_moves().last().unwrap()).unwrap();
+        }
+
+        let piece = Piece { kind: PIECE_SHAPES_BY_NAME["PENTO_L"].clone(), rotation: Rotation::Left, is_flipped: true, color: Color::Red, position: Vec2::new(15, 16) };
+        let encoded = Move::Set { piece: piece.clone() }.encode();
+        assert_eq!(encoded, (piece.kind.kind() as u32) << 13 | 3 << 11 | 1 << 10 | 16 << 5 | 15);
+        assert_eq!(Move::decode(encoded, Color::Green), Some(Move::Set { piece: Piece { color: Color::Green, ..piece } }));
+
+        // Transformations of the monomino all describe the same placement
+        let mono = Piece { kind: PIECE_SHAPES_BY_NAME["MONO"].clone(), rotation: Rotation::Left, is_flipped: true, color: Color::Red, position: Vec2::new(19, 19) };
+        let encoded = Move::Set { piece: mono.clone() }.encode();
+        assert_eq!(encoded, Move::Set { piece: Piece { rotation: Rotation::None, is_flipped: false, ..mono.clone() } }.encode());
+        assert_eq!(Move::decode(encoded, Color::Red), Some(Move::Set { piece: mono.canonicalize() }));
+
+        let skip = Move::Skip { color: Color::Yellow };
+        assert_eq!(skip.encode(), Move::ENCODED_SKIP);
+        assert_eq!(Move::decode(Move::ENCODED_SKIP, Color::Yellow), Some(skip));
+        assert_eq!(Move::decode(21 << 13, Color::Blue), None);
+        assert_eq!(Move::decode(20, Color::Blue), None);
+        assert_eq!(Move::decode(Move::ENCODED_SKIP | 1, Color::Blue), None);
+    }
+}
@@ -0,0 +1,20 @@
+use crate::rules::BOARD_SIZE;
+use super::{BitGrid, COLOR_COUNT, Move};
+
+/// The information needed to take back a performed move,
+/// see `GameState::perform_move_undoable`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveUndo<const N: usize = BOARD_SIZE> {
+    pub(super) game_move: Move,
+    pub(super) turn: u32,
+    pub(super) round: u32,
+    pub(super) anchors: [BitGrid<N, N>; COLOR_COUNT],
+    pub(super) last_move_mono: Option<bool>
+}
+
+impl<const N: usize> MoveUndo<N> {
+    /// The move that will be taken back.
+    pub fn game_move(&self) -> &Move {
+        &self.game_move
+    }
+}
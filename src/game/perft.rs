@@ -0,0 +1,74 @@
+use super::{GameState, Move};
+
+/// Counts the leaf nodes of the game tree of the given depth,
+/// i.e. the number of distinct move sequences of that length.
+/// Comparing these counts against a reference implementation
+/// is a cheap way to catch regressions in the rule logic.
+pub fn perft(state: &GameState, depth: usize) -> u64 {
+    match depth {
+        0 => 1,
+        1 => state.possible_moves().count() as u64,
+        _ => state.possible_moves()
+            .filter_map(|m| state.after_move(m).ok())
+            .map(|s| perft(&s, depth - 1))
+            .sum()
+    }
+}
+
+/// Computes the perft count below each of the possible
+/// moves, which helps with pinpointing discrepancies.
+pub fn perft_divide(state: &GameState, depth: usize) -> Vec<(Move, u64)> {
+    state.possible_moves()
+        .map(|m| {
+            let count = match state.after_move(m.clone()) {
+                Ok(s) if depth > 0 => perft(&s, depth - 1),
+                _ => 0
+            };
+            (m, count)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{GameState, PIECE_SHAPES_BY_NAME};
+    use super::{perft, perft_divide};
+
+    // Move generation enumerates the distinct transformations of the
+    // start piece for each of the 4 corners. Every color has to
+    // occupy another corner, which leaves 4, 3, 2 and 1 corners.
+
+    #[test]
+    fn test_perft_mono() {
+        // The monomino has a single distinct transformation
+        let state = GameState::new(PIECE_SHAPES_BY_NAME["MONO"].clone());
+        assert_eq!(perft(&state, 0), 1);
+        assert_eq!(perft(&state, 1), 4);
+        assert_eq!(perft(&state, 2), 4 * 3);
+        assert_eq!(perft(&state, 3), 4 * 3 * 2);
+    }
+
+    #[test]
+    fn test_perft_pento_y() {
+        // Each transformation of the Y occupies 2 corners of its bounding box
+        let state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_Y"].clone());
+        assert_eq!(perft(&state, 1), 16);
+        assert_eq!(perft(&state, 2), 16 * 12);
+        assert_eq!(perft(&state, 3), 16 * 12 * 8);
+    }
+
+    #[test]
+    fn test_perft_pento_x() {
+        // The X does not occupy any corner of its bounding box
+        let state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_X"].clone());
+        assert_eq!(perft(&state, 1), 0);
+    }
+
+    #[test]
+    fn test_perft_divide() {
+        let state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_Y"].clone());
+        let divided = perft_divide(&state, 2);
+        assert_eq!(divided.len(), 16);
+        assert!(divided.iter().all(|&(_, n)| n == 12));
+    }
+}
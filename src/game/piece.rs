@@ -1,8 +1,10 @@
 use crate::util::{SCResult, FromXmlNode, XmlNode};
-use super::{Color, Vec2, PieceShape, Rotation};
+#[cfg(feature = "json")]
+use crate::util::{FromJson, ToJson, JsonValue};
+use super::{Color, Vec2, PieceBuilder, PieceShape, Rotation, ShapeMask};
 
 /// A game piece with color, position and transformed form.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Piece {
     /// The piece's untransformed shape
     pub kind: PieceShape,
@@ -17,6 +19,18 @@ pub struct Piece {
 }
 
 impl Piece {
+    /// Creates a builder for a piece of the given shape.
+    pub fn builder(kind: PieceShape) -> PieceBuilder {
+        PieceBuilder::new(kind)
+    }
+
+    /// Creates a piece whose transformed shape covers the given
+    /// board cell with the cell at `corner_offset`, which is usually
+    /// one of the transformed shape's `corner_cells`.
+    pub fn anchored(kind: PieceShape, rotation: Rotation, is_flipped: bool, color: Color, cell: Vec2, corner_offset: Vec2) -> Self {
+        Self { kind, rotation, is_flipped, color, position: cell - corner_offset }
+    }
+
     /// Fetches the piece's actual (transformed) shape
     pub fn shape(&self) -> PieceShape {
         self.kind.transform(self.rotation, self.is_flipped)
@@ -27,16 +41,38 @@ impl Piece {
         let position = self.position;
         self.shape().coordinates().map(move |c| c + position)
     }
+
+    /// Reconstructs a piece from the absolute board cells it covers,
+    /// e.g. taken from a board diff. Yields `None` if the cells do not
+    /// form a known shape. The transformation is canonical.
+    pub fn from_cells(color: Color, cells: impl IntoIterator<Item=Vec2>) -> Option<Self> {
+        let cells: Vec<_> = cells.into_iter().collect();
+        let position = cells.iter().copied().reduce(Vec2::min)?;
+        let relative: Vec<_> = cells.into_iter().map(|c| c - position).collect();
+        if !relative.iter().all(|&c| ShapeMask::is_in_bounds(c)) {
+            return None;
+        }
+        let (kind, rotation, is_flipped) = PieceShape::identify_mask(relative.into_iter().collect())?;
+        Some(Self { kind: kind.shape().clone(), rotation, is_flipped, color, position })
+    }
+
+    /// Picks the canonical rotation/flip-combination among those yielding
+    /// the same transformed shape, so that equal placements compare equal.
+    pub fn canonicalize(&self) -> Self {
+        let (_, rotation, is_flipped) = PieceShape::identify(&self.shape())
+            .expect("Transformed shape should always be identifiable");
+        Self { rotation, is_flipped, ..self.clone() }
+    }
 }
 
 impl FromXmlNode for Piece {
     fn from_node(node: &XmlNode) -> SCResult<Self> {
         Ok(Self {
-            color: node.attribute("color")?.parse()?,
-            kind: node.attribute("kind")?.parse()?,
-            rotation: node.attribute("rotation")?.parse()?,
-            is_flipped: node.attribute("isFlipped")?.parse()?,
-            position: Vec2::from_node(node.child_by_name("position")?)?
+            color: node.parse_attribute("color")?,
+            kind: node.parse_attribute("kind")?,
+            rotation: node.parse_attribute("rotation")?,
+            is_flipped: node.parse_attribute("isFlipped")?,
+            position: node.parse_child("position")?
         })
     }
 }
@@ -44,14 +80,61 @@ impl FromXmlNode for Piece {
 impl From<Piece> for XmlNode {
     fn from(piece: Piece) -> Self {
         XmlNode::new("piece")
-            .attribute("color", piece.color.to_string())
-            .attribute("kind", piece.kind.to_string())
-            .attribute("rotation", piece.rotation.to_string())
-            .attribute("isFlipped", piece.is_flipped.to_string())
-            .child(XmlNode::new("position")
-                .attribute("x", piece.position.x.to_string())
-                .attribute("y", piece.position.y.to_string())
-                .build())
+            .attribute("color", piece.color)
+            .attribute("kind", &piece.kind)
+            .attribute("rotation", piece.rotation)
+            .attribute("isFlipped", piece.is_flipped)
+            .child(piece.position)
             .build()
     }
 }
+
+#[cfg(feature = "json")]
+impl ToJson for Piece {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object([
+            ("kind", self.kind.to_json()),
+            ("rotation", self.rotation.to_json()),
+            ("is_flipped", self.is_flipped.into()),
+            ("color", self.color.to_json()),
+            ("position", self.position.to_json())
+        ])
+    }
+}
+
+#[cfg(feature = "json")]
+impl FromJson for Piece {
+    fn from_json(json: &JsonValue) -> SCResult<Self> {
+        Ok(Self {
+            kind: PieceShape::from_json(json.get("kind")?)?,
+            rotation: Rotation::from_json(json.get("rotation")?)?,
+            is_flipped: json.get("is_flipped")?.as_bool()?,
+            color: Color::from_json(json.get("color")?)?,
+            position: Vec2::from_json(json.get("position")?)?
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{Color, Piece, Rotation, Vec2, PIECE_SHAPES_BY_NAME};
+
+    #[test]
+    fn test_from_cells_and_canonicalize() {
+        let o = PIECE_SHAPES_BY_NAME["TETRO_O"].clone();
+        let piece = Piece { kind: o.clone(), rotation: Rotation::Mirror, is_flipped: false, color: Color::Red, position: Vec2::new(3, 4) };
+        let canonical = piece.canonicalize();
+        assert_eq!(canonical.coordinates().collect::<Vec<_>>(), piece.coordinates().collect::<Vec<_>>());
+        assert_eq!(canonical, Piece { rotation: Rotation::None, is_flipped: true, ..piece.clone() });
+        assert_eq!(Piece::from_cells(Color::Red, piece.coordinates()), Some(canonical));
+
+        let l = PIECE_SHAPES_BY_NAME["PENTO_L"].clone();
+        let piece = Piece { kind: l, rotation: Rotation::Right, is_flipped: true, color: Color::Blue, position: Vec2::new(7, 0) };
+        let reconstructed = Piece::from_cells(Color::Blue, piece.coordinates()).unwrap();
+        assert_eq!(reconstructed, piece.canonicalize());
+        assert_eq!(reconstructed.shape(), piece.shape());
+
+        assert_eq!(Piece::from_cells(Color::Blue, vec![Vec2::new(0, 0), Vec2::new(2, 0)]), None);
+        assert_eq!(Piece::from_cells(Color::Blue, vec![]), None);
+    }
+}
@@ -0,0 +1,69 @@
+use crate::util::{SCError, SCResult};
+use super::{Color, GameState, Move, Piece, PieceShape, Rotation, Vec2};
+
+/// A builder that makes constructing pieces (e.g. for
+/// opening books, tests or GUIs) more convenient.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PieceBuilder {
+    kind: PieceShape,
+    rotation: Rotation,
+    is_flipped: bool,
+    color: Option<Color>,
+    position: Option<Vec2>
+}
+
+impl PieceBuilder {
+    /// Creates a new builder for an untransformed piece of the given shape.
+    pub fn new(kind: PieceShape) -> Self {
+        Self { kind, rotation: Rotation::None, is_flipped: false, color: None, position: None }
+    }
+
+    /// Sets the rotation and whether the piece is flipped.
+    pub fn transform(mut self, rotation: Rotation, is_flipped: bool) -> Self {
+        self.rotation = rotation;
+        self.is_flipped = is_flipped;
+        self
+    }
+
+    /// Sets the rotation.
+    pub fn rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Sets whether the piece is flipped.
+    pub fn flipped(mut self, is_flipped: bool) -> Self {
+        self.is_flipped = is_flipped;
+        self
+    }
+
+    /// Sets the color.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Sets the top left corner of the piece's bounding box.
+    pub fn position(mut self, position: Vec2) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    /// Builds the piece, failing if no color or no position has been set.
+    pub fn build(self) -> SCResult<Piece> {
+        Ok(Piece {
+            color: self.color.filter(|&c| c != Color::None).ok_or_else(|| SCError::parse(format!("No color was set for the {} piece!", self.kind)))?,
+            position: self.position.ok_or_else(|| SCError::parse(format!("No position was set for the {} piece!", self.kind)))?,
+            kind: self.kind,
+            rotation: self.rotation,
+            is_flipped: self.is_flipped
+        })
+    }
+
+    /// Builds the piece and checks whether it could be placed in the given state.
+    pub fn build_validated(self, state: &GameState) -> SCResult<Piece> {
+        let piece = self.build()?;
+        state.validate_move(&Move::Set { piece: piece.clone() })?;
+        Ok(piece)
+    }
+}
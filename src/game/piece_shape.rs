@@ -1,169 +1,94 @@
 use std::{collections::HashMap, fmt, str::FromStr};
 use lazy_static::lazy_static;
 use crate::util::{SCResult, SCError, FromXmlNode, XmlNode};
-use super::{BOARD_SIZE, Vec2, ROTATIONS, Rotation};
+#[cfg(feature = "json")]
+use crate::util::{FromJson, ToJson, JsonValue};
+use super::{BOARD_SIZE, BitGrid, PIECE_SHAPE_COUNT, PieceShapeKind, Vec2, ROTATIONS, Rotation};
 
 lazy_static! {
-    pub static ref PIECE_SHAPES: [PieceShape; 21] = [
-        PieceShape::new("MONO", vec![Vec2::new(0, 0)]),
-        PieceShape::new("DOMINO", vec![Vec2::new(0, 0), Vec2::new(1, 0)]),
-        PieceShape::new("TRIO_L", vec![Vec2::new(0, 0), Vec2::new(0, 1), Vec2::new(1, 1)]),
-        PieceShape::new("TRIO_I", vec![Vec2::new(0, 0), Vec2::new(0, 1), Vec2::new(0, 2)]),
-        PieceShape::new("TETRO_O", vec![Vec2::new(0, 0), Vec2::new(1, 0), Vec2::new(0, 1), Vec2::new(1, 1)]),
-        PieceShape::new("TETRO_T", vec![Vec2::new(0, 0), Vec2::new(1, 0), Vec2::new(2, 0), Vec2::new(1, 1)]),
-        PieceShape::new("TETRO_I", vec![Vec2::new(0, 0), Vec2::new(0, 1), Vec2::new(0, 2), Vec2::new(0, 3)]),
-        PieceShape::new("TETRO_L", vec![Vec2::new(0, 0), Vec2::new(0, 1), Vec2::new(0, 2), Vec2::new(1, 2)]),
-        PieceShape::new("TETRO_Z", vec![Vec2::new(0, 0), Vec2::new(1, 0), Vec2::new(1, 1), Vec2::new(2, 1)]),
-        PieceShape::new("PENTO_L", vec![Vec2::new(0, 0), Vec2::new(0, 1), Vec2::new(0, 2), Vec2::new(0, 3), Vec2::new(1, 3)]),
-        PieceShape::new("PENTO_T", vec![Vec2::new(0, 0), Vec2::new(1, 0), Vec2::new(2, 0), Vec2::new(1, 1), Vec2::new(1, 2)]),
-        PieceShape::new("PENTO_V", vec![Vec2::new(0, 0), Vec2::new(0, 1), Vec2::new(0, 2), Vec2::new(1, 2), Vec2::new(2, 2)]),
-        PieceShape::new("PENTO_S", vec![Vec2::new(1, 0), Vec2::new(2, 0), Vec2::new(3, 0), Vec2::new(0, 1), Vec2::new(1, 1)]),
-        PieceShape::new("PENTO_Z", vec![Vec2::new(0, 0), Vec2::new(1, 0), Vec2::new(1, 1), Vec2::new(1, 2), Vec2::new(2, 2)]),
-        PieceShape::new("PENTO_I", vec![Vec2::new(0, 0), Vec2::new(0, 1), Vec2::new(0, 2), Vec2::new(0, 3), Vec2::new(0, 4)]),
-        PieceShape::new("PENTO_P", vec![Vec2::new(0, 0), Vec2::new(1, 0), Vec2::new(0, 1), Vec2::new(1, 1), Vec2::new(0, 2)]),
-        PieceShape::new("PENTO_W", vec![Vec2::new(0, 0), Vec2::new(0, 1), Vec2::new(1, 1), Vec2::new(1, 2), Vec2::new(2, 2)]),
-        PieceShape::new("PENTO_U", vec![Vec2::new(0, 0), Vec2::new(0, 1), Vec2::new(1, 1), Vec2::new(2, 1), Vec2::new(2, 0)]),
-        PieceShape::new("PENTO_R", vec![Vec2::new(0, 1), Vec2::new(1, 1), Vec2::new(1, 2), Vec2::new(2, 1), Vec2::new(2, 0)]),
-        PieceShape::new("PENTO_X", vec![Vec2::new(1, 0), Vec2::new(0, 1), Vec2::new(1, 1), Vec2::new(2, 1), Vec2::new(1, 2)]),
-        PieceShape::new("PENTO_Y", vec![Vec2::new(0, 1), Vec2::new(1, 0), Vec2::new(1, 1), Vec2::new(1, 2), Vec2::new(1, 3)])
+    pub static ref PIECE_SHAPES: [PieceShape; PIECE_SHAPE_COUNT] = [
+        PieceShape::new(PieceShapeKind::Mono, vec![Vec2::new(0, 0)]),
+        PieceShape::new(PieceShapeKind::Domino, vec![Vec2::new(0, 0), Vec2::new(1, 0)]),
+        PieceShape::new(PieceShapeKind::TrioL, vec![Vec2::new(0, 0), Vec2::new(0, 1), Vec2::new(1, 1)]),
+        PieceShape::new(PieceShapeKind::TrioI, vec![Vec2::new(0, 0), Vec2::new(0, 1), Vec2::new(0, 2)]),
+        PieceShape::new(PieceShapeKind::TetroO, vec![Vec2::new(0, 0), Vec2::new(1, 0), Vec2::new(0, 1), Vec2::new(1, 1)]),
+        PieceShape::new(PieceShapeKind::TetroT, vec![Vec2::new(0, 0), Vec2::new(1, 0), Vec2::new(2, 0), Vec2::new(1, 1)]),
+        PieceShape::new(PieceShapeKind::TetroI, vec![Vec2::new(0, 0), Vec2::new(0, 1), Vec2::new(0, 2), Vec2::new(0, 3)]),
+        PieceShape::new(PieceShapeKind::TetroL, vec![Vec2::new(0, 0), Vec2::new(0, 1), Vec2::new(0, 2), Vec2::new(1, 2)]),
+        PieceShape::new(PieceShapeKind::TetroZ, vec![Vec2::new(0, 0), Vec2::new(1, 0), Vec2::new(1, 1), Vec2::new(2, 1)]),
+        PieceShape::new(PieceShapeKind::PentoL, vec![Vec2::new(0, 0), Vec2::new(0, 1), Vec2::new(0, 2), Vec2::new(0, 3), Vec2::new(1, 3)]),
+        PieceShape::new(PieceShapeKind::PentoT, vec![Vec2::new(0, 0), Vec2::new(1, 0), Vec2::new(2, 0), Vec2::new(1, 1), Vec2::new(1, 2)]),
+        PieceShape::new(PieceShapeKind::PentoV, vec![Vec2::new(0, 0), Vec2::new(0, 1), Vec2::new(0, 2), Vec2::new(1, 2), Vec2::new(2, 2)]),
+        PieceShape::new(PieceShapeKind::PentoS, vec![Vec2::new(1, 0), Vec2::new(2, 0), Vec2::new(3, 0), Vec2::new(0, 1), Vec2::new(1, 1)]),
+        PieceShape::new(PieceShapeKind::PentoZ, vec![Vec2::new(0, 0), Vec2::new(1, 0), Vec2::new(1, 1), Vec2::new(1, 2), Vec2::new(2, 2)]),
+        PieceShape::new(PieceShapeKind::PentoI, vec![Vec2::new(0, 0), Vec2::new(0, 1), Vec2::new(0, 2), Vec2::new(0, 3), Vec2::new(0, 4)]),
+        PieceShape::new(PieceShapeKind::PentoP, vec![Vec2::new(0, 0), Vec2::new(1, 0), Vec2::new(0, 1), Vec2::new(1, 1), Vec2::new(0, 2)]),
+        PieceShape::new(PieceShapeKind::PentoW, vec![Vec2::new(0, 0), Vec2::new(0, 1), Vec2::new(1, 1), Vec2::new(1, 2), Vec2::new(2, 2)]),
+        PieceShape::new(PieceShapeKind::PentoU, vec![Vec2::new(0, 0), Vec2::new(0, 1), Vec2::new(1, 1), Vec2::new(2, 1), Vec2::new(2, 0)]),
+        PieceShape::new(PieceShapeKind::PentoR, vec![Vec2::new(0, 1), Vec2::new(1, 1), Vec2::new(1, 2), Vec2::new(2, 1), Vec2::new(2, 0)]),
+        PieceShape::new(PieceShapeKind::PentoX, vec![Vec2::new(1, 0), Vec2::new(0, 1), Vec2::new(1, 1), Vec2::new(2, 1), Vec2::new(1, 2)]),
+        PieceShape::new(PieceShapeKind::PentoY, vec![Vec2::new(0, 1), Vec2::new(1, 0), Vec2::new(1, 1), Vec2::new(1, 2), Vec2::new(1, 3)])
     ];
 
     pub static ref PIECE_SHAPES_BY_NAME: HashMap<String, PieceShape> = {
         let mut m = HashMap::new();
         for piece in PIECE_SHAPES.iter() {
-            m.insert(piece.name.to_owned(), piece.clone());
+            m.insert(piece.name().to_owned(), piece.clone());
         }
         m
     };
-}
-
-const MAX_SIDE_LENGTH: i32 = 5;
-
-/// An efficient representation of a piece shape's normalized coordinates.
-/// Since every piece shape is less than 5x5 is size, we can represent it
-/// using a 5x5 bit-matrix:
-///
-/// ```text
-///  +---+---+---+---+----+
-///  | 0 | 1 | 2 | 3 |  4 |
-///  +---+---+---+---+----+
-///  | 5 | 6 |            |
-///  +---+---+    ...     |
-///  |                    |
-///  +               +----+
-///  |               | 24 |
-///  +---+---+---+---+----+
-/// ```
-///
-/// These bits are stored in the right-end of of a 32-bit integer.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-struct CoordinateSet {
-    bits: u32
-}
-
-impl CoordinateSet {
-    pub fn new() -> Self {
-        Self { bits: 0 }
-    }
-
-    fn index_of(coordinates: Vec2) -> usize {
-        assert!(coordinates.x >= 0 && coordinates.y >= 0, "Coordinates have to be positive!");
-        assert!(coordinates.y < MAX_SIDE_LENGTH && coordinates.y < MAX_SIDE_LENGTH, "Vec2 are out of bounds!");
-
-        let i = (coordinates.y * MAX_SIDE_LENGTH) + coordinates.x;
-        i as usize
-    }
-
-    /// Inserts a pair of coordinates (inside the 5x5 box) into the set.
-    pub fn insert(&mut self, coordinates: Vec2) {
-        self.bits |= 1 << Self::index_of(coordinates);
-    }
-
-    /// Checks whether the set contains a given pair of coordinates.
-    pub fn contains(&self, coordinates: Vec2) -> bool {
-           coordinates.x >= 0
-        && coordinates.y >= 0
-        && coordinates.x < MAX_SIDE_LENGTH
-        && coordinates.y < MAX_SIDE_LENGTH
-        && ((self.bits >> Self::index_of(coordinates)) & 1) == 1
-    }
-}
-
-impl<I> From<I> for CoordinateSet where I: Iterator<Item=Vec2> {
-    fn from(coordinates: I) -> Self {
-        let mut set = Self::new();
-
-        for coordinates in coordinates {
-            set.insert(coordinates);
-        }
-
-        set
-    }
-}
 
-impl fmt::Display for CoordinateSet {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for y in 0..MAX_SIDE_LENGTH {
-            for x in 0..MAX_SIDE_LENGTH {
-                write!(f, "{}", if self.contains(Vec2::new(x, y)) { '#' } else { '.' })?;
-            }
-            write!(f, "\n")?;
-        }
-        Ok(())
-    }
-}
-
-struct CoordinateSetIterator {
-    bits: u32,
-    i: i32
-}
-
-impl Iterator for CoordinateSetIterator {
-    type Item = Vec2;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        while self.i < (MAX_SIDE_LENGTH * MAX_SIDE_LENGTH) {
-            let i = self.i;
-            let bits = self.bits;
-
-            self.bits >>= 1;
-            self.i += 1;
-
-            if (bits & 1) == 1 {
-                return Some(Vec2::new(i % MAX_SIDE_LENGTH, i / MAX_SIDE_LENGTH));
-            }
-        }
-        
-        None
-    }
+    /// The canonical rotation/flip-combinations of each shape
+    /// (indexed by kind), i.e. the first of those yielding the
+    /// same transformed shape.
+    static ref UNIQUE_TRANSFORMATIONS: Vec<Vec<(Rotation, bool)>> = PIECE_SHAPES.iter()
+        .map(|shape| {
+            let mut seen = Vec::new();
+            shape.transformations()
+                .filter(|&(r, f)| {
+                    let mask = shape.transform(r, f).coordinates;
+                    let is_new = !seen.contains(&mask);
+                    seen.push(mask);
+                    is_new
+                })
+                .collect()
+        })
+        .collect();
 }
 
-impl IntoIterator for CoordinateSet {
-    type Item = Vec2;
-    type IntoIter = CoordinateSetIterator;
+/// Every piece shape fits into a 5x5 box.
+const MAX_SIDE_LENGTH: usize = 5;
 
-    fn into_iter(self) -> Self::IntoIter {
-        CoordinateSetIterator { bits: self.bits, i: 0 }
-    }
-}
+/// The normalized coordinates of a piece shape.
+pub type ShapeMask = BitGrid<MAX_SIDE_LENGTH, MAX_SIDE_LENGTH>;
 
 /// Represents a shape in Blokus. There are 21 different kinds of these.
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct PieceShape {
-    /// The shape's internal name.
-    name: &'static str,
+    /// The shape's kind.
+    kind: PieceShapeKind,
     /// The normalized coordinates that make up the shape.
-    coordinates: CoordinateSet
+    coordinates: ShapeMask
 }
 
 impl PieceShape {
-    fn new(name: &'static str, coordinates: impl IntoIterator<Item=Vec2>) -> Self {
-        Self { name, coordinates: CoordinateSet::from(coordinates.into_iter()) }
+    fn new(kind: PieceShapeKind, coordinates: impl IntoIterator<Item=Vec2>) -> Self {
+        Self { kind, coordinates: coordinates.into_iter().collect() }
     }
 
     /// The piece's (internal) name.
     pub fn name(&self) -> &'static str {
-        self.name
+        self.kind.name()
+    }
+
+    /// The piece's kind.
+    pub fn kind(&self) -> PieceShapeKind {
+        self.kind
+    }
+
+    /// The number of squares the piece covers.
+    pub fn size(&self) -> usize {
+        self.coordinates.count()
     }
 
     /// Checks whether the piece shape contains the provided (normalized) coordinate pair.
@@ -177,29 +102,93 @@ impl PieceShape {
         self.coordinates.into_iter()
     }
 
+    /// The (normalized) coordinates as a bit mask.
+    pub fn mask(&self) -> ShapeMask {
+        self.coordinates
+    }
+
+    /// The cells that can touch another piece of the same color
+    /// by corner, i.e. those with a diagonal neighbor whose two
+    /// adjacent cells are not part of the shape either.
+    pub fn corner_cells(&self) -> impl Iterator<Item=Vec2> + '_ {
+        self.coordinates().filter(move |&c| c.diagonal_neighbors().any(|n| {
+            !self.contains(n)
+                && !self.contains(Vec2::new(n.x, c.y))
+                && !self.contains(Vec2::new(c.x, n.y))
+        }))
+    }
+
     /// Prints a human-readable ASCII-art of the coordinates to a string.
     pub fn ascii_art(&self) -> String {
         format!("{}", self.coordinates)
     }
 
+    /// Parses an ASCII-art in the format emitted by `ascii_art`, i.e. rows
+    /// of `#` (occupied) and `.` (empty), and matches it to a known shape.
+    /// Blank lines and surrounding whitespace are ignored and the art does
+    /// not need to be normalized. Returns the (untransformed) shape along with
+    /// the first rotation/flip-combination that produces the drawn variant.
+    pub fn from_ascii(art: &str) -> SCResult<(PieceShape, Rotation, bool)> {
+        let mut coordinates = Vec::new();
+        for (y, line) in art.lines().map(str::trim).filter(|l| !l.is_empty()).enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                match c {
+                    '#' => coordinates.push(Vec2::new(x as i32, y as i32)),
+                    '.' => {},
+                    _ => return Err(SCError::parse(format!("Unexpected character '{}' in piece shape art", c)))
+                }
+            }
+        }
+
+        if coordinates.is_empty() {
+            return Err(SCError::parse("Piece shape art contains no occupied cells"));
+        }
+        let aligned: Vec<_> = Self::align(coordinates).collect();
+        if !aligned.iter().all(|&c| ShapeMask::is_in_bounds(c)) {
+            return Err(SCError::parse(format!("Piece shape art exceeds {0}x{0} cells", MAX_SIDE_LENGTH)));
+        }
+        let mask: ShapeMask = aligned.into_iter().collect();
+
+        Self::identify_mask(mask)
+            .map(|(kind, r, f)| (kind.shape().clone(), r, f))
+            .ok_or_else(|| SCError::parse(format!("Piece shape art matches no known shape:\n{}", mask)))
+    }
+
+    /// Recovers the kind and the canonical rotation/flip-combination
+    /// producing the given transformed shape. Only the cells are
+    /// considered, the transformed shape's own kind is ignored.
+    pub fn identify(transformed: &PieceShape) -> Option<(PieceShapeKind, Rotation, bool)> {
+        Self::identify_mask(transformed.coordinates)
+    }
+
+    /// Recovers the kind and the canonical rotation/flip-combination
+    /// producing the given normalized cell set.
+    pub fn identify_mask(mask: ShapeMask) -> Option<(PieceShapeKind, Rotation, bool)> {
+        PIECE_SHAPES.iter()
+            .filter(|s| s.size() == mask.count())
+            .find_map(|s| s.transformations()
+                .find(|&(r, f)| s.transform(r, f).coordinates == mask)
+                .map(|(r, f)| (s.kind, r, f)))
+    }
+
     /// Mirrors this shape by negating all coordinates.
     fn mirror(&self) -> Self {
-        Self::new(self.name(), Self::align(self.coordinates().map(|c| -c).collect()))
+        Self::new(self.kind, Self::align(self.coordinates().map(|c| -c).collect()))
     }
 
     /// Turns this piece 90 degrees to the right.
     fn turn_right(&self) -> Self {
-        Self::new(self.name(), Self::align(self.coordinates().map(|c| c.turn_right()).collect()))
+        Self::new(self.kind, Self::align(self.coordinates().map(|c| c.turn_right()).collect()))
     }
 
     /// Turns this piece 90 degrees to the left.
     fn turn_left(&self) -> Self {
-        Self::new(self.name(), Self::align(self.coordinates().map(|c| c.turn_left()).collect()))
+        Self::new(self.kind, Self::align(self.coordinates().map(|c| c.turn_left()).collect()))
     }
 
     /// Flips this piece along the y-axis.
     pub fn flip(&self) -> Self {
-        Self::new(self.name(), Self::align(self.coordinates().map(|c| c.flip()).collect()))
+        Self::new(self.kind, Self::align(self.coordinates().map(|c| c.flip()).collect()))
     }
 
     /// Adjusts the coordinates of this piece shape to be relative
@@ -233,6 +222,13 @@ impl PieceShape {
         ROTATIONS.iter().flat_map(|&r| [true, false].iter().map(move |&f| (r, f)))
     }
 
+    /// Fetches the rotation/flip-combinations yielding distinct
+    /// transformed shapes, e.g. only one for the monomino. These
+    /// are the canonical ones, as picked by `identify`.
+    pub fn unique_transformations(&self) -> impl Iterator<Item=(Rotation, bool)> {
+        UNIQUE_TRANSFORMATIONS[self.kind as usize].iter().copied()
+    }
+
     /// Fetches each variant of this shape.
     pub fn variants(&self) -> impl Iterator<Item=PieceShape> {
         let current = self.clone();
@@ -251,18 +247,107 @@ impl FromStr for PieceShape {
     type Err = SCError;
 
     fn from_str(raw: &str) -> SCResult<Self> {
-        Ok(PIECE_SHAPES_BY_NAME.get(raw).ok_or_else(|| format!("Could not parse shape {}", raw))?.clone())
+        Ok(raw.parse::<PieceShapeKind>()?.shape().clone())
     }
 }
 
 impl fmt::Display for PieceShape {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.name)
+        write!(f, "{}", self.kind)
     }
 }
 
 impl FromXmlNode for PieceShape {
     fn from_node(node: &XmlNode) -> SCResult<Self> {
-        node.content().parse()
+        node.parse_content()
+    }
+}
+
+#[cfg(feature = "json")]
+impl ToJson for PieceShape {
+    fn to_json(&self) -> JsonValue {
+        self.to_string().into()
+    }
+}
+
+#[cfg(feature = "json")]
+impl FromJson for PieceShape {
+    fn from_json(json: &JsonValue) -> SCResult<Self> {
+        json.as_str()?.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{PieceShape, Rotation, Vec2, PIECE_SHAPES, PIECE_SHAPES_BY_NAME};
+
+    #[test]
+    fn test_from_ascii() {
+        for shape in PIECE_SHAPES.iter() {
+            for (r, f) in shape.transformations() {
+                let variant = shape.transform(r, f);
+                let (parsed, pr, pf) = PieceShape::from_ascii(&variant.ascii_art()).unwrap();
+                assert_eq!(parsed, *shape);
+                assert_eq!(parsed.transform(pr, pf), variant);
+            }
+        }
+
+        let (shape, rotation, flip) = PieceShape::from_ascii("
+            ...#
+            ####
+        ").unwrap();
+        assert_eq!(shape.name(), "PENTO_L");
+        let variant = shape.transform(rotation, flip);
+        assert!(variant.contains(Vec2::new(3, 0)) && (0..4).all(|x| variant.contains(Vec2::new(x, 1))));
+        assert_eq!(PieceShape::from_ascii("#\n#\n##").unwrap().0.name(), "TETRO_L");
+        assert_eq!(PieceShape::from_ascii("##\n##").unwrap(), (PIECE_SHAPES_BY_NAME["TETRO_O"].clone(), Rotation::None, true));
+
+        assert!(PieceShape::from_ascii("").is_err());
+        assert!(PieceShape::from_ascii("#.#").is_err());
+        assert!(PieceShape::from_ascii("#x").is_err());
+        assert!(PieceShape::from_ascii("######").is_err());
+    }
+
+    #[test]
+    fn test_identify() {
+        for shape in PIECE_SHAPES.iter() {
+            for (r, f) in shape.transformations() {
+                let (kind, cr, cf) = PieceShape::identify(&shape.transform(r, f)).unwrap();
+                assert_eq!(kind, shape.kind());
+                assert_eq!(shape.transform(cr, cf), shape.transform(r, f));
+            }
+        }
+    }
+
+    #[test]
+    fn test_unique_transformations() {
+        let count = |name: &str| PIECE_SHAPES_BY_NAME[name].unique_transformations().count();
+        assert_eq!(count("MONO"), 1);
+        assert_eq!(count("PENTO_X"), 1);
+        assert_eq!(count("TETRO_O"), 1);
+        assert_eq!(count("DOMINO"), 2);
+        assert_eq!(count("TETRO_T"), 4);
+        assert_eq!(count("TETRO_Z"), 4);
+        assert_eq!(count("PENTO_Y"), 8);
+        for shape in PIECE_SHAPES.iter() {
+            for (r, f) in shape.unique_transformations() {
+                assert_eq!(PieceShape::identify(&shape.transform(r, f)), Some((shape.kind(), r, f)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_corner_cells() {
+        let corner_cells = |name: &str| {
+            let mut cells: Vec<_> = PIECE_SHAPES_BY_NAME[name].corner_cells().collect();
+            cells.sort_by_key(|c| (c.y, c.x));
+            cells
+        };
+
+        assert_eq!(corner_cells("MONO"), vec![Vec2::new(0, 0)]);
+        assert_eq!(corner_cells("TETRO_O").len(), 4);
+        // The center of the X touches no corners
+        assert_eq!(corner_cells("PENTO_X"), vec![Vec2::new(1, 0), Vec2::new(0, 1), Vec2::new(2, 1), Vec2::new(1, 2)]);
+        assert_eq!(corner_cells("TETRO_T"), vec![Vec2::new(0, 0), Vec2::new(2, 0), Vec2::new(1, 1)]);
     }
 }
@@ -0,0 +1,140 @@
+use std::{fmt, str::FromStr};
+use crate::util::{SCError, SCResult};
+use super::{PIECE_SHAPES, PieceShape};
+
+/// The number of different piece shapes.
+pub const PIECE_SHAPE_COUNT: usize = 21;
+
+/// Every kind of piece shape, in the order of `PIECE_SHAPES`.
+pub const PIECE_SHAPE_KINDS: [PieceShapeKind; PIECE_SHAPE_COUNT] = [
+    PieceShapeKind::Mono,
+    PieceShapeKind::Domino,
+    PieceShapeKind::TrioL,
+    PieceShapeKind::TrioI,
+    PieceShapeKind::TetroO,
+    PieceShapeKind::TetroT,
+    PieceShapeKind::TetroI,
+    PieceShapeKind::TetroL,
+    PieceShapeKind::TetroZ,
+    PieceShapeKind::PentoL,
+    PieceShapeKind::PentoT,
+    PieceShapeKind::PentoV,
+    PieceShapeKind::PentoS,
+    PieceShapeKind::PentoZ,
+    PieceShapeKind::PentoI,
+    PieceShapeKind::PentoP,
+    PieceShapeKind::PentoW,
+    PieceShapeKind::PentoU,
+    PieceShapeKind::PentoR,
+    PieceShapeKind::PentoX,
+    PieceShapeKind::PentoY
+];
+
+/// Identifies one of the 21 piece shapes. Converting it into
+/// a `usize` yields an index into `PIECE_SHAPES`, which makes
+/// per-shape tables simple arrays.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PieceShapeKind {
+    Mono,
+    Domino,
+    TrioL,
+    TrioI,
+    TetroO,
+    TetroT,
+    TetroI,
+    TetroL,
+    TetroZ,
+    PentoL,
+    PentoT,
+    PentoV,
+    PentoS,
+    PentoZ,
+    PentoI,
+    PentoP,
+    PentoW,
+    PentoU,
+    PentoR,
+    PentoX,
+    PentoY
+}
+
+impl PieceShapeKind {
+    /// The kind's (internal) name, as used by the protocol.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Mono => "MONO",
+            Self::Domino => "DOMINO",
+            Self::TrioL => "TRIO_L",
+            Self::TrioI => "TRIO_I",
+            Self::TetroO => "TETRO_O",
+            Self::TetroT => "TETRO_T",
+            Self::TetroI => "TETRO_I",
+            Self::TetroL => "TETRO_L",
+            Self::TetroZ => "TETRO_Z",
+            Self::PentoL => "PENTO_L",
+            Self::PentoT => "PENTO_T",
+            Self::PentoV => "PENTO_V",
+            Self::PentoS => "PENTO_S",
+            Self::PentoZ => "PENTO_Z",
+            Self::PentoI => "PENTO_I",
+            Self::PentoP => "PENTO_P",
+            Self::PentoW => "PENTO_W",
+            Self::PentoU => "PENTO_U",
+            Self::PentoR => "PENTO_R",
+            Self::PentoX => "PENTO_X",
+            Self::PentoY => "PENTO_Y"
+        }
+    }
+
+    /// The (normalized) shape of this kind.
+    pub fn shape(self) -> &'static PieceShape {
+        &PIECE_SHAPES[usize::from(self)]
+    }
+}
+
+impl From<PieceShapeKind> for usize {
+    fn from(kind: PieceShapeKind) -> Self {
+        kind as usize
+    }
+}
+
+impl From<usize> for PieceShapeKind {
+    /// Fetches the kind with the given index. Panics if
+    /// the index is not less than `PIECE_SHAPE_COUNT`.
+    fn from(index: usize) -> Self {
+        PIECE_SHAPE_KINDS[index]
+    }
+}
+
+impl FromStr for PieceShapeKind {
+    type Err = SCError;
+
+    fn from_str(raw: &str) -> SCResult<Self> {
+        PIECE_SHAPE_KINDS.iter().copied().find(|kind| kind.name() == raw).ok_or_else(|| SCError::parse(format!("Could not parse shape {}", raw)))
+    }
+}
+
+impl fmt::Display for PieceShapeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{PIECE_SHAPES, PIECE_SHAPE_KINDS};
+    use super::PieceShapeKind;
+
+    #[test]
+    fn test_piece_shape_kinds() {
+        for (i, &kind) in PIECE_SHAPE_KINDS.iter().enumerate() {
+            assert_eq!(usize::from(kind), i);
+            assert_eq!(PieceShapeKind::from(i), kind);
+            assert_eq!(kind.name().parse::<PieceShapeKind>().unwrap(), kind);
+            assert_eq!(PIECE_SHAPES[i].kind(), kind);
+        }
+        assert_eq!(PieceShapeKind::PentoY.shape().size(), 5);
+        assert_eq!(PieceShapeKind::Mono.shape().size(), 1);
+        assert!("HEXO_Q".parse::<PieceShapeKind>().is_err());
+    }
+}
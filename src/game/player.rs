@@ -1,4 +1,6 @@
 use crate::util::{SCResult, FromXmlNode, XmlNode};
+#[cfg(feature = "json")]
+use crate::util::{FromJson, ToJson, JsonValue};
 use super::Team;
 
 /// Metadata about a player.
@@ -11,8 +13,36 @@ pub struct Player {
 impl FromXmlNode for Player {
     fn from_node(node: &XmlNode) -> SCResult<Self> {
         Ok(Self {
-            team: Team::from_node(node.child_by_name("color")?)?,
+            team: node.parse_child("color")?,
             display_name: node.attribute("displayName")?.to_owned()
         })
     }
 }
+
+impl From<Player> for XmlNode {
+    /// Serializes the player as a `<player>` node, which usually
+    /// has to be renamed to e.g. `first` or `winner`.
+    fn from(player: Player) -> Self {
+        XmlNode::new("player")
+            .attribute("displayName", player.display_name)
+            .child(XmlNode::new("color").attribute("class", "team").content(player.team))
+            .build()
+    }
+}
+
+#[cfg(feature = "json")]
+impl ToJson for Player {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object([("team", self.team.to_json()), ("display_name", self.display_name.as_str().into())])
+    }
+}
+
+#[cfg(feature = "json")]
+impl FromJson for Player {
+    fn from_json(json: &JsonValue) -> SCResult<Self> {
+        Ok(Self {
+            team: Team::from_json(json.get("team")?)?,
+            display_name: json.get("display_name")?.as_str()?.to_owned()
+        })
+    }
+}
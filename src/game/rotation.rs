@@ -1,10 +1,12 @@
 use std::{convert::TryFrom, fmt, str::FromStr};
 use crate::util::{SCError, SCResult};
+#[cfg(feature = "json")]
+use crate::util::{FromJson, ToJson, JsonValue};
 
 pub const ROTATIONS: [Rotation; 4] = [Rotation::None, Rotation::Left, Rotation::Right, Rotation::Mirror];
 
 /// Describes how a piece shape is rotated.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Rotation {
     None,
     Right,
@@ -21,7 +23,7 @@ impl TryFrom<i32> for Rotation {
             1 => Ok(Self::Right),
             2 => Ok(Self::Mirror),
             3 => Ok(Self::Left),
-            _ => Err(format!("Could not parse rotation {}", n).into())
+            _ => Err(SCError::parse(format!("Could not parse rotation {}", n)))
         }
     }
 }
@@ -46,7 +48,7 @@ impl FromStr for Rotation {
             "RIGHT" => Ok(Rotation::Right),
             "MIRROR" => Ok(Rotation::Mirror),
             "LEFT" => Ok(Rotation::Left),
-            _ => Err(format!("Could not parse rotation {}", raw).into())
+            _ => Err(SCError::parse(format!("Could not parse rotation {}", raw)))
         }
     }
 }
@@ -61,3 +63,17 @@ impl fmt::Display for Rotation {
         }
     }
 }
+
+#[cfg(feature = "json")]
+impl ToJson for Rotation {
+    fn to_json(&self) -> JsonValue {
+        self.to_string().into()
+    }
+}
+
+#[cfg(feature = "json")]
+impl FromJson for Rotation {
+    fn from_json(json: &JsonValue) -> SCResult<Self> {
+        json.as_str()?.parse()
+    }
+}
@@ -0,0 +1,119 @@
+use super::{BOARD_SIZE, Move, Piece, Vec2};
+
+pub const SYMMETRIES: [Symmetry; 8] = [
+    Symmetry::Identity,
+    Symmetry::Rotate90,
+    Symmetry::Rotate180,
+    Symmetry::Rotate270,
+    Symmetry::FlipX,
+    Symmetry::FlipY,
+    Symmetry::Transpose,
+    Symmetry::AntiTranspose
+];
+
+/// One of the 8 symmetries of the square board, i.e. a
+/// rotation (clockwise) or a reflection. Since the colors
+/// may start in any corner, positions related by a symmetry
+/// are equivalent (with every color keeping its role).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirrors the x-coordinates.
+    FlipX,
+    /// Mirrors the y-coordinates.
+    FlipY,
+    /// Swaps x and y.
+    Transpose,
+    /// Reflects along the diagonal from the top right to the bottom left.
+    AntiTranspose
+}
+
+impl Symmetry {
+    /// The symmetry undoing this one.
+    pub fn inverse(self) -> Self {
+        match self {
+            Self::Rotate90 => Self::Rotate270,
+            Self::Rotate270 => Self::Rotate90,
+            s => s
+        }
+    }
+
+    /// Applies the symmetry to a position on the board.
+    pub fn apply(self, position: Vec2) -> Vec2 {
+        self.apply_in(BOARD_SIZE, position)
+    }
+
+    /// Applies the symmetry to a position on a board of the given size.
+    pub fn apply_in(self, size: usize, position: Vec2) -> Vec2 {
+        let n = size as i32 - 1;
+        let Vec2 { x, y } = position;
+        match self {
+            Self::Identity => Vec2::new(x, y),
+            Self::Rotate90 => Vec2::new(n - y, x),
+            Self::Rotate180 => Vec2::new(n - x, n - y),
+            Self::Rotate270 => Vec2::new(y, n - x),
+            Self::FlipX => Vec2::new(n - x, y),
+            Self::FlipY => Vec2::new(x, n - y),
+            Self::Transpose => Vec2::new(y, x),
+            Self::AntiTranspose => Vec2::new(n - y, n - x)
+        }
+    }
+
+    /// Applies the symmetry to a piece, i.e. finds the rotation,
+    /// flip and position covering the transformed coordinates.
+    pub fn apply_to_piece(self, piece: &Piece) -> Piece {
+        self.apply_to_piece_in(BOARD_SIZE, piece)
+    }
+
+    /// Applies the symmetry to a piece on a board of the given size.
+    pub fn apply_to_piece_in(self, size: usize, piece: &Piece) -> Piece {
+        let coordinates: Vec<_> = piece.coordinates().map(|c| self.apply_in(size, c)).collect();
+        let position = coordinates.iter().fold(Vec2::both(size as i32), |m, &c| m.min(c));
+        let mask = coordinates.iter().map(|&c| c - position).collect();
+        let (rotation, is_flipped) = piece.kind.transformations()
+            .find(|&(r, f)| piece.kind.transform(r, f).mask() == mask)
+            .expect("Every symmetric image of a shape is one of its transformations");
+        Piece { kind: piece.kind.clone(), rotation, is_flipped, color: piece.color, position }
+    }
+
+    /// Applies the symmetry to a move.
+    pub fn apply_to_move(self, game_move: &Move) -> Move {
+        self.apply_to_move_in(BOARD_SIZE, game_move)
+    }
+
+    /// Applies the symmetry to a move on a board of the given size.
+    pub fn apply_to_move_in(self, size: usize, game_move: &Move) -> Move {
+        match game_move {
+            Move::Set { piece } => Move::Set { piece: self.apply_to_piece_in(size, piece) },
+            Move::Skip { color } => Move::Skip { color: *color }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use crate::game::{GameState, Move, PIECE_SHAPES_BY_NAME, Vec2};
+    use super::SYMMETRIES;
+
+    #[test]
+    fn test_symmetries() {
+        let state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_W"].clone());
+        for symmetry in SYMMETRIES {
+            let position = Vec2::new(3, 7);
+            assert_eq!(symmetry.inverse().apply(symmetry.apply(position)), position);
+
+            // Symmetric images of legal moves are legal
+            for game_move in state.possible_moves() {
+                let image = symmetry.apply_to_move(&game_move);
+                assert!(state.validate_move(&image).is_ok(), "{:?} is not legal", image);
+                if let (Move::Set { piece }, Move::Set { piece: restored }) = (&game_move, symmetry.inverse().apply_to_move(&image)) {
+                    assert_eq!(restored.coordinates().collect::<HashSet<_>>(), piece.coordinates().collect::<HashSet<_>>());
+                }
+            }
+        }
+    }
+}
@@ -1,9 +1,13 @@
 use std::{fmt, str::FromStr};
 use crate::util::{SCError, SCResult, FromXmlNode, XmlNode};
+#[cfg(feature = "json")]
+use crate::util::{FromJson, ToJson, JsonValue};
+use super::Color;
 
 /// A player's team.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Team {
+    #[default]
     None,
     One,
     Two
@@ -31,11 +35,14 @@ impl Team {
             Self::Two => Self::One
         }
     }
-}
 
-impl Default for Team {
-    fn default() -> Self {
-        Self::None
+    /// Fetches the colors controlled by the team.
+    pub fn colors(self) -> &'static [Color] {
+        match self {
+            Self::None => &[],
+            Self::One => &[Color::Blue, Color::Red],
+            Self::Two => &[Color::Yellow, Color::Green]
+        }
     }
 }
 
@@ -47,7 +54,7 @@ impl FromStr for Team {
             "NONE" => Ok(Self::None),
             "ONE" => Ok(Self::One),
             "TWO" => Ok(Self::Two),
-            _ => Err(format!("Could not parse team {}", raw).into())
+            _ => Err(SCError::parse(format!("Could not parse team {}", raw)))
         }
     }
 }
@@ -64,6 +71,20 @@ impl fmt::Display for Team {
 
 impl FromXmlNode for Team {
     fn from_node(node: &XmlNode) -> SCResult<Self> {
-        node.content().parse()
+        node.parse_content()
+    }
+}
+
+#[cfg(feature = "json")]
+impl ToJson for Team {
+    fn to_json(&self) -> JsonValue {
+        self.to_string().into()
+    }
+}
+
+#[cfg(feature = "json")]
+impl FromJson for Team {
+    fn from_json(json: &JsonValue) -> SCResult<Self> {
+        json.as_str()?.parse()
     }
 }
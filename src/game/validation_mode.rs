@@ -0,0 +1,23 @@
+/// Determines when moves are validated before being performed.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ValidationMode {
+    /// Always validates moves.
+    #[default]
+    Always,
+    /// Only validates moves in debug builds.
+    DebugOnly,
+    /// Never validates moves, e.g. for performance-critical search loops
+    /// that only perform moves from the move generator.
+    Never
+}
+
+impl ValidationMode {
+    /// Whether moves should be validated in the current build.
+    pub fn is_enabled(self) -> bool {
+        match self {
+            Self::Always => true,
+            Self::DebugOnly => cfg!(debug_assertions),
+            Self::Never => false
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use std::{fmt, ops::{Add, Neg, Sub}};
 use crate::util::{SCResult, FromXmlNode, XmlNode};
+#[cfg(feature = "json")]
+use crate::util::{FromJson, ToJson, JsonValue};
 
 /// A vector in 2D-space. The x-axis
 /// usually points to the right while
@@ -12,7 +14,7 @@ pub struct Vec2 {
 
 impl Vec2 {
     /// Creates a new vector.
-    pub fn new(x: i32, y: i32) -> Self {
+    pub const fn new(x: i32, y: i32) -> Self {
         Self { x, y }
     }
 
@@ -50,6 +52,16 @@ impl Vec2 {
     pub fn max(self, other: Vec2) -> Self {
         Self::new(self.x.max(other.x), self.y.max(other.y))
     }
+
+    /// Fetches the 4 points sharing an edge with this one.
+    pub fn orthogonal_neighbors(self) -> impl Iterator<Item=Vec2> {
+        [(1, 0), (0, 1), (-1, 0), (0, -1)].into_iter().map(move |(dx, dy)| self + Self::new(dx, dy))
+    }
+
+    /// Fetches the 4 points sharing only a corner with this one.
+    pub fn diagonal_neighbors(self) -> impl Iterator<Item=Vec2> {
+        [(1, 1), (-1, 1), (1, -1), (-1, -1)].into_iter().map(move |(dx, dy)| self + Self::new(dx, dy))
+    }
 }
 
 pub struct Vec2Iterator {
@@ -119,8 +131,35 @@ impl Sub for Vec2 {
 impl FromXmlNode for Vec2 {
     fn from_node(node: &XmlNode) -> SCResult<Self> {
         Ok(Self {
-            x: node.attribute("x")?.parse()?,
-            y: node.attribute("y")?.parse()?
+            x: node.parse_attribute("x")?,
+            y: node.parse_attribute("y")?
+        })
+    }
+}
+
+impl From<Vec2> for XmlNode {
+    /// Serializes the vector as a `<position>` node, as used by pieces.
+    fn from(vec: Vec2) -> Self {
+        XmlNode::new("position")
+            .attribute("x", vec.x)
+            .attribute("y", vec.y)
+            .build()
+    }
+}
+
+#[cfg(feature = "json")]
+impl ToJson for Vec2 {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object([("x", self.x.into()), ("y", self.y.into())])
+    }
+}
+
+#[cfg(feature = "json")]
+impl FromJson for Vec2 {
+    fn from_json(json: &JsonValue) -> SCResult<Self> {
+        Ok(Self {
+            x: json.get("x")?.as_i32()?,
+            y: json.get("y")?.as_i32()?
         })
     }
 }
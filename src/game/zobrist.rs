@@ -0,0 +1,81 @@
+use super::{COLORS, Color, Move, Piece, SizedGameState, Vec2};
+
+/// The offset separating the turn keys from the field keys.
+const TURN_KEY_OFFSET: u64 = 1 << 32;
+
+/// The stride of the field keys, i.e. the largest board size.
+const MAX_BOARD_SIZE: usize = 32;
+
+/// Zobrist keys for incrementally hashing game states.
+///
+/// The keys are derived deterministically from their index
+/// (using SplitMix64), so hashes are stable across runs.
+/// A state's hash is the XOR of the keys of its occupied
+/// fields and of its turn, which lets a search update it
+/// in constant time per placed field.
+pub struct Zobrist;
+
+impl Zobrist {
+    /// Fetches the key of a field occupied by the given color.
+    pub fn field(color: Color, position: Vec2) -> u64 {
+        match COLORS.iter().position(|&c| c == color) {
+            Some(index) => splitmix64(((index * MAX_BOARD_SIZE + position.y as usize) * MAX_BOARD_SIZE + position.x as usize) as u64),
+            None => 0
+        }
+    }
+
+    /// Fetches the key of the given turn.
+    pub fn turn(turn: u32) -> u64 {
+        splitmix64(TURN_KEY_OFFSET + turn as u64)
+    }
+
+    /// Fetches the combined key of the fields covered by the given piece.
+    pub fn piece(piece: &Piece) -> u64 {
+        piece.coordinates().fold(0, |key, position| key ^ Self::field(piece.color, position))
+    }
+
+    /// Updates the hash of a state at the given turn to the
+    /// hash of the state after the given (valid) move.
+    pub fn after_move(hash: u64, turn: u32, game_move: &Move) -> u64 {
+        let hash = hash ^ Self::turn(turn) ^ Self::turn(turn + 1);
+        match game_move {
+            Move::Set { piece } => hash ^ Self::piece(piece),
+            Move::Skip { .. } => hash
+        }
+    }
+
+    /// Computes the hash of the given state from scratch.
+    pub fn hash<const N: usize>(state: &SizedGameState<N>) -> u64 {
+        COLORS.iter()
+            .flat_map(|&color| state.board.fields_of_color(color).map(move |position| Self::field(color, position)))
+            .fold(Self::turn(state.turn), |hash, key| hash ^ key)
+    }
+}
+
+/// The SplitMix64 finalizer, mapping indices to well-distributed keys.
+fn splitmix64(index: u64) -> u64 {
+    let mut z = index.wrapping_add(1).wrapping_mul(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{GameState, PIECE_SHAPES_BY_NAME};
+    use super::Zobrist;
+
+    #[test]
+    fn test_incremental_hash() {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_T"].clone());
+        let mut hash = state.zobrist_hash();
+        for i in 0..16 {
+            let moves: Vec<_> = state.possible_moves().collect();
+            let game_move = moves[(i * 3) % moves.len()].clone();
+            hash = Zobrist::after_move(hash, state.turn, &game_move);
+            state.perform_move(game_move).unwrap();
+            assert_eq!(hash, state.zobrist_hash());
+        }
+        assert_ne!(hash, GameState::new(PIECE_SHAPES_BY_NAME["PENTO_T"].clone()).zobrist_hash());
+    }
+}
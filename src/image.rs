@@ -0,0 +1,269 @@
+//! Renders boards to PNG or SVG images, optionally with a
+//! heatmap overlay (e.g. the values of an evaluation function),
+//! for reports or for visually debugging heuristics.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use crate::eval::Heuristic;
+use crate::game::{BOARD_SIZE, Board, Color, GameState, Move, Team, Vec2};
+use crate::util::{SCResult, adler32, crc32};
+
+type Rgb = [u8; 3];
+
+const EMPTY: Rgb = [238, 238, 236];
+const GRID: Rgb = [186, 189, 182];
+const HEAT: Rgb = [245, 121, 0];
+/// The opacity of the overlay on the hottest cells.
+const MAX_HEAT_ALPHA: f64 = 0.8;
+
+/// The fill of a color's cells.
+fn fill(color: Color) -> Rgb {
+    match color {
+        Color::Blue => [52, 101, 164],
+        Color::Yellow => [237, 212, 0],
+        Color::Red => [204, 0, 0],
+        Color::Green => [78, 154, 6],
+        Color::None => EMPTY
+    }
+}
+
+fn blend(base: Rgb, top: Rgb, alpha: f64) -> Rgb {
+    let mix = |i: usize| (base[i] as f64 * (1.0 - alpha) + top[i] as f64 * alpha).round() as u8;
+    [mix(0), mix(1), mix(2)]
+}
+
+/// Renders boards as images with square cells separated by grid lines.
+#[derive(Debug, Clone)]
+pub struct BoardRenderer {
+    cell_size: usize,
+    /// The overlay values by cell, row by row.
+    overlay: Option<Vec<Option<f64>>>
+}
+
+impl BoardRenderer {
+    /// Creates a renderer with 16 pixel cells and no overlay.
+    pub fn new() -> Self {
+        Self { cell_size: 16, overlay: None }
+    }
+
+    /// Sets the size of a cell in pixels, including its grid line.
+    pub fn cell_size(mut self, cell_size: usize) -> Self {
+        self.cell_size = cell_size.max(2);
+        self
+    }
+
+    /// Overlays a heatmap of the given values, which are scaled
+    /// from transparent (lowest) to opaque (highest). Cells
+    /// without a value are left as they are.
+    pub fn overlay(mut self, values: impl Fn(Vec2) -> Option<f64>) -> Self {
+        self.overlay = Some(Board::positions().map(values).collect());
+        self
+    }
+
+    /// Overlays the best value of the heuristic after any of the
+    /// current color's possible placements covering a cell.
+    pub fn heuristic_overlay(self, state: &GameState, team: Team, heuristic: &impl Heuristic) -> Self {
+        let mut best = vec![None::<f64>; BOARD_SIZE * BOARD_SIZE];
+        for game_move in state.possible_moves() {
+            let piece = match &game_move {
+                Move::Set { piece } => piece.clone(),
+                Move::Skip { .. } => continue
+            };
+            let value = match state.after_move(game_move) {
+                Ok(next) => heuristic.evaluate(&next, team),
+                Err(_) => continue
+            };
+            for cell in piece.coordinates() {
+                let slot = &mut best[cell.y as usize * BOARD_SIZE + cell.x as usize];
+                *slot = Some(slot.map_or(value, |v| v.max(value)));
+            }
+        }
+        self.overlay(|p| best[p.y as usize * BOARD_SIZE + p.x as usize])
+    }
+
+    /// Computes the color of every cell, row by row.
+    fn cell_colors(&self, board: &Board) -> Vec<Rgb> {
+        let mut colors: Vec<_> = Board::positions().map(|p| fill(board.get(p))).collect();
+        if let Some(values) = &self.overlay {
+            let known = values.iter().flatten().copied().filter(|v| v.is_finite());
+            let (min, max) = known.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| (min.min(v), max.max(v)));
+            for (color, value) in colors.iter_mut().zip(values) {
+                if let Some(value) = value.filter(|v| v.is_finite()) {
+                    let heat = if max > min { (value - min) / (max - min) } else { 1.0 };
+                    *color = blend(*color, HEAT, heat * MAX_HEAT_ALPHA);
+                }
+            }
+        }
+        colors
+    }
+
+    /// The width and height of the image in pixels.
+    pub fn image_size(&self) -> usize {
+        BOARD_SIZE * self.cell_size + 1
+    }
+
+    /// Renders the board as RGB pixels, row by row.
+    pub fn render_pixels(&self, board: &Board) -> Vec<Rgb> {
+        let colors = self.cell_colors(board);
+        let size = self.image_size();
+        let mut pixels = Vec::with_capacity(size * size);
+        for y in 0..size {
+            for x in 0..size {
+                let on_grid = x % self.cell_size == 0 || y % self.cell_size == 0;
+                pixels.push(if on_grid { GRID } else { colors[(y / self.cell_size) * BOARD_SIZE + x / self.cell_size] });
+            }
+        }
+        pixels
+    }
+
+    /// Renders the board as a PNG image.
+    pub fn render_png(&self, board: &Board) -> Vec<u8> {
+        let size = self.image_size() as u32;
+        let mut raw = Vec::new();
+        for row in self.render_pixels(board).chunks(size as usize) {
+            raw.push(0); // No filter
+            raw.extend(row.iter().flatten());
+        }
+
+        let mut header = Vec::new();
+        header.extend_from_slice(&size.to_be_bytes());
+        header.extend_from_slice(&size.to_be_bytes());
+        header.extend_from_slice(&[8, 2, 0, 0, 0]); // 8-bit RGB, no interlacing
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        write_chunk(&mut png, b"IHDR", &header);
+        write_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        write_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    /// Renders the board as an SVG image.
+    pub fn render_svg(&self, board: &Board) -> String {
+        let size = self.image_size();
+        let cell = self.cell_size;
+        let mut svg = format!(r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{0}" viewBox="0 0 {0} {0}">"#, size);
+        write!(svg, r#"<rect width="{0}" height="{0}" fill="{1}"/>"#, size, hex(GRID)).unwrap();
+        for (p, color) in Board::positions().zip(self.cell_colors(board)) {
+            let (x, y) = (p.x as usize * cell + 1, p.y as usize * cell + 1);
+            write!(svg, r#"<rect x="{}" y="{}" width="{2}" height="{2}" fill="{3}"/>"#, x, y, cell - 1, hex(color)).unwrap();
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Saves the board as an SVG image if the path ends
+    /// with `.svg` and as a PNG image otherwise.
+    pub fn save(&self, board: &Board, path: impl AsRef<Path>) -> SCResult<()> {
+        let path = path.as_ref();
+        if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("svg")) {
+            fs::write(path, self.render_svg(board))?;
+        } else {
+            fs::write(path, self.render_png(board))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for BoardRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+/// Appends a PNG chunk with its length and checksum.
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Wraps the data in a zlib stream of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut stream = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xFFFF).peekable();
+    if blocks.peek().is_none() {
+        stream.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        let len = block.len() as u16;
+        stream.push(blocks.peek().is_none() as u8); // Whether this is the final block
+        stream.extend_from_slice(&len.to_le_bytes());
+        stream.extend_from_slice(&(!len).to_le_bytes());
+        stream.extend_from_slice(block);
+    }
+    stream.extend_from_slice(&adler32(data).to_be_bytes());
+    stream
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::eval::PointDifference;
+    use crate::game::{BOARD_SIZE, Color, GameState, PIECE_SHAPES_BY_NAME};
+    use crate::util::{adler32, crc32};
+    use super::{BoardRenderer, EMPTY, GRID, fill};
+
+    /// Extracts the raw scanlines from a PNG written by the renderer.
+    fn decode(png: &[u8]) -> Vec<u8> {
+        let mut pos = 8;
+        let mut raw = Vec::new();
+        while pos < png.len() {
+            let len = u32::from_be_bytes(png[pos..pos + 4].try_into().unwrap()) as usize;
+            let chunk = &png[pos + 4..pos + 8 + len];
+            assert_eq!(crc32(chunk), u32::from_be_bytes(png[pos + 8 + len..pos + 12 + len].try_into().unwrap()));
+            if &chunk[..4] == b"IDAT" {
+                let mut block = &chunk[6..chunk.len() - 4];
+                while !block.is_empty() {
+                    let block_len = u16::from_le_bytes([block[1], block[2]]) as usize;
+                    raw.extend_from_slice(&block[5..5 + block_len]);
+                    block = &block[5 + block_len..];
+                }
+                assert_eq!(adler32(&raw).to_be_bytes(), chunk[chunk.len() - 4..]);
+            }
+            pos += 12 + len;
+        }
+        raw
+    }
+
+    #[test]
+    fn test_png() {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["MONO"].clone());
+        state.perform_move(state.possible_moves().next().unwrap()).unwrap();
+        let renderer = BoardRenderer::new().cell_size(10);
+        let size = renderer.image_size();
+        assert_eq!(size, BOARD_SIZE * 10 + 1);
+
+        let png = renderer.render_png(&state.board);
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        let raw = decode(&png);
+        assert_eq!(raw.len(), size * (1 + 3 * size));
+        let pixel = |x: usize, y: usize| raw[y * (1 + 3 * size) + 1 + 3 * x..][..3].to_vec();
+        assert_eq!(pixel(0, 0), GRID);
+        assert_eq!(pixel(5, 5), fill(Color::Blue));
+        assert_eq!(pixel(15, 5), EMPTY);
+    }
+
+    #[test]
+    fn test_overlay_and_svg() {
+        let state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_L"].clone());
+        let renderer = BoardRenderer::new().overlay(|p| Some(p.x as f64).filter(|_| p.y == 0));
+        let pixels = renderer.render_pixels(&state.board);
+        let size = renderer.image_size();
+        assert_eq!(pixels[5 * size + 5], EMPTY);
+        assert_ne!(pixels[5 * size + size - 5], EMPTY);
+        assert_eq!(pixels[size * 20 + 5], EMPTY);
+
+        let heuristic = BoardRenderer::new().heuristic_overlay(&state, state.current_team(), &PointDifference);
+        assert!(heuristic.render_pixels(&state.board).iter().any(|&p| p != EMPTY && p != GRID));
+        let svg = heuristic.render_svg(&state.board);
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<rect").count(), BOARD_SIZE * BOARD_SIZE + 1);
+    }
+}
@@ -1,5 +1,29 @@
 pub mod logic;
+pub mod admin;
+pub mod book;
+#[cfg(feature = "broadcast")]
+pub mod broadcast;
 pub mod client;
+mod bot_info;
+mod diagnostics;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod game;
+pub mod eval;
+#[cfg(feature = "image")]
+pub mod image;
+pub mod metrics;
+pub mod ml;
 pub mod protocol;
+pub mod rules;
+pub mod search;
+pub mod sim;
+pub mod telemetry;
+pub mod testing;
+pub mod tournament;
+#[cfg(feature = "tui")]
+pub mod tui;
 pub mod util;
+
+pub use bot_info::*;
+pub use diagnostics::*;
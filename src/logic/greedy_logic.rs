@@ -0,0 +1,48 @@
+use log::info;
+use crate::{BotInfo, client::SCClientDelegate, game::{GameState, Team, Move}};
+
+/// A deterministic game logic that always places the
+/// largest legal piece, taking the first generated
+/// move among pieces of equal size.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GreedyLogic;
+
+impl SCClientDelegate for GreedyLogic {
+    fn bot_info(&self) -> BotInfo {
+        BotInfo::new("GreedyLogic", env!("CARGO_PKG_VERSION")).settings_summary("largest piece first")
+    }
+
+    fn request_move(&mut self, state: &GameState, _my_team: Team) -> Move {
+        let mut best: Option<(usize, Move)> = None;
+        for game_move in state.possible_moves() {
+            let size = match &game_move {
+                Move::Set { piece } => piece.kind.size(),
+                Move::Skip { .. } => 0
+            };
+            if best.as_ref().is_none_or(|&(best_size, _)| size > best_size) {
+                best = Some((size, game_move));
+            }
+        }
+        let (size, game_move) = best.expect("No move found");
+        info!("Chose {:?} (size {})", game_move, size);
+        game_move
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{client::SCClientDelegate, game::{GameState, Move, PIECE_SHAPES_BY_NAME, Team}};
+    use super::GreedyLogic;
+
+    #[test]
+    fn test_places_largest_piece() {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["MONO"].clone());
+        for _ in 0..4 {
+            let game_move = GreedyLogic.request_move(&state, state.current_team());
+            state.perform_move(game_move).unwrap();
+        }
+        let game_move = GreedyLogic.request_move(&state, state.current_team());
+        assert!(matches!(game_move, Move::Set { piece } if piece.kind.size() == 5));
+        assert_eq!(GreedyLogic.request_move(&state, Team::One), GreedyLogic.request_move(&state, Team::One));
+    }
+}
@@ -0,0 +1,10 @@
+//! Sample game logics that serve as a starting point
+//! and as reproducible baselines for custom bots.
+
+mod greedy_logic;
+mod random_logic;
+mod timed_search_logic;
+
+pub use greedy_logic::*;
+pub use random_logic::*;
+pub use timed_search_logic::*;
@@ -0,0 +1,74 @@
+use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
+use log::{info, debug};
+use crate::{BotInfo, client::SCClientDelegate, game::{GameState, Team, Move}};
+
+/// A game logic that picks uniformly random moves.
+///
+/// Given an explicit seed, the logic plays
+/// the same moves in the same positions, which
+/// makes it usable as a reproducible baseline.
+pub struct RandomLogic {
+    rng: StdRng,
+    seed: Option<u64>
+}
+
+impl RandomLogic {
+    /// Creates a logic seeded from the system's entropy source.
+    pub fn new() -> Self {
+        Self { rng: StdRng::from_entropy(), seed: None }
+    }
+
+    /// Creates a logic with the given seed.
+    pub fn with_seed(seed: u64) -> Self {
+        Self { rng: StdRng::seed_from_u64(seed), seed: Some(seed) }
+    }
+
+    /// The seed, if one was given explicitly.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+}
+
+impl Default for RandomLogic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SCClientDelegate for RandomLogic {
+    fn bot_info(&self) -> BotInfo {
+        let summary = match self.seed {
+            Some(seed) => format!("random moves, seed {}", seed),
+            None => "random moves".to_owned()
+        };
+        BotInfo::new("RandomLogic", env!("CARGO_PKG_VERSION")).settings_summary(summary)
+    }
+
+    fn request_move(&mut self, state: &GameState, _my_team: Team) -> Move {
+        // Implement custom game logic here!
+        let moves: Vec<_> = state.possible_moves().collect();
+        let game_move = moves.choose(&mut self.rng).cloned().expect("No move found");
+        info!("Chose {:?} from {} moves", game_move, moves.len());
+        game_move
+    }
+    
+    fn on_update_state(&mut self, state: &GameState) {
+        debug!("New board:\n{:?}", state.board);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{game::PIECE_SHAPES_BY_NAME, sim::GameRunner};
+    use super::RandomLogic;
+
+    #[test]
+    fn test_seeded_games_are_reproducible() {
+        let play = |seed_one, seed_two| {
+            let runner = GameRunner::new(RandomLogic::with_seed(seed_one), RandomLogic::with_seed(seed_two), PIECE_SHAPES_BY_NAME["PENTO_V"].clone());
+            runner.run().unwrap().0.moves
+        };
+        assert_eq!(play(1, 2), play(1, 2));
+        assert_ne!(play(1, 2), play(3, 4));
+    }
+}
@@ -0,0 +1,120 @@
+use std::thread;
+use std::time::{Duration, Instant};
+use log::{debug, info};
+use crate::{BotInfo, client::SCClientDelegate, eval::{Heuristic, PointDifference}, game::{GameState, Move, Team}};
+use crate::search::{AlphaBeta, MoveOrdering, SearchHandle, SearchResult, TranspositionTable};
+
+/// The maximum depth of the iterative deepening, which
+/// is usually not reached within the time budget.
+const MAX_DEPTH: usize = 64;
+
+/// The memory used by the transposition table.
+const TABLE_BYTES: usize = 32 << 20;
+
+/// The heuristic used by `TimedSearchLogic::new`.
+pub type DefaultHeuristic = fn(&GameState, Team) -> f64;
+
+/// Evaluates a state by the point difference plus half the
+/// difference in corners the teams can still place pieces at.
+pub fn default_heuristic(state: &GameState, team: Team) -> f64 {
+    let corners = |team: Team| team.colors().iter().map(|&c| state.corner_count(c) as f64).sum::<f64>();
+    PointDifference.evaluate(state, team) + 0.5 * (corners(team) - corners(team.opponent()))
+}
+
+/// A game logic that searches the best move using iterative
+/// deepening alpha-beta with move ordering and a transposition
+/// table, playing the result of the deepest iteration completed
+/// within the time budget.
+pub struct TimedSearchLogic<H> {
+    search: AlphaBeta<H>,
+    time_budget: Duration
+}
+
+impl TimedSearchLogic<DefaultHeuristic> {
+    /// Creates a logic using the default heuristic.
+    pub fn new() -> Self {
+        Self::with_heuristic(default_heuristic)
+    }
+}
+
+impl<H> TimedSearchLogic<H> where H: Heuristic {
+    /// Creates a logic using the given heuristic.
+    pub fn with_heuristic(heuristic: H) -> Self {
+        Self::with_search(AlphaBeta::new(heuristic)
+            .depth(MAX_DEPTH)
+            .ordering(MoveOrdering::new())
+            .transposition_table(TranspositionTable::with_memory_limit(TABLE_BYTES)))
+    }
+
+    /// Creates a logic using the given search, whose
+    /// depth is the maximum depth of the iterative deepening.
+    pub fn with_search(search: AlphaBeta<H>) -> Self {
+        // Leave some headroom below the client's default hard limit
+        Self { search, time_budget: Duration::from_millis(1500) }
+    }
+
+    /// Sets the time after which the search is canceled.
+    pub fn time_budget(mut self, time_budget: Duration) -> Self {
+        self.time_budget = time_budget;
+        self
+    }
+}
+
+impl Default for TimedSearchLogic<DefaultHeuristic> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<H> SCClientDelegate for TimedSearchLogic<H> where H: Heuristic {
+    fn bot_info(&self) -> BotInfo {
+        BotInfo::new("TimedSearchLogic", env!("CARGO_PKG_VERSION"))
+            .settings_summary(format!("iterative deepening alpha-beta, {} ms per move", self.time_budget.as_millis()))
+    }
+
+    fn request_move(&mut self, state: &GameState, my_team: Team) -> Move {
+        self.request_search(state, my_team).best_move
+    }
+
+    fn request_search(&mut self, state: &GameState, _my_team: Team) -> SearchResult {
+        let start = Instant::now();
+        let handle = SearchHandle::new();
+        let watchdog = handle.clone();
+        let time_budget = self.time_budget;
+        // Returns early once the search has finished
+        thread::spawn(move || watchdog.wait_timeout(time_budget));
+
+        let result = self.search.iterative_deepening(state, &handle, |result| debug!("Searched {}", result));
+        let result = result.unwrap_or_else(|| {
+            // Not even the first iteration completed in time
+            let game_move = state.possible_moves().next().unwrap_or(Move::Skip { color: state.current_color() });
+            SearchResult::unsearched(game_move, start.elapsed())
+        });
+        info!("Chose {} ({})", result.best_move, result);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+    use crate::{client::SCClientDelegate, game::{GameState, PIECE_SHAPES_BY_NAME}};
+    use super::TimedSearchLogic;
+
+    #[test]
+    fn test_plays_within_budget() {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_L"].clone());
+        let mut logic = TimedSearchLogic::new().time_budget(Duration::from_millis(100));
+        for _ in 0..6 {
+            let start = Instant::now();
+            let result = logic.request_search(&state, state.current_team());
+            assert!(start.elapsed() < Duration::from_secs(1));
+            assert!(result.depth >= 1);
+            state.perform_move(result.best_move).unwrap();
+        }
+
+        let mut impatient = TimedSearchLogic::new().time_budget(Duration::ZERO);
+        let game_move = impatient.request_move(&state, state.current_team());
+        assert!(state.validate_move(&game_move).is_ok());
+    }
+}
@@ -1,10 +1,17 @@
 use std::env;
+use std::fs::File;
 use std::str::FromStr;
+use std::time::Duration;
 use simplelog::{SimpleLogger, Config};
 use log::LevelFilter;
 use getopts::Options;
-use socha_client_2021::client::{SCClient, DebugMode};
-use socha_client_2021::logic::OwnGameLogic;
+use socha_client_2021::book::{BookLogic, OpeningBook};
+use socha_client_2021::client::{SCClient, SCClientDelegate, DebugMode, ReconnectPolicy, TimeoutPolicy, XmlTranscript, DEFAULT_HOST, DEFAULT_PORT};
+use socha_client_2021::logic::RandomLogic;
+#[cfg(feature = "broadcast")]
+use socha_client_2021::broadcast::{BroadcastLogic, StateBroadcaster};
+#[cfg(feature = "tui")]
+use socha_client_2021::tui::TuiViewer;
 
 fn print_usage(program: &str, options: Options) {
     let brief = format!("Usage: {} [options]", program);
@@ -18,9 +25,20 @@ fn main() {
     options.optopt("h", "host", "The game server's host address", "HOST");
     options.optopt("p", "port", "The game server's port", "PORT");
     options.optopt("r", "reservation", "A game reservation", "RESERVATION");
+    options.optopt("R", "retries", "How often to try reconnecting after losing the connection (5 by default)", "RETRIES");
+    options.optopt("T", "timeout", "The time in milliseconds after which a fallback move is sent (1800 by default, 0 disables it)", "MILLIS");
+    options.optopt("t", "transcript", "Writes the raw XML traffic to the given file", "FILE");
+    #[cfg(feature = "json")]
+    options.optopt("b", "book", "Plays moves from the given opening book (JSON) where possible", "FILE");
+    options.optopt("s", "seed", "Seeds the random move choice to make games reproducible", "SEED");
     options.optopt("l", "level", "Optionally provides a custom log level ('Info' by default)", "LEVEL");
     options.optflag("d", "debug-reader", "Reads incoming XML messages from the console for debugging");
     options.optflag("D", "debug-writer", "Prints incoming XML messages to the console for debugging");
+    #[cfg(feature = "tui")]
+    options.optflag("u", "tui", "Renders the live game in the terminal");
+    #[cfg(feature = "broadcast")]
+    options.optopt("B", "broadcast", "Serves the game state as JSON on the given address, e.g. 127.0.0.1:8080", "ADDRESS");
+    options.optflag("V", "diagnostics", "Prints version and diagnostic info for bug reports");
     options.optflag("H", "help", "Prints usage info");
     
     let parsed_args = options.parse(&args[1..]).expect("Could not parse arguments!");
@@ -28,10 +46,18 @@ fn main() {
         print_usage(&args[0], options);
         return;
     }
+    if parsed_args.opt_present("diagnostics") {
+        println!("{}", socha_client_2021::diagnostics());
+        return;
+    }
     
-    let host = parsed_args.opt_str("host").unwrap_or("localhost".to_owned());
-    let port = parsed_args.opt_str("port").unwrap_or("13050".to_owned()).parse::<u16>().expect("Invalid port.");
+    let host = parsed_args.opt_str("host").unwrap_or(DEFAULT_HOST.to_owned());
+    let port = parsed_args.opt_str("port").map(|p| p.parse::<u16>().expect("Invalid port.")).unwrap_or(DEFAULT_PORT);
     let reservation = parsed_args.opt_str("reservation");
+    let retries = parsed_args.opt_str("retries").map(|r| r.parse::<usize>().expect("Invalid retry count."));
+    let timeout = parsed_args.opt_str("timeout").map(|t| t.parse::<u64>().expect("Invalid timeout."));
+    let transcript = parsed_args.opt_str("transcript");
+    let seed = parsed_args.opt_str("seed").map(|s| s.parse::<u64>().expect("Invalid seed."));
     let level = parsed_args.opt_str("level").unwrap_or("Info".to_owned());
     
     // Setup logging
@@ -42,7 +68,44 @@ fn main() {
         debug_reader: parsed_args.opt_present("debug-reader"),
         debug_writer: parsed_args.opt_present("debug-writer")
     };
-    let client = SCClient::new(OwnGameLogic, debug_mode);
+    let mut reconnect_policy = ReconnectPolicy::default();
+    if let Some(retries) = retries {
+        reconnect_policy.max_retries = retries;
+    }
+    let mut timeout_policy = TimeoutPolicy::default();
+    if let Some(timeout) = timeout {
+        timeout_policy.hard_limit = Some(Duration::from_millis(timeout)).filter(|t| !t.is_zero());
+    }
+    #[allow(unused_mut)]
+    let mut book = OpeningBook::default();
+    #[cfg(feature = "json")]
+    if let Some(path) = parsed_args.opt_str("book") {
+        book = OpeningBook::load(path).expect("Could not load opening book.");
+    }
+    let logic = BookLogic::new(book, seed.map(RandomLogic::with_seed).unwrap_or_default());
+    #[allow(unused_mut)]
+    let mut delegate: Box<dyn SCClientDelegate + Send> = Box::new(logic);
+    #[cfg(feature = "tui")]
+    if parsed_args.opt_present("tui") {
+        delegate = Box::new(TuiViewer::new(delegate));
+    }
+    #[cfg(feature = "broadcast")]
+    if let Some(address) = parsed_args.opt_str("broadcast") {
+        let broadcaster = StateBroadcaster::bind(address).expect("Could not start broadcasting.");
+        delegate = Box::new(BroadcastLogic::new(broadcaster, delegate));
+    }
+    let mut builder = SCClient::builder(delegate)
+        .host(host)
+        .port(port)
+        .debug_mode(debug_mode)
+        .reconnect_policy(reconnect_policy)
+        .timeout_policy(timeout_policy);
+    if let Some(reservation) = reservation {
+        builder = builder.reservation(reservation);
+    }
+    if let Some(path) = transcript {
+        builder = builder.interceptor(XmlTranscript::new(File::create(path).expect("Could not create transcript file.")));
+    }
     
-    client.run(&host, port, reservation.as_ref().map(|s| s.as_str())).expect("Error while running client.");
+    builder.build().run().expect("Error while running client.");
 }
@@ -0,0 +1,25 @@
+/// An approximation of a color's number of legal set moves
+/// together with a (roughly 95%) confidence interval.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MobilityEstimate {
+    /// The estimated number of legal set moves.
+    pub estimate: f64,
+    /// The lower bound of the confidence interval.
+    pub lower: f64,
+    /// The upper bound of the confidence interval.
+    pub upper: f64,
+    /// The number of samples the estimate is based on.
+    pub samples: usize
+}
+
+impl MobilityEstimate {
+    /// An exact estimate, e.g. for colors that cannot move at all.
+    pub fn exact(value: f64) -> Self {
+        Self { estimate: value, lower: value, upper: value, samples: 0 }
+    }
+
+    /// Checks whether the given value lies within the confidence interval.
+    pub fn contains(&self, value: f64) -> bool {
+        self.lower <= value && value <= self.upper
+    }
+}
@@ -0,0 +1,7 @@
+//! Fast (approximate) metrics on game states for use in evaluation functions.
+
+mod mobility_estimate;
+mod sampled_mobility;
+
+pub use mobility_estimate::*;
+pub use sampled_mobility::*;
@@ -0,0 +1,94 @@
+use rand::{Rng, seq::SliceRandom};
+use crate::game::{Color, GameState, Piece, PieceShape, Vec2};
+use super::MobilityEstimate;
+
+/// The z-score of the confidence interval.
+const Z_SCORE: f64 = 1.96;
+
+/// Estimates the number of legal set moves of the given color
+/// (counted like `possible_moves` does) without generating them.
+///
+/// Each sample picks a random anchor (a free cell diagonally
+/// adjacent to the color, or a board corner in the first round),
+/// a random shape, distinct transformation and corner cell of
+/// that shape to put onto the anchor (only corner cells can cover
+/// anchors). Valid placements are weighted by the inverse of
+/// their sampling probability and the number of anchors they
+/// cover, which makes the estimate unbiased.
+pub fn sampled_mobility(state: &GameState, color: Color, samples: usize, rng: &mut impl Rng) -> MobilityEstimate {
+    let anchors = anchors(state, color);
+    let shapes: Vec<PieceShape> = if state.is_first_move_of(color) {
+        vec![state.start_piece.clone()]
+    } else {
+        state.undeployed_shapes_of_color(color).cloned().collect()
+    };
+
+    if anchors.is_empty() || shapes.is_empty() {
+        return MobilityEstimate::exact(0.0);
+    }
+    if samples == 0 {
+        return MobilityEstimate { estimate: 0.0, lower: 0.0, upper: f64::INFINITY, samples };
+    }
+
+    let mut sum = 0.0;
+    let mut sum_of_squares = 0.0;
+
+    for _ in 0..samples {
+        let anchor = *anchors.choose(rng).unwrap();
+        let kind = shapes.choose(rng).unwrap();
+        let transformations: Vec<_> = kind.unique_transformations().collect();
+        let (rotation, is_flipped) = *transformations.choose(rng).unwrap();
+        let cells: Vec<_> = kind.transform(rotation, is_flipped).corner_cells().collect();
+        let cell = *cells.choose(rng).unwrap();
+        let piece = Piece::anchored(kind.clone(), rotation, is_flipped, color, anchor, cell);
+
+        let value = if state.validate_set_move(&piece).is_ok() {
+            let covered_anchors = piece.coordinates().filter(|c| anchors.contains(c)).count();
+            let inverse_probability = (anchors.len() * shapes.len() * transformations.len() * cells.len()) as f64;
+            inverse_probability / covered_anchors as f64
+        } else {
+            0.0
+        };
+
+        sum += value;
+        sum_of_squares += value * value;
+    }
+
+    let n = samples as f64;
+    let mean = sum / n;
+    let variance = (sum_of_squares / n - mean * mean).max(0.0);
+    let margin = Z_SCORE * (variance / n).sqrt();
+
+    MobilityEstimate { estimate: mean, lower: (mean - margin).max(0.0), upper: mean + margin, samples }
+}
+
+/// Finds the cells a new piece of the given color has to cover.
+fn anchors(state: &GameState, color: Color) -> Vec<Vec2> {
+    state.anchors(color).into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+    use crate::game::{Color, GameState, PIECE_SHAPES_BY_NAME};
+    use super::sampled_mobility;
+
+    #[test]
+    fn test_sampled_mobility() {
+        let mut rng = StdRng::seed_from_u64(2021);
+
+        // Every sample of the monomino hits one of 4 placements
+        let state = GameState::new(PIECE_SHAPES_BY_NAME["MONO"].clone());
+        let estimate = sampled_mobility(&state, Color::Blue, 100, &mut rng);
+        assert_eq!(estimate.estimate, 4.0);
+        assert!(estimate.contains(4.0));
+
+        let state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_Y"].clone());
+        let exact = state.possible_moves().count() as f64;
+        let estimate = sampled_mobility(&state, Color::Blue, 2000, &mut rng);
+        assert!(estimate.contains(exact), "{:?} does not contain {}", estimate, exact);
+
+        let state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_X"].clone());
+        assert_eq!(sampled_mobility(&state, Color::Blue, 100, &mut rng).estimate, 0.0);
+    }
+}
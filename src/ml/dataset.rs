@@ -0,0 +1,113 @@
+use std::io::{self, Write};
+use crate::game::{BOARD_SIZE, COLOR_COUNT, GameState, PIECE_SHAPE_COUNT, Team};
+use crate::sim::GameRecord;
+use super::{Features, NpyArray, SCALARS_LEN, write_npz};
+
+/// A collection of encoded states with labels,
+/// e.g. extracted from replays for training.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Dataset {
+    pub samples: Vec<Features>,
+    pub labels: Vec<f32>
+}
+
+impl Dataset {
+    /// Creates an empty dataset.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a sample.
+    pub fn push(&mut self, features: Features, label: f32) {
+        self.samples.push(features);
+        self.labels.push(label);
+    }
+
+    /// The number of samples.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Checks whether the dataset holds no samples.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Adds every state of the given game, labeled with the outcome
+    /// from the perspective of the team to move: 1 for a win,
+    /// -1 for a loss and 0 for a draw.
+    pub fn add_record(&mut self, record: &GameRecord) {
+        let winner = record.winner();
+        let label = |state: &GameState| match winner {
+            Some(team) if team == state.current_team() => 1.0,
+            Some(_) => -1.0,
+            None => 0.0
+        };
+
+        let mut state = record.initial_state.clone();
+        for game_move in &record.moves {
+            self.push(Features::extract(&state), label(&state));
+            if state.perform_move_unchecked(game_move.clone()).is_err() {
+                break;
+            }
+        }
+        if state.current_team() != Team::None {
+            self.push(Features::extract(&state), label(&state));
+        }
+    }
+
+    /// Writes the dataset as an `.npz` archive containing the arrays
+    /// `planes` (`[n, 4, 20, 20]`), `undeployed` (`[n, 4, 21]`),
+    /// `scalars` (`[n, 6]`) and `labels` (`[n]`), as documented
+    /// on `Features`.
+    pub fn write_npz(&self, writer: impl Write) -> io::Result<()> {
+        let n = self.len();
+        let concat = |f: fn(&Features) -> &Vec<f32>| self.samples.iter().flat_map(f).copied().collect();
+        let planes = NpyArray::new(vec![n, COLOR_COUNT, BOARD_SIZE, BOARD_SIZE], concat(|s| &s.planes));
+        let undeployed = NpyArray::new(vec![n, COLOR_COUNT, PIECE_SHAPE_COUNT], concat(|s| &s.undeployed));
+        let scalars = NpyArray::new(vec![n, SCALARS_LEN], concat(|s| &s.scalars));
+        let labels = NpyArray::new(vec![n], self.labels.clone());
+        write_npz(writer, &[("planes", &planes), ("undeployed", &undeployed), ("scalars", &scalars), ("labels", &labels)])
+    }
+
+    /// Writes the dataset as CSV with a header, one flattened
+    /// sample per row and the label in the last column.
+    pub fn write_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "{},label", Features::names().join(","))?;
+        for (features, label) in self.samples.iter().zip(&self.labels) {
+            for value in features.to_vec() {
+                write!(writer, "{},", value)?;
+            }
+            writeln!(writer, "{}", label)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::PIECE_SHAPES_BY_NAME;
+    use crate::logic::RandomLogic;
+    use crate::ml::FEATURES_LEN;
+    use crate::sim::GameRunner;
+    use super::Dataset;
+
+    #[test]
+    fn test_export() {
+        let runner = GameRunner::new(RandomLogic::with_seed(1), RandomLogic::with_seed(2), PIECE_SHAPES_BY_NAME["PENTO_V"].clone());
+        let (record, _, _) = runner.run().unwrap();
+        let mut dataset = Dataset::new();
+        dataset.add_record(&record);
+        assert_eq!(dataset.len(), record.moves.len() + 1);
+
+        let mut csv = Vec::new();
+        dataset.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), dataset.len() + 1);
+        assert!(csv.lines().all(|line| line.split(',').count() == FEATURES_LEN + 1));
+
+        let mut npz = Vec::new();
+        dataset.write_npz(&mut npz).unwrap();
+        assert!(npz.len() > dataset.len() * FEATURES_LEN * 4);
+    }
+}
@@ -0,0 +1,15 @@
+use super::Features;
+
+/// A trained model predicting the value of an encoded state
+/// from the perspective of the team to move, e.g. in `[-1, 1]`
+/// when trained on the labels of a `Dataset`.
+pub trait EvalModel {
+    /// Predicts the value of the given features.
+    fn predict(&self, features: &Features) -> f32;
+}
+
+impl<M> EvalModel for Box<M> where M: EvalModel + ?Sized {
+    fn predict(&self, features: &Features) -> f32 {
+        (**self).predict(features)
+    }
+}
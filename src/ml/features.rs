@@ -0,0 +1,103 @@
+use crate::game::{COLOR_COUNT, COLORS, GameState, PIECE_SHAPE_COUNT, PIECE_SHAPE_KINDS};
+use crate::rules::{BOARD_SIZE, MAX_ROUND};
+
+/// The number of values in the board planes.
+pub const PLANES_LEN: usize = COLOR_COUNT * BOARD_SIZE * BOARD_SIZE;
+/// The number of values in the undeployed piece vectors.
+pub const UNDEPLOYED_LEN: usize = COLOR_COUNT * PIECE_SHAPE_COUNT;
+/// The number of scalar values.
+pub const SCALARS_LEN: usize = 2 + COLOR_COUNT;
+/// The number of values in a flattened feature vector.
+pub const FEATURES_LEN: usize = PLANES_LEN + UNDEPLOYED_LEN + SCALARS_LEN;
+
+/// A game state encoded as fixed-size numeric tensors.
+/// Colors are always ordered as in `COLORS` and pieces as
+/// in `PIECE_SHAPE_KINDS`. The layout is:
+///
+/// - `planes`: shape `[4, 20, 20]`, indexed by color, y and x,
+///   1 where the field is occupied by the color and 0 otherwise.
+/// - `undeployed`: shape `[4, 21]`, indexed by color and piece,
+///   1 where the piece has not been placed yet and 0 otherwise.
+/// - `scalars`: shape `[6]`, holding the turn divided by the
+///   maximum turn (100), the round divided by the maximum round
+///   (25) and a one-hot encoding of the current color.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Features {
+    pub planes: Vec<f32>,
+    pub undeployed: Vec<f32>,
+    pub scalars: Vec<f32>
+}
+
+impl Features {
+    /// Encodes the given state.
+    pub fn extract(state: &GameState) -> Self {
+        let mut planes = vec![0.0; PLANES_LEN];
+        let mut undeployed = vec![0.0; UNDEPLOYED_LEN];
+        for (i, &color) in COLORS.iter().enumerate() {
+            for position in state.board.fields_of_color(color) {
+                planes[(i * BOARD_SIZE + position.y as usize) * BOARD_SIZE + position.x as usize] = 1.0;
+            }
+            for shape in state.undeployed_shapes_of_color(color) {
+                undeployed[i * PIECE_SHAPE_COUNT + usize::from(shape.kind())] = 1.0;
+            }
+        }
+
+        let mut scalars = vec![0.0; SCALARS_LEN];
+        scalars[0] = state.turn as f32 / (MAX_ROUND as usize * COLOR_COUNT) as f32;
+        scalars[1] = state.round as f32 / MAX_ROUND as f32;
+        if let Some(i) = COLORS.iter().position(|&c| c == state.current_color()) {
+            scalars[2 + i] = 1.0;
+        }
+
+        Self { planes, undeployed, scalars }
+    }
+
+    /// Concatenates the tensors into a single vector of length `FEATURES_LEN`.
+    pub fn to_vec(&self) -> Vec<f32> {
+        self.planes.iter().chain(&self.undeployed).chain(&self.scalars).copied().collect()
+    }
+
+    /// Names the values of the flattened vector, e.g. for CSV headers.
+    pub fn names() -> Vec<String> {
+        let mut names = Vec::with_capacity(FEATURES_LEN);
+        for color in COLORS {
+            for y in 0..BOARD_SIZE {
+                for x in 0..BOARD_SIZE {
+                    names.push(format!("{}_{}_{}", color, x, y));
+                }
+            }
+        }
+        for color in COLORS {
+            for kind in PIECE_SHAPE_KINDS {
+                names.push(format!("{}_{}", color, kind));
+            }
+        }
+        names.push("turn".to_owned());
+        names.push("round".to_owned());
+        names.extend(COLORS.iter().map(|color| format!("current_{}", color)));
+        names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{GameState, PIECE_SHAPES_BY_NAME, PIECE_SHAPE_COUNT};
+    use super::{FEATURES_LEN, Features, PLANES_LEN};
+
+    #[test]
+    fn test_layout() {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["DOMINO"].clone());
+        let features = Features::extract(&state);
+        assert_eq!(features.to_vec().len(), FEATURES_LEN);
+        assert_eq!(Features::names().len(), FEATURES_LEN);
+        assert!(features.planes.iter().all(|&v| v == 0.0));
+        assert_eq!(features.scalars, vec![0.0, 1.0 / 25.0, 1.0, 0.0, 0.0, 0.0]);
+
+        state.perform_move(state.possible_moves().next().unwrap()).unwrap();
+        let features = Features::extract(&state);
+        assert_eq!(features.planes[..PLANES_LEN / 4].iter().sum::<f32>(), 2.0);
+        assert_eq!(features.undeployed[..PIECE_SHAPE_COUNT].iter().sum::<f32>(), 20.0);
+        assert_eq!(features.undeployed[1], 0.0);
+        assert_eq!(features.scalars[3], 1.0);
+    }
+}
@@ -0,0 +1,64 @@
+#[cfg(feature = "json")]
+use std::{fs, path::Path};
+use crate::util::{SCError, SCResult};
+#[cfg(feature = "json")]
+use crate::util::{FromJson, ToJson, JsonValue};
+use super::{EvalModel, FEATURES_LEN, Features};
+
+/// A linear model over the flattened features,
+/// i.e. the dot product with a weight vector plus a bias.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearModel {
+    pub weights: Vec<f32>,
+    pub bias: f32
+}
+
+impl LinearModel {
+    /// Creates a model with the given weights, one per flattened feature.
+    pub fn new(weights: Vec<f32>, bias: f32) -> SCResult<Self> {
+        if weights.len() != FEATURES_LEN {
+            return Err(SCError::parse(format!("Expected {} weights, but got {}", FEATURES_LEN, weights.len())));
+        }
+        Ok(Self { weights, bias })
+    }
+
+    /// Loads the model from a JSON weights file of the
+    /// form `{"weights": [...], "bias": 0.0}`.
+    #[cfg(feature = "json")]
+    pub fn load(path: impl AsRef<Path>) -> SCResult<Self> {
+        Self::from_json(&fs::read_to_string(path)?.parse()?)
+    }
+
+    /// Saves the model to the given JSON weights file.
+    #[cfg(feature = "json")]
+    pub fn save(&self, path: impl AsRef<Path>) -> SCResult<()> {
+        fs::write(path, self.to_json().to_string())?;
+        Ok(())
+    }
+}
+
+impl EvalModel for LinearModel {
+    fn predict(&self, features: &Features) -> f32 {
+        self.bias + features.to_vec().iter().zip(&self.weights).map(|(x, w)| x * w).sum::<f32>()
+    }
+}
+
+#[cfg(feature = "json")]
+impl ToJson for LinearModel {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object([
+            ("weights", JsonValue::array(self.weights.iter().map(|&w| (w as f64).into()))),
+            ("bias", (self.bias as f64).into())
+        ])
+    }
+}
+
+#[cfg(feature = "json")]
+impl FromJson for LinearModel {
+    fn from_json(json: &JsonValue) -> SCResult<Self> {
+        let weights = json.get("weights")?.as_array()?.iter()
+            .map(|w| Ok(w.as_f64()? as f32))
+            .collect::<SCResult<_>>()?;
+        Self::new(weights, json.get("bias")?.as_f64()? as f32)
+    }
+}
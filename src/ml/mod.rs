@@ -0,0 +1,16 @@
+//! Feature extraction and dataset export for training
+//! machine-learned evaluation functions.
+
+mod dataset;
+mod eval_model;
+mod features;
+mod linear_model;
+mod model_heuristic;
+mod npz;
+
+pub use dataset::*;
+pub use eval_model::*;
+pub use features::*;
+pub use linear_model::*;
+pub use model_heuristic::*;
+pub use npz::*;
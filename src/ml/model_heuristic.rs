@@ -0,0 +1,61 @@
+use crate::eval::Heuristic;
+use crate::game::{GameState, Team};
+use super::{EvalModel, Features};
+
+/// Evaluates states using a trained model, so it can be
+/// plugged into the searches like any other heuristic.
+pub struct ModelHeuristic<M> {
+    model: M
+}
+
+impl<M> ModelHeuristic<M> where M: EvalModel {
+    /// Creates a heuristic backed by the given model.
+    pub fn new(model: M) -> Self {
+        Self { model }
+    }
+
+    /// Fetches the model.
+    pub fn model(&self) -> &M {
+        &self.model
+    }
+}
+
+impl<M> Heuristic for ModelHeuristic<M> where M: EvalModel {
+    fn evaluate(&self, state: &GameState, team: Team) -> f64 {
+        // The model predicts from the perspective of the team to move
+        let value = self.model.predict(&Features::extract(state)) as f64;
+        if state.current_team() == team { value } else { -value }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::eval::Heuristic;
+    use crate::game::{GameState, PIECE_SHAPES_BY_NAME, Team};
+    use crate::ml::{FEATURES_LEN, LinearModel, PLANES_LEN};
+    use crate::search::AlphaBeta;
+    #[cfg(feature = "json")]
+    use crate::util::{FromJson, ToJson};
+    use super::ModelHeuristic;
+
+    #[test]
+    fn test_linear_model() {
+        assert!(LinearModel::new(vec![1.0], 0.0).is_err());
+
+        // Count the fields occupied by blue
+        let mut weights = vec![0.0; FEATURES_LEN];
+        weights[..PLANES_LEN / 4].iter_mut().for_each(|w| *w = 1.0);
+        let model = LinearModel::new(weights, 0.5).unwrap();
+        #[cfg(feature = "json")]
+        assert_eq!(LinearModel::from_json(&model.to_json()).unwrap(), model);
+
+        let heuristic = ModelHeuristic::new(model);
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["TETRO_O"].clone());
+        assert_eq!(heuristic.evaluate(&state, Team::One), 0.5);
+        state.perform_move(state.possible_moves().next().unwrap()).unwrap();
+        assert_eq!(heuristic.evaluate(&state, Team::Two), 4.5);
+        assert_eq!(heuristic.evaluate(&state, Team::One), -4.5);
+
+        assert!(AlphaBeta::new(heuristic).best_move(&state).is_some());
+    }
+}
@@ -0,0 +1,122 @@
+use std::io::{self, Write};
+use crate::util::crc32;
+
+/// An array of 32-bit floats with a shape, as stored in NumPy's `.npy` format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NpyArray {
+    pub shape: Vec<usize>,
+    pub data: Vec<f32>
+}
+
+impl NpyArray {
+    /// Creates an array with the given shape.
+    pub fn new(shape: Vec<usize>, data: Vec<f32>) -> Self {
+        assert_eq!(shape.iter().product::<usize>(), data.len(), "Shape {:?} does not match {} values", shape, data.len());
+        Self { shape, data }
+    }
+
+    /// Encodes the array in the `.npy` format (version 1.0).
+    pub fn to_npy(&self) -> Vec<u8> {
+        let shape = match self.shape.as_slice() {
+            [n] => format!("({},)", n),
+            dims => format!("({})", dims.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(", "))
+        };
+        let mut header = format!("{{'descr': '<f4', 'fortran_order': False, 'shape': {}, }}", shape);
+        // The magic string, version and header length take 10 bytes and
+        // the header (including the trailing newline) is padded to 64 bytes
+        while (10 + header.len() + 1) % 64 != 0 {
+            header.push(' ');
+        }
+        header.push('\n');
+
+        let mut bytes = Vec::with_capacity(10 + header.len() + 4 * self.data.len());
+        bytes.extend_from_slice(b"\x93NUMPY\x01\x00");
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        for value in &self.data {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes
+    }
+}
+
+/// Writes the named arrays as an uncompressed `.npz` archive,
+/// loadable using `numpy.load`.
+pub fn write_npz(mut writer: impl Write, arrays: &[(&str, &NpyArray)]) -> io::Result<()> {
+    let mut central_directory = Vec::new();
+    let mut offset = 0u32;
+
+    for (name, array) in arrays {
+        let name = format!("{}.npy", name);
+        let data = array.to_npy();
+        let crc = crc32(&data);
+        let size = data.len() as u32;
+
+        // Local file header, storing the data without compression
+        let mut header = Vec::new();
+        header.extend_from_slice(&0x04034b50u32.to_le_bytes());
+        header.extend_from_slice(&zip_entry_fields(crc, size, &name));
+        header.extend_from_slice(name.as_bytes());
+        writer.write_all(&header)?;
+        writer.write_all(&data)?;
+
+        central_directory.extend_from_slice(&0x02014b50u32.to_le_bytes());
+        central_directory.extend_from_slice(&20u16.to_le_bytes()); // Version made by
+        central_directory.extend_from_slice(&zip_entry_fields(crc, size, &name));
+        central_directory.extend_from_slice(&[0; 6]); // Comment length, disk number and internal attributes
+        central_directory.extend_from_slice(&0u32.to_le_bytes()); // External attributes
+        central_directory.extend_from_slice(&offset.to_le_bytes());
+        central_directory.extend_from_slice(name.as_bytes());
+
+        offset += header.len() as u32 + size;
+    }
+
+    writer.write_all(&central_directory)?;
+    let mut end = Vec::new();
+    end.extend_from_slice(&0x06054b50u32.to_le_bytes());
+    end.extend_from_slice(&[0; 4]); // Disk numbers
+    end.extend_from_slice(&(arrays.len() as u16).to_le_bytes());
+    end.extend_from_slice(&(arrays.len() as u16).to_le_bytes());
+    end.extend_from_slice(&(central_directory.len() as u32).to_le_bytes());
+    end.extend_from_slice(&offset.to_le_bytes());
+    end.extend_from_slice(&0u16.to_le_bytes()); // Comment length
+    writer.write_all(&end)
+}
+
+/// Encodes the fields shared by local and central zip headers.
+fn zip_entry_fields(crc: u32, size: u32, name: &str) -> Vec<u8> {
+    let mut fields = Vec::new();
+    fields.extend_from_slice(&20u16.to_le_bytes()); // Version needed
+    fields.extend_from_slice(&0u16.to_le_bytes()); // Flags
+    fields.extend_from_slice(&0u16.to_le_bytes()); // Stored
+    fields.extend_from_slice(&0u16.to_le_bytes()); // Time
+    fields.extend_from_slice(&0x21u16.to_le_bytes()); // Date (1980-01-01)
+    fields.extend_from_slice(&crc.to_le_bytes());
+    fields.extend_from_slice(&size.to_le_bytes());
+    fields.extend_from_slice(&size.to_le_bytes());
+    fields.extend_from_slice(&(name.len() as u16).to_le_bytes());
+    fields.extend_from_slice(&0u16.to_le_bytes()); // Extra field length
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NpyArray, write_npz};
+
+    #[test]
+    fn test_npz() {
+        let array = NpyArray::new(vec![2, 3], vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0]);
+        let npy = array.to_npy();
+        assert!(npy.starts_with(b"\x93NUMPY\x01\x00"));
+        let header_len = u16::from_le_bytes([npy[8], npy[9]]) as usize;
+        assert_eq!((10 + header_len) % 64, 0);
+        assert!(String::from_utf8_lossy(&npy[10..10 + header_len]).contains("'shape': (2, 3)"));
+        assert_eq!(npy.len(), 10 + header_len + 6 * 4);
+
+        let mut npz = Vec::new();
+        write_npz(&mut npz, &[("a", &array), ("b", &NpyArray::new(vec![1], vec![7.0]))]).unwrap();
+        assert!(npz.starts_with(&[b'P', b'K', 3, 4]));
+        assert_eq!(&npz[npz.len() - 22..npz.len() - 18], &[b'P', b'K', 5, 6]);
+        assert_eq!(npz[npz.len() - 14], 2);
+    }
+}
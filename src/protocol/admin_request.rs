@@ -0,0 +1,93 @@
+use crate::util::{SCError, SCResult, FromXmlNode, XmlNode};
+use super::SlotDescriptor;
+
+/// A request that only administrators (e.g. tournament
+/// managers or observers) may send to the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdminRequest {
+    /// Authenticates as an administrator, which is
+    /// required before sending any other request.
+    Authenticate { password: String },
+    /// Creates a room with reserved slots. The server
+    /// responds with `Prepared`.
+    Prepare { game_type: String, pause: bool, slots: Vec<SlotDescriptor> },
+    /// Subscribes to the messages of a room. The server
+    /// responds with `Observed`.
+    Observe { room_id: String },
+    /// Pauses or resumes a game.
+    Pause { room_id: String, pause: bool },
+    /// Performs a single move in a paused game.
+    Step { room_id: String },
+    /// Aborts a game.
+    Cancel { room_id: String }
+}
+
+impl FromXmlNode for AdminRequest {
+    fn from_node(node: &XmlNode) -> SCResult<Self> {
+        let room_id = || node.attribute("roomId").map(|s| s.to_owned());
+        match node.name() {
+            "authenticate" => Ok(Self::Authenticate { password: node.attribute("password")?.to_owned() }),
+            "prepare" => Ok(Self::Prepare {
+                game_type: node.attribute("gameType")?.to_owned(),
+                pause: node.parse_attribute("pause")?,
+                slots: node.parse_children("slot")?
+            }),
+            "observe" => Ok(Self::Observe { room_id: room_id()? }),
+            "pause" => Ok(Self::Pause { room_id: room_id()?, pause: node.parse_attribute("pause")? }),
+            "step" => Ok(Self::Step { room_id: room_id()? }),
+            "cancel" => Ok(Self::Cancel { room_id: room_id()? }),
+            name => Err(SCError::protocol(format!("Unrecognized admin request: <{}>", name)))
+        }
+    }
+}
+
+impl From<AdminRequest> for XmlNode {
+    fn from(request: AdminRequest) -> Self {
+        match request {
+            AdminRequest::Authenticate { password } => XmlNode::new("authenticate")
+                .attribute("password", password)
+                .build(),
+            AdminRequest::Prepare { game_type, pause, slots } => XmlNode::new("prepare")
+                .attribute("gameType", game_type)
+                .attribute("pause", pause)
+                .childs(slots)
+                .build(),
+            AdminRequest::Observe { room_id } => XmlNode::new("observe")
+                .attribute("roomId", room_id)
+                .build(),
+            AdminRequest::Pause { room_id, pause } => XmlNode::new("pause")
+                .attribute("roomId", room_id)
+                .attribute("pause", pause)
+                .build(),
+            AdminRequest::Step { room_id } => XmlNode::new("step")
+                .attribute("roomId", room_id)
+                .build(),
+            AdminRequest::Cancel { room_id } => XmlNode::new("cancel")
+                .attribute("roomId", room_id)
+                .build()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::protocol::SlotDescriptor;
+    use crate::util::{FromXmlNode, XmlNode};
+    use super::AdminRequest;
+
+    #[test]
+    fn test_admin_requests() {
+        let requests = [
+            AdminRequest::Authenticate { password: "secret".to_owned() },
+            AdminRequest::Prepare { game_type: "swc_2021_blokus".to_owned(), pause: true, slots: vec![SlotDescriptor::new("One"), SlotDescriptor::new("Two")] },
+            AdminRequest::Observe { room_id: "abc".to_owned() },
+            AdminRequest::Pause { room_id: "abc".to_owned(), pause: false },
+            AdminRequest::Step { room_id: "abc".to_owned() },
+            AdminRequest::Cancel { room_id: "abc".to_owned() }
+        ];
+        for request in requests {
+            let xml = XmlNode::from(request.clone()).serialize().unwrap();
+            assert_eq!(AdminRequest::from_node(&xml.parse().unwrap()).unwrap(), request, "Could not round-trip {}", xml);
+        }
+    }
+}
@@ -1,39 +1,75 @@
-use std::convert::TryFrom;
-use crate::{util::{SCError, SCResult, FromXmlNode, XmlNode}, game::{Move, Team, GameState}};
+use crate::{util::{SCResult, FromXmlNode, XmlNode}, game::{Move, Team, GameState}};
 use super::GameResult;
 
+const MOVE_REQUEST_CLASS: &str = "sc.framework.plugins.protocol.MoveRequest";
+
 /// A container for game data used by the protocol.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Data {
     WelcomeMessage { team: Team },
-    Memento { state: GameState },
+    Memento { state: Box<GameState> },
     Move(Move),
     MoveRequest,
     GameResult(GameResult),
-    Error { message: String }
+    Error { message: String },
+    /// Data of a class this client does not know, e.g. one
+    /// introduced by a newer version of the server plugin.
+    Unknown { class: String, node: XmlNode }
 }
 
 impl FromXmlNode for Data {
     fn from_node(node: &XmlNode) -> SCResult<Self> {
         let class = node.attribute("class")?;
         match class {
-            "welcomeMessage" => Ok(Self::WelcomeMessage { team: node.attribute("color")?.parse()? }),
-            "memento" => Ok(Self::Memento { state: GameState::from_node(node.child_by_name("state")?)? }),
-            "sc.framework.plugins.protocol.MoveRequest" => Ok(Self::MoveRequest),
+            "welcomeMessage" => Ok(Self::WelcomeMessage { team: node.parse_attribute("color")? }),
+            "memento" => Ok(Self::Memento { state: Box::new(node.parse_child("state")?) }),
+            "sc.plugin2021.SetMove" | "sc.plugin2021.SkipMove" => Ok(Self::Move(Move::from_node(node)?)),
+            MOVE_REQUEST_CLASS => Ok(Self::MoveRequest),
             "result" => Ok(Self::GameResult(GameResult::from_node(node)?)),
             "error" => Ok(Self::Error { message: node.attribute("message")?.to_owned() }),
-            _ => Err(format!("Unrecognized data class: {}", class).into())
+            _ => Ok(Self::Unknown { class: class.to_owned(), node: node.clone() })
         }
     }
 }
 
-impl TryFrom<Data> for XmlNode {
-    type Error = SCError;
-
-    fn try_from(data: Data) -> SCResult<XmlNode> {
+impl From<Data> for XmlNode {
+    fn from(data: Data) -> Self {
         match data {
-            Data::Move(game_move) => Ok(game_move.into()),
-            _ => Err(format!("{:?} can currently not be serialized", data).into())
+            Data::WelcomeMessage { team } => XmlNode::new("data")
+                .attribute("class", "welcomeMessage")
+                .attribute("color", team)
+                .build(),
+            Data::Memento { state } => XmlNode::new("data")
+                .attribute("class", "memento")
+                .child(*state)
+                .build(),
+            Data::Move(game_move) => game_move.into(),
+            Data::MoveRequest => XmlNode::new("data")
+                .attribute("class", MOVE_REQUEST_CLASS)
+                .build(),
+            Data::GameResult(result) => result.into(),
+            Data::Error { message } => XmlNode::new("data")
+                .attribute("class", "error")
+                .attribute("message", message)
+                .build(),
+            Data::Unknown { node, .. } => node
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::util::{FromXmlNode, XmlNode};
+    use super::Data;
+
+    #[test]
+    fn test_unknown_class() {
+        let node: XmlNode = r#"<data class="sc.plugin2022.Hint" priority="2"><content>Try again</content></data>"#.parse().unwrap();
+        let data = Data::from_node(&node).unwrap();
+        assert_eq!(data, Data::Unknown { class: "sc.plugin2022.Hint".to_owned(), node: node.clone() });
+        assert_eq!(XmlNode::from(data), node);
+
+        let node: XmlNode = r#"<data class="error" message="Oops" code="42"><details /></data>"#.parse().unwrap();
+        assert_eq!(Data::from_node(&node).unwrap(), Data::Error { message: "Oops".to_owned() });
+    }
+}
@@ -12,9 +12,21 @@ pub struct GameResult {
 impl FromXmlNode for GameResult {
     fn from_node(node: &XmlNode) -> SCResult<Self> {
         Ok(Self {
-            definition: ScoreDefinition::from_node(node.child_by_name("definition")?)?,
-            scores: node.childs_by_name("score").map(PlayerScore::from_node).collect::<SCResult<_>>()?,
-            winners: node.childs_by_name("winner").map(Player::from_node).collect::<SCResult<_>>()?
+            definition: node.parse_child("definition")?,
+            scores: node.parse_children("score")?,
+            winners: node.parse_children("winner")?
         })
     }
 }
+
+impl From<GameResult> for XmlNode {
+    /// Serializes the result as a `<data>` node, as sent in rooms.
+    fn from(result: GameResult) -> Self {
+        XmlNode::new("data")
+            .attribute("class", "result")
+            .child(result.definition)
+            .childs(result.scores)
+            .childs(result.winners.into_iter().map(|w| XmlNode::from(w).renamed("winner")))
+            .build()
+    }
+}
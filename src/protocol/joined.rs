@@ -10,3 +10,7 @@ pub struct Joined {
 impl FromXmlNode for Joined {
     fn from_node(node: &XmlNode) -> SCResult<Self> { Ok(Self { room_id: node.attribute("roomId")?.to_owned() }) }
 }
+
+impl From<Joined> for XmlNode {
+    fn from(joined: Joined) -> Self { XmlNode::new("joined").attribute("roomId", joined.room_id).build() }
+}
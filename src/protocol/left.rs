@@ -10,3 +10,7 @@ pub struct Left {
 impl FromXmlNode for Left {
     fn from_node(node: &XmlNode) -> SCResult<Self> { Ok(Self { room_id: node.attribute("roomId")?.to_owned() }) }
 }
+
+impl From<Left> for XmlNode {
+    fn from(left: Left) -> Self { XmlNode::new("left").attribute("roomId", left.room_id).build() }
+}
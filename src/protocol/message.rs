@@ -0,0 +1,153 @@
+use crate::util::{SCError, SCResult, FromXmlNode, XmlNode};
+use super::{AdminRequest, Joined, Left, Observed, Prepared, Room};
+
+/// A top-level message of the protocol, i.e. a direct
+/// child of the `<protocol>` element, in either direction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    /// Joins any open game of the given type.
+    Join { game_type: String },
+    /// Joins a prepared game using a reservation code.
+    JoinPrepared { reservation_code: String },
+    /// Joins (or rejoins) the room with the given id.
+    JoinRoom { room_id: String },
+    /// Confirms that the client has joined a room.
+    Joined(Joined),
+    /// Indicates that the client has left a room.
+    Left(Left),
+    /// Confirms that an administrator observes a room.
+    Observed(Observed),
+    /// Confirms that a room has been prepared.
+    Prepared(Prepared),
+    /// A message in a game room.
+    Room(Room),
+    /// A request only administrators may send.
+    Admin(AdminRequest),
+    /// Indicates that the server rejected a request.
+    ErrorPacket { message: String },
+    /// Asks the other side to close the connection.
+    Close
+}
+
+impl FromXmlNode for Message {
+    fn from_node(node: &XmlNode) -> SCResult<Self> {
+        match node.name() {
+            "join" => Ok(Self::Join { game_type: node.attribute("gameType")?.to_owned() }),
+            "joinPrepared" => Ok(Self::JoinPrepared { reservation_code: node.attribute("reservationCode")?.to_owned() }),
+            "joinRoom" => Ok(Self::JoinRoom { room_id: node.attribute("roomId")?.to_owned() }),
+            "joined" => Ok(Self::Joined(Joined::from_node(node)?)),
+            "left" => Ok(Self::Left(Left::from_node(node)?)),
+            "observed" => Ok(Self::Observed(Observed::from_node(node)?)),
+            "prepared" => Ok(Self::Prepared(Prepared::from_node(node)?)),
+            "room" => Ok(Self::Room(Room::from_node(node)?)),
+            "authenticate" | "prepare" | "observe" | "pause" | "step" | "cancel" => Ok(Self::Admin(AdminRequest::from_node(node)?)),
+            "errorpacket" => Ok(Self::ErrorPacket { message: node.opt_attribute("message").unwrap_or_default().to_owned() }),
+            "close" | "sc.protocol.responses.CloseConnection" => Ok(Self::Close),
+            name => Err(SCError::protocol(format!("Unrecognized message: <{}>", name)))
+        }
+    }
+}
+
+impl From<Message> for XmlNode {
+    fn from(message: Message) -> Self {
+        match message {
+            Message::Join { game_type } => XmlNode::new("join")
+                .attribute("gameType", game_type)
+                .build(),
+            Message::JoinPrepared { reservation_code } => XmlNode::new("joinPrepared")
+                .attribute("reservationCode", reservation_code)
+                .build(),
+            Message::JoinRoom { room_id } => XmlNode::new("joinRoom")
+                .attribute("roomId", room_id)
+                .build(),
+            Message::Joined(joined) => joined.into(),
+            Message::Left(left) => left.into(),
+            Message::Observed(observed) => observed.into(),
+            Message::Prepared(prepared) => prepared.into(),
+            Message::Room(room) => room.into(),
+            Message::Admin(request) => request.into(),
+            Message::ErrorPacket { message } => XmlNode::new("errorpacket")
+                .attribute("message", message)
+                .build(),
+            Message::Close => XmlNode::new("close").build()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{Color, GameState, Move};
+    use crate::protocol::{Data, MessageStream, Room};
+    use crate::util::{FromXmlNode, XmlNode};
+    use super::Message;
+
+    /// Both directions of the first player's connection, adapted from a server log.
+    const TRANSCRIPT: &str = include_str!("../../fixtures/transcripts/player_one.xml");
+
+    fn round_trip(message: &Message) -> Message {
+        let xml = XmlNode::from(message.clone()).serialize().unwrap();
+        Message::from_node(&xml.parse().unwrap()).unwrap_or_else(|e| panic!("Could not reparse {}: {:?}", xml, e))
+    }
+
+    #[test]
+    fn test_transcript() {
+        let mut stream = MessageStream::new(TRANSCRIPT.as_bytes());
+        let messages: Vec<_> = stream.by_ref()
+            .map(|m| Message::from_node(&m.unwrap().node).unwrap())
+            .collect();
+        assert!(stream.is_closed());
+
+        let kinds: Vec<_> = messages.iter().map(|m| match m {
+            Message::Joined(_) => "joined",
+            Message::Room(Room { data: Data::WelcomeMessage { .. }, .. }) => "welcome",
+            Message::Room(Room { data: Data::Memento { .. }, .. }) => "memento",
+            Message::Room(Room { data: Data::MoveRequest, .. }) => "request",
+            Message::Room(Room { data: Data::Move(_), .. }) => "move",
+            Message::Room(Room { data: Data::GameResult(_), .. }) => "result",
+            Message::Left(_) => "left",
+            Message::Close => "close",
+            _ => "other"
+        }).collect();
+        assert_eq!(kinds, ["joined", "welcome", "memento", "request", "move", "memento", "memento", "result", "left", "close"]);
+
+        let states: Vec<&GameState> = messages.iter().filter_map(|m| match m {
+            Message::Room(Room { data: Data::Memento { state }, .. }) => Some(state.as_ref()),
+            _ => None
+        }).collect();
+        let sent = messages.iter().find_map(|m| match m {
+            Message::Room(Room { data: Data::Move(Move::Set { piece }), .. }) => Some(piece.canonicalize()),
+            _ => None
+        }).unwrap();
+        assert_eq!(states[0].infer_move(states[1]), Some(Move::Set { piece: sent }));
+        assert_eq!(states[2].last_move_mono.len(), 1);
+
+        if let Message::Room(Room { data: Data::GameResult(result), .. }) = &messages[7] {
+            assert_eq!(result.definition.fragments.len(), 2);
+            assert_eq!(result.scores.len(), 2);
+            assert_eq!(result.winners[0].display_name, "Alice");
+        } else {
+            panic!("Expected a game result");
+        }
+
+        for message in &messages {
+            assert_eq!(&round_trip(message), message);
+        }
+    }
+
+    #[test]
+    fn test_client_messages() {
+        let messages = [
+            Message::Join { game_type: "swc_2021_blokus".to_owned() },
+            Message::JoinPrepared { reservation_code: "r1".to_owned() },
+            Message::JoinRoom { room_id: "abc".to_owned() },
+            Message::ErrorPacket { message: "Room does not exist".to_owned() },
+            Message::Room(Room { room_id: "abc".to_owned(), data: Data::Move(Move::Skip { color: Color::Red }) }),
+            Message::Room(Room { room_id: "abc".to_owned(), data: Data::Error { message: "Invalid move".to_owned() } })
+        ];
+        for message in &messages {
+            assert_eq!(&round_trip(message), message);
+        }
+        assert!(Message::from_node(&"<unknown/>".parse().unwrap()).is_err());
+        assert_eq!(Message::from_node(&"<sc.protocol.responses.CloseConnection/>".parse().unwrap()).unwrap(), Message::Close);
+    }
+}
@@ -0,0 +1,125 @@
+use std::io::{ErrorKind, Read};
+use crate::telemetry::Metrics;
+use crate::util::{SCResult, XmlFrameBuffer, XmlNode};
+
+const READ_CHUNK_SIZE: usize = 4096;
+
+/// A message received from the server, both in its
+/// raw form and as a parsed XML tree.
+#[derive(Debug)]
+pub struct ReceivedMessage {
+    /// The raw XML exactly as it has been received.
+    pub xml: String,
+    /// The parsed XML.
+    pub node: XmlNode
+}
+
+/// An iterator over the messages in the server's continuous
+/// `<protocol>` stream, reading from the underlying reader
+/// only as far as needed for the next message.
+///
+/// The stream ends when the server closes the protocol
+/// element (see `is_closed`), when the reader reaches its
+/// end or after an I/O error (which is yielded once).
+/// Malformed messages are yielded as errors without
+/// ending the stream.
+pub struct MessageStream<R> where R: Read {
+    reader: R,
+    frames: XmlFrameBuffer,
+    chunk: Box<[u8; READ_CHUNK_SIZE]>,
+    is_done: bool
+}
+
+impl<R> MessageStream<R> where R: Read {
+    /// Creates a new stream reading from the given reader.
+    pub fn new(reader: R) -> Self {
+        Self { reader, frames: XmlFrameBuffer::new(), chunk: Box::new([0; READ_CHUNK_SIZE]), is_done: false }
+    }
+
+    /// Whether the server has closed the protocol stream
+    /// (as opposed to the connection just ending).
+    pub fn is_closed(&self) -> bool {
+        self.frames.is_closed()
+    }
+
+    /// Reads the next chunk into the frame buffer and
+    /// returns whether the reader has ended.
+    fn read_chunk(&mut self) -> SCResult<bool> {
+        loop {
+            match self.reader.read(&mut self.chunk[..]) {
+                Ok(0) => return Ok(true),
+                Ok(count) => {
+                    self.frames.push(&self.chunk[..count])?;
+                    return Ok(false);
+                },
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into())
+            }
+        }
+    }
+}
+
+impl<R> Iterator for MessageStream<R> where R: Read {
+    type Item = SCResult<ReceivedMessage>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(xml) = self.frames.next_frame() {
+                let parsed = Metrics::global().xml_parse_time.time(|| xml.parse());
+                return Some(parsed.map(|node| ReceivedMessage { xml, node }));
+            }
+            if self.is_done || self.is_closed() {
+                return None;
+            }
+            match self.read_chunk() {
+                Ok(false) => {},
+                Ok(true) => self.is_done = true,
+                Err(e) => {
+                    // Malformed framing cannot be recovered from either
+                    self.is_done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Read};
+    use crate::util::SCError;
+    use super::MessageStream;
+
+    /// A reader returning at most the given number of bytes per read.
+    struct Trickle<'a> {
+        bytes: &'a [u8],
+        chunk_size: usize
+    }
+
+    impl<'a> Read for Trickle<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let count = self.chunk_size.min(buf.len()).min(self.bytes.len());
+            buf[..count].copy_from_slice(&self.bytes[..count]);
+            self.bytes = &self.bytes[count..];
+            Ok(count)
+        }
+    }
+
+    #[test]
+    fn test_message_stream() {
+        let xml = b"<protocol><joined roomId=\"abc\"/><room roomId=\"abc\"><data class=\"welcomeMessage\" color=\"ONE\"/></room><broken></protocol>";
+        let mut stream = MessageStream::new(Trickle { bytes: xml, chunk_size: 3 });
+        assert_eq!(stream.next().unwrap().unwrap().node.name(), "joined");
+        let room = stream.next().unwrap().unwrap();
+        assert_eq!(room.xml, "<room roomId=\"abc\"><data class=\"welcomeMessage\" color=\"ONE\"/></room>");
+        // The unclosed element breaks the framing
+        assert!(matches!(stream.next(), Some(Err(SCError::Xml(_)))));
+        assert!(stream.next().is_none());
+        assert!(!stream.is_closed());
+
+        let mut stream = MessageStream::new(Trickle { bytes: b"<protocol><left roomId=\"abc\"/></protocol>", chunk_size: 1 });
+        assert_eq!(stream.next().unwrap().unwrap().node.name(), "left");
+        assert!(stream.next().is_none());
+        assert!(stream.is_closed());
+    }
+}
@@ -1,24 +1,35 @@
 //! The data structures used by the XML protocol.
 
+mod admin_request;
 mod data;
 mod game_result;
 mod joined;
 mod left;
+mod message;
+mod message_stream;
+mod observed;
 mod player_score;
+mod prepared;
 mod room;
 mod score_aggregation;
 mod score_cause;
 mod score_definition;
 mod score_fragment;
+mod slot_descriptor;
 
+pub use admin_request::*;
 pub use data::*;
 pub use game_result::*;
 pub use joined::*;
 pub use left::*;
-pub use data::*;
+pub use message::*;
+pub use message_stream::*;
+pub use observed::*;
 pub use player_score::*;
+pub use prepared::*;
 pub use room::*;
 pub use score_definition::*;
 pub use score_fragment::*;
 pub use score_aggregation::*;
 pub use score_cause::*;
+pub use slot_descriptor::*;
@@ -0,0 +1,16 @@
+use crate::util::{SCResult, FromXmlNode, XmlNode};
+
+/// A message indicating that the client
+/// observes the room with the specified id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Observed {
+    pub room_id: String
+}
+
+impl FromXmlNode for Observed {
+    fn from_node(node: &XmlNode) -> SCResult<Self> { Ok(Self { room_id: node.attribute("roomId")?.to_owned() }) }
+}
+
+impl From<Observed> for XmlNode {
+    fn from(observed: Observed) -> Self { XmlNode::new("observed").attribute("roomId", observed.room_id).build() }
+}
@@ -11,8 +11,17 @@ pub struct PlayerScore {
 impl FromXmlNode for PlayerScore {
     fn from_node(node: &XmlNode) -> SCResult<Self> {
         Ok(Self {
-            cause: node.attribute("cause")?.parse()?,
+            cause: node.parse_attribute("cause")?,
             reason: node.attribute("reason").map(|s| s.to_owned()).unwrap_or_default()
         })
     }
 }
+
+impl From<PlayerScore> for XmlNode {
+    fn from(score: PlayerScore) -> Self {
+        XmlNode::new("score")
+            .attribute("cause", score.cause)
+            .attribute("reason", score.reason)
+            .build()
+    }
+}
@@ -0,0 +1,28 @@
+use crate::util::{SCResult, FromXmlNode, XmlNode};
+
+/// A message indicating that a room has been
+/// prepared with the given reservation codes,
+/// one per requested slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Prepared {
+    pub room_id: String,
+    pub reservations: Vec<String>
+}
+
+impl FromXmlNode for Prepared {
+    fn from_node(node: &XmlNode) -> SCResult<Self> {
+        Ok(Self {
+            room_id: node.attribute("roomId")?.to_owned(),
+            reservations: node.childs_by_name("reservation").map(|r| r.content().to_owned()).collect()
+        })
+    }
+}
+
+impl From<Prepared> for XmlNode {
+    fn from(prepared: Prepared) -> Self {
+        XmlNode::new("prepared")
+            .attribute("roomId", prepared.room_id)
+            .childs(prepared.reservations.into_iter().map(|r| XmlNode::new("reservation").content(r)))
+            .build()
+    }
+}
@@ -1,5 +1,4 @@
-use std::convert::TryFrom;
-use crate::util::{SCError, SCResult, FromXmlNode, XmlNode};
+use crate::util::{SCResult, FromXmlNode, XmlNode};
 use super::Data;
 
 /// A message in a room together with some data.
@@ -13,18 +12,16 @@ impl FromXmlNode for Room {
     fn from_node(node: &XmlNode) -> SCResult<Self> {
         Ok(Self {
             room_id: node.attribute("roomId")?.to_owned(),
-            data: Data::from_node(node.child_by_name("data")?)?
+            data: node.parse_child("data")?
         })
     }
 }
 
-impl TryFrom<Room> for XmlNode {
-    type Error = SCError;
-
-    fn try_from(room: Room) -> SCResult<XmlNode> {
-        Ok(XmlNode::new("room")
+impl From<Room> for XmlNode {
+    fn from(room: Room) -> Self {
+        XmlNode::new("room")
             .attribute("roomId", room.room_id)
-            .try_child(room.data)?
-            .into())
+            .child(room.data)
+            .build()
     }
 }
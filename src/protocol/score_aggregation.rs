@@ -1,4 +1,5 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
+use crate::util::{SCError, SCResult};
 
 /// Determines how scores should be aggregated (e.g. summed up or averaged over).
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -8,13 +9,22 @@ pub enum ScoreAggregation {
 }
 
 impl FromStr for ScoreAggregation {
-    type Err = String;
+    type Err = SCError;
 
-    fn from_str(raw: &str) -> Result<Self, String> {
+    fn from_str(raw: &str) -> SCResult<Self> {
         match raw {
             "SUM" => Ok(Self::Sum),
             "AVERAGE" => Ok(Self::Average),
-            _ => Err(format!("Unknown score aggregation: {}", raw))
+            _ => Err(SCError::parse(format!("Unknown score aggregation: {}", raw)))
+        }
+    }
+}
+
+impl fmt::Display for ScoreAggregation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sum => write!(f, "SUM"),
+            Self::Average => write!(f, "AVERAGE")
         }
     }
 }
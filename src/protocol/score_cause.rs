@@ -1,4 +1,6 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
+use log::warn;
+use crate::util::{SCError, SCResult};
 
 /// Determines the cause of a game score.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -12,9 +14,9 @@ pub enum ScoreCause {
 }
 
 impl FromStr for ScoreCause {
-    type Err = String;
+    type Err = SCError;
 
-    fn from_str(raw: &str) -> Result<Self, String> {
+    fn from_str(raw: &str) -> SCResult<Self> {
         match raw {
             "REGULAR" => Ok(Self::Regular),
             "LEFT" => Ok(Self::Left),
@@ -22,7 +24,24 @@ impl FromStr for ScoreCause {
             "SOFT_TIMEOUT" => Ok(Self::SoftTimeout),
             "HARD_TIMEOUT" => Ok(Self::HardTimeout),
             "UNKNOWN" => Ok(Self::Unknown),
-            _ => Err(format!("Unknown score cause: {}", raw))
+            // Tolerate causes added by newer versions of the server
+            _ => {
+                warn!("Unknown score cause: {}", raw);
+                Ok(Self::Unknown)
+            }
+        }
+    }
+}
+
+impl fmt::Display for ScoreCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Regular => write!(f, "REGULAR"),
+            Self::Left => write!(f, "LEFT"),
+            Self::RuleViolation => write!(f, "RULE_VIOLATION"),
+            Self::SoftTimeout => write!(f, "SOFT_TIMEOUT"),
+            Self::HardTimeout => write!(f, "HARD_TIMEOUT"),
+            Self::Unknown => write!(f, "UNKNOWN")
         }
     }
 }
@@ -10,7 +10,15 @@ pub struct ScoreDefinition {
 impl FromXmlNode for ScoreDefinition {
     fn from_node(node: &XmlNode) -> SCResult<Self> {
         Ok(Self {
-            fragments: node.childs_by_name("fragment").map(ScoreFragment::from_node).collect::<SCResult<_>>()?
+            fragments: node.parse_children("fragment")?
         })
     }
 }
+
+impl From<ScoreDefinition> for XmlNode {
+    fn from(definition: ScoreDefinition) -> Self {
+        XmlNode::new("definition")
+            .childs(definition.fragments)
+            .build()
+    }
+}
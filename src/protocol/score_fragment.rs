@@ -13,8 +13,18 @@ impl FromXmlNode for ScoreFragment {
     fn from_node(node: &XmlNode) -> SCResult<Self> {
         Ok(Self {
             name: node.attribute("name")?.to_owned(),
-            aggregation: node.child_by_name("aggregation")?.content().parse()?,
-            relevant_for_ranking: node.child_by_name("relevantForRanking")?.content().parse()?
+            aggregation: node.child_by_name("aggregation")?.parse_content()?,
+            relevant_for_ranking: node.child_by_name("relevantForRanking")?.parse_content()?
         })
     }
 }
+
+impl From<ScoreFragment> for XmlNode {
+    fn from(fragment: ScoreFragment) -> Self {
+        XmlNode::new("fragment")
+            .attribute("name", fragment.name)
+            .child(XmlNode::new("aggregation").content(fragment.aggregation))
+            .child(XmlNode::new("relevantForRanking").content(fragment.relevant_for_ranking))
+            .build()
+    }
+}
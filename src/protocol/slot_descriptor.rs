@@ -0,0 +1,38 @@
+use crate::util::{SCResult, FromXmlNode, XmlNode};
+
+/// Describes a player slot in a game prepared by an administrator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotDescriptor {
+    pub display_name: String,
+    /// Whether the player may exceed the time limit without losing.
+    pub can_timeout: bool,
+    /// Whether the slot can only be joined using its reservation.
+    pub reserved: bool
+}
+
+impl SlotDescriptor {
+    /// Creates a reserved slot with the given display name.
+    pub fn new(display_name: impl Into<String>) -> Self {
+        Self { display_name: display_name.into(), can_timeout: true, reserved: true }
+    }
+}
+
+impl FromXmlNode for SlotDescriptor {
+    fn from_node(node: &XmlNode) -> SCResult<Self> {
+        Ok(Self {
+            display_name: node.attribute("displayName")?.to_owned(),
+            can_timeout: node.parse_attribute("canTimeout")?,
+            reserved: node.parse_attribute("reserved")?
+        })
+    }
+}
+
+impl From<SlotDescriptor> for XmlNode {
+    fn from(slot: SlotDescriptor) -> Self {
+        XmlNode::new("slot")
+            .attribute("displayName", slot.display_name)
+            .attribute("canTimeout", slot.can_timeout)
+            .attribute("reserved", slot.reserved)
+            .build()
+    }
+}
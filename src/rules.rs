@@ -0,0 +1,115 @@
+//! The parameters of the game rules, matching the backend's
+//! `Constants.kt` and `GameRuleLogic.kt`. Evaluation functions and
+//! tools should use these instead of hard-coding the numbers.
+
+use crate::game::{PieceShape, PieceShapeKind, Vec2};
+
+/// The version of the official game rules this crate implements.
+pub const RULES_VERSION: &str = "Blokus 2021 (backend 21.4.0)";
+
+/// The width and height of the board.
+pub const BOARD_SIZE: usize = 20;
+
+/// The last round that is played before the game ends.
+pub const MAX_ROUND: u32 = 25;
+
+/// The number of squares covered by all pieces of one color.
+pub const SUM_MAX_SQUARES: i32 = 89;
+
+/// The bonus for placing every piece of a color.
+pub const ALL_PIECES_BONUS: i32 = 15;
+
+/// The additional bonus if the monomino was the last piece placed.
+pub const MONO_LAST_BONUS: i32 = 5;
+
+/// The most points a single color can achieve.
+pub const MAX_POINTS: i32 = SUM_MAX_SQUARES + ALL_PIECES_BONUS + MONO_LAST_BONUS;
+
+/// The points for placing a piece of the given kind, i.e. its number of squares.
+pub fn piece_points(kind: PieceShapeKind) -> i32 {
+    kind.shape().size() as i32
+}
+
+/// Computes the points of a color from its undeployed piece shapes and
+/// whether its last placed piece was the monomino (which only counts
+/// once every piece has been placed).
+pub fn points_from_undeployed<'a>(undeployed: impl IntoIterator<Item=&'a PieceShape>, mono_last: bool) -> i32 {
+    RuleSet::OFFICIAL.points_from_undeployed(undeployed, mono_last)
+}
+
+/// Whether the game has ended because the round limit has been exceeded.
+pub fn is_past_round_limit(round: u32) -> bool {
+    RuleSet::OFFICIAL.is_past_round_limit(round)
+}
+
+/// The parameters of a variant of the rules, which a `SizedGameState`
+/// plays by along with its board size, e.g. for Blokus Duo-style games.
+/// The default are the official rules.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RuleSet {
+    /// The last round that is played before the game ends.
+    pub max_round: u32,
+    /// The bonus for placing every piece of a color.
+    pub all_pieces_bonus: i32,
+    /// The additional bonus if the monomino was the last piece placed.
+    pub mono_last_bonus: i32,
+    /// The cells the first piece of each color has to cover one of,
+    /// or `None` for the corners of the board.
+    pub start_cells: Option<&'static [Vec2]>
+}
+
+impl RuleSet {
+    /// The official rules.
+    pub const OFFICIAL: Self = Self {
+        max_round: MAX_ROUND,
+        all_pieces_bonus: ALL_PIECES_BONUS,
+        mono_last_bonus: MONO_LAST_BONUS,
+        start_cells: None
+    };
+
+    /// The most points a single color can achieve.
+    pub fn max_points(&self) -> i32 {
+        SUM_MAX_SQUARES + self.all_pieces_bonus + self.mono_last_bonus
+    }
+
+    /// Computes the points of a color, see `points_from_undeployed`.
+    pub fn points_from_undeployed<'a>(&self, undeployed: impl IntoIterator<Item=&'a PieceShape>, mono_last: bool) -> i32 {
+        let mut undeployed = undeployed.into_iter().peekable();
+        if undeployed.peek().is_none() {
+            SUM_MAX_SQUARES + self.all_pieces_bonus + if mono_last { self.mono_last_bonus } else { 0 }
+        } else {
+            SUM_MAX_SQUARES - undeployed.map(|s| piece_points(s.kind())).sum::<i32>()
+        }
+    }
+
+    /// Whether the game has ended because the round limit has been exceeded.
+    pub fn is_past_round_limit(&self, round: u32) -> bool {
+        round > self.max_round
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self::OFFICIAL
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{PIECE_SHAPES, PIECE_SHAPE_KINDS, PIECE_SHAPES_BY_NAME};
+    use super::{MAX_POINTS, RuleSet, SUM_MAX_SQUARES, piece_points, points_from_undeployed};
+
+    #[test]
+    fn test_rules() {
+        assert_eq!(PIECE_SHAPE_KINDS.iter().map(|&k| piece_points(k)).sum::<i32>(), SUM_MAX_SQUARES);
+        assert_eq!(points_from_undeployed(PIECE_SHAPES.iter(), false), 0);
+        assert_eq!(points_from_undeployed([&PIECE_SHAPES_BY_NAME["PENTO_X"]], true), SUM_MAX_SQUARES - 5);
+        assert_eq!(points_from_undeployed([], false), 104);
+        assert_eq!(points_from_undeployed([], true), MAX_POINTS);
+
+        let variant = RuleSet { all_pieces_bonus: 20, mono_last_bonus: 0, ..RuleSet::default() };
+        assert_eq!(RuleSet::OFFICIAL.max_points(), MAX_POINTS);
+        assert_eq!(variant.points_from_undeployed([], true), variant.max_points());
+        assert_eq!(variant.max_points(), SUM_MAX_SQUARES + 20);
+    }
+}
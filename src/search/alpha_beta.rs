@@ -0,0 +1,552 @@
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread;
+use std::time::Instant;
+use log::Level;
+use crate::eval::Heuristic;
+use crate::game::{COLOR_COUNT, GameState, Move, Team};
+use crate::telemetry::Metrics;
+use crate::util::Span;
+use super::{MoveOrdering, SearchHandle, SearchMode, SearchResult, TranspositionEntry, TranspositionTable};
+
+/// Mixed into the hashes of positions searched for the second
+/// team, since values are stored from the searching team's view.
+const TEAM_TWO_SALT: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// How a value stored in the transposition table relates to
+/// the actual value of the position.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Bound {
+    /// The value is exact.
+    Exact,
+    /// The actual value is at least as high (the search failed high).
+    Lower,
+    /// The actual value is at most as high (the search failed low).
+    Upper
+}
+
+/// A search result cached in the transposition table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchEntry {
+    pub value: f64,
+    pub bound: Bound,
+    /// The first move of the best decision, which is searched first
+    /// when the position is visited again (e.g. in the next iteration).
+    pub best_move: Option<Move>
+}
+
+/// A depth-limited alpha-beta search that maximizes
+/// the heuristic from the perspective of the team to move.
+/// The depth is measured in decisions rather than moves
+/// (which only differs in team mode).
+pub struct AlphaBeta<H> {
+    heuristic: H,
+    depth: usize,
+    mode: SearchMode,
+    ordering: Option<Mutex<MoveOrdering>>,
+    table: Option<Mutex<TranspositionTable<SearchEntry>>>,
+    nodes: AtomicU64,
+    /// The principal variation of the last completed search.
+    pv: Mutex<Vec<Move>>
+}
+
+/// The state of one search.
+struct Context<'a> {
+    team: Team,
+    canceled: &'a AtomicBool,
+    /// The principal variations by ply, where the
+    /// line at a ply starts with the decision there.
+    pv: RefCell<Vec<Vec<Move>>>
+}
+
+impl Context<'_> {
+    /// Empties the principal variation at the given ply.
+    fn clear_pv(&self, ply: usize) {
+        let mut pv = self.pv.borrow_mut();
+        if pv.len() <= ply + 1 {
+            pv.resize_with(ply + 2, Vec::new);
+        }
+        pv[ply].clear();
+    }
+
+    /// Sets the principal variation at the given ply to the
+    /// decision followed by the line of the next ply.
+    fn update_pv(&self, ply: usize, moves: &[Move]) {
+        let mut pv = self.pv.borrow_mut();
+        let (current, next) = pv.split_at_mut(ply + 1);
+        let current = &mut current[ply];
+        current.clear();
+        current.extend_from_slice(moves);
+        current.extend_from_slice(&next[0]);
+    }
+}
+
+/// A decision of a team, i.e. the moves up to the next decision of
+/// another team, along with the state resulting from them.
+type Decision = (Vec<Move>, GameState);
+
+impl<H> AlphaBeta<H> where H: Heuristic {
+    /// Creates a new search with depth 1 in per-color mode.
+    pub fn new(heuristic: H) -> Self {
+        Self { heuristic, depth: 1, mode: SearchMode::default(), ordering: None, table: None, nodes: AtomicU64::new(0), pv: Mutex::new(Vec::new()) }
+    }
+
+    /// Sets the number of decisions to search ahead.
+    pub fn depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Sets the search mode.
+    pub fn mode(mut self, mode: SearchMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Searches the decisions in the order given by the move
+    /// ordering, which also records killer moves and history
+    /// from the cutoffs of every search.
+    pub fn ordering(mut self, ordering: MoveOrdering) -> Self {
+        self.ordering = Some(Mutex::new(ordering));
+        self
+    }
+
+    /// Caches the values of searched positions in the given table,
+    /// which is kept across searches. Every search starts a new
+    /// generation, so entries of previous searches are replaced first.
+    pub fn transposition_table(mut self, table: TranspositionTable<SearchEntry>) -> Self {
+        self.table = Some(Mutex::new(table));
+        self
+    }
+
+    /// Searches the best move for the current color.
+    pub fn best_move(&self, state: &GameState) -> Option<Move> {
+        self.search(state).map(|result| result.best_move)
+    }
+
+    /// Searches the current state at the configured depth.
+    pub fn search(&self, state: &GameState) -> Option<SearchResult> {
+        let start = Instant::now();
+        let (decision, score) = self.best_decision(state)?;
+        Some(self.result(decision, score, self.depth, start))
+    }
+
+    /// Searches the best decision of the current team, i.e. the move
+    /// of the current color or, in team mode, possibly the moves of
+    /// both of the team's colors (with the opponent's skip in between),
+    /// along with its value.
+    pub fn best_decision(&self, state: &GameState) -> Option<(Vec<Move>, f64)> {
+        let span = Span::enter(Level::Debug, "search").with("depth", self.depth).with("mode", format!("{:?}", self.mode));
+        self.nodes.store(0, Ordering::Relaxed);
+        self.new_generation();
+        let canceled = AtomicBool::new(false);
+        let decision = self.timed_search_root(state, self.depth, &canceled);
+        span.record("nodes", self.nodes.load(Ordering::Relaxed));
+        if let Some((moves, value)) = &decision {
+            span.record("move", &moves[0]);
+            span.record("value", value);
+        }
+        decision
+    }
+
+    /// Searches with increasing depth up to the configured one,
+    /// publishing every completed iteration to the handle and
+    /// passing it to the callback, until the handle is canceled.
+    /// Returns the result of the deepest completed iteration.
+    pub fn iterative_deepening(&self, state: &GameState, handle: &SearchHandle, mut on_depth: impl FnMut(&SearchResult)) -> Option<SearchResult> {
+        let _span = Span::enter(Level::Debug, "iterative_deepening").with("depth", self.depth);
+        let start = Instant::now();
+        let mut latest = None;
+        self.new_generation();
+
+        for depth in 1..=self.depth.max(1) {
+            self.nodes.store(0, Ordering::Relaxed);
+            let decision = self.timed_search_root(state, depth, handle.canceled_flag());
+            let (decision, score) = match decision {
+                Some(decision) if !handle.is_canceled() => decision,
+                _ => break
+            };
+            let result = self.result(decision, score, depth, start);
+            on_depth(&result);
+            handle.publish(result.clone());
+            latest = Some(result);
+        }
+
+        handle.finish();
+        latest
+    }
+
+    /// Runs the iterative deepening search on a new thread.
+    pub fn spawn(self, state: GameState) -> SearchHandle where H: Send + 'static {
+        self.spawn_with(state, |_| {})
+    }
+
+    /// Runs the iterative deepening search on a new thread,
+    /// invoking the callback after every completed iteration.
+    pub fn spawn_with(self, state: GameState, on_depth: impl FnMut(&SearchResult) + Send + 'static) -> SearchHandle where H: Send + 'static {
+        let handle = SearchHandle::new();
+        let worker_handle = handle.clone();
+        let spawned = thread::Builder::new().name("search".to_owned()).spawn(move || {
+            // Do not leave waiting threads hanging if the heuristic panics
+            if panic::catch_unwind(AssertUnwindSafe(|| self.iterative_deepening(&state, &worker_handle, on_depth))).is_err() {
+                worker_handle.finish();
+            }
+        });
+        if spawned.is_err() {
+            handle.finish();
+        }
+        handle
+    }
+
+    /// Describes the last searched decision.
+    fn result(&self, mut decision: Vec<Move>, score: f64, depth: usize, start: Instant) -> SearchResult {
+        let pv = lock(&self.pv).clone();
+        let pv = if pv.is_empty() { decision.clone() } else { pv };
+        SearchResult { best_move: decision.swap_remove(0), score, depth, pv, nodes: self.nodes.load(Ordering::Relaxed), time: start.elapsed() }
+    }
+
+    /// Searches the root, recording the search's time and
+    /// nodes in the global metrics once it has finished.
+    fn timed_search_root(&self, state: &GameState, depth: usize, canceled: &AtomicBool) -> Option<(Vec<Move>, f64)> {
+        let metrics = Metrics::global();
+        let decision = metrics.search_time.time(|| self.search_root(state, depth, canceled));
+        metrics.search_nodes.add(self.nodes.load(Ordering::Relaxed));
+        decision
+    }
+
+    fn search_root(&self, state: &GameState, depth: usize, canceled: &AtomicBool) -> Option<(Vec<Move>, f64)> {
+        let ctx = Context { team: state.current_team(), canceled, pv: RefCell::new(Vec::new()) };
+        let mut alpha = f64::NEG_INFINITY;
+        let mut best = None;
+        ctx.clear_pv(0);
+
+        // Start with the best decision of the previous iteration
+        let hash = self.hash(state, ctx.team);
+        let mut decisions = Metrics::global().move_generation_time.time(|| self.ordered_decisions(state, 0));
+        move_to_front(&mut decisions, hash.and_then(|hash| self.probe(hash)).and_then(|entry| entry.value.best_move));
+
+        for (moves, next) in decisions {
+            let value = self.alpha_beta(&ctx, &next, depth.saturating_sub(1), 1, alpha, f64::INFINITY);
+            if canceled.load(Ordering::Relaxed) {
+                return None;
+            }
+            if best.is_none() || value > alpha {
+                alpha = value;
+                ctx.update_pv(0, &moves);
+                best = Some(moves);
+            }
+        }
+
+        if let (Some(hash), Some(moves)) = (hash, &best) {
+            self.store(hash, depth as u32, SearchEntry { value: alpha, bound: Bound::Exact, best_move: Some(moves[0].clone()) });
+        }
+        *lock(&self.pv) = ctx.pv.into_inner().swap_remove(0);
+        best.map(|moves| (moves, alpha))
+    }
+
+    fn alpha_beta(&self, ctx: &Context, state: &GameState, depth: usize, ply: usize, mut alpha: f64, mut beta: f64) -> f64 {
+        self.nodes.fetch_add(1, Ordering::Relaxed);
+        ctx.clear_pv(ply);
+        if ctx.canceled.load(Ordering::Relaxed) {
+            // The result is discarded anyway
+            return 0.0;
+        }
+        if depth == 0 || state.rules.is_past_round_limit(state.round) {
+            return self.heuristic.evaluate(state, ctx.team);
+        }
+
+        let hash = self.hash(state, ctx.team);
+        let (original_alpha, original_beta) = (alpha, beta);
+        let mut hash_move = None;
+        if let Some(entry) = hash.and_then(|hash| self.probe(hash)) {
+            if entry.depth >= depth as u32 {
+                let value = entry.value.value;
+                match entry.value.bound {
+                    Bound::Exact => return value,
+                    Bound::Lower => alpha = alpha.max(value),
+                    Bound::Upper => beta = beta.min(value)
+                }
+                if alpha >= beta {
+                    return value;
+                }
+            }
+            hash_move = entry.value.best_move;
+        }
+
+        let mut decisions = self.ordered_decisions(state, ply);
+        if decisions.is_empty() {
+            return self.heuristic.evaluate(state, ctx.team);
+        }
+        move_to_front(&mut decisions, hash_move);
+
+        let mut best_move = None;
+        let value = if state.current_team() == ctx.team {
+            for (moves, next) in decisions {
+                let value = self.alpha_beta(ctx, &next, depth - 1, ply + 1, alpha, beta);
+                if value > alpha {
+                    alpha = value;
+                    ctx.update_pv(ply, &moves);
+                    best_move = Some(moves[0].clone());
+                }
+                if alpha >= beta {
+                    self.record_cutoff(&moves[0], ply, depth);
+                    break;
+                }
+            }
+            alpha
+        } else {
+            for (moves, next) in decisions {
+                let value = self.alpha_beta(ctx, &next, depth - 1, ply + 1, alpha, beta);
+                if value < beta {
+                    beta = value;
+                    ctx.update_pv(ply, &moves);
+                    best_move = Some(moves[0].clone());
+                }
+                if alpha >= beta {
+                    self.record_cutoff(&moves[0], ply, depth);
+                    break;
+                }
+            }
+            beta
+        };
+
+        if let (Some(hash), false) = (hash, ctx.canceled.load(Ordering::Relaxed)) {
+            let bound = if value <= original_alpha {
+                Bound::Upper
+            } else if value >= original_beta {
+                Bound::Lower
+            } else {
+                Bound::Exact
+            };
+            self.store(hash, depth as u32, SearchEntry { value, bound, best_move });
+        }
+        value
+    }
+
+    /// Hashes the position for the transposition table, if there is one.
+    fn hash(&self, state: &GameState, team: Team) -> Option<u64> {
+        self.table.as_ref().map(|_| state.zobrist_hash() ^ if team == Team::Two { TEAM_TWO_SALT } else { 0 })
+    }
+
+    fn probe(&self, hash: u64) -> Option<TranspositionEntry<SearchEntry>> {
+        lock(self.table.as_ref()?).get(hash).cloned()
+    }
+
+    fn store(&self, hash: u64, depth: u32, entry: SearchEntry) {
+        if let Some(table) = &self.table {
+            lock(table).insert(hash, depth, entry);
+        }
+    }
+
+    /// Prefers replacing the table entries of previous searches.
+    fn new_generation(&self) {
+        if let Some(table) = &self.table {
+            lock(table).new_generation();
+        }
+    }
+
+    /// Generates the decisions of the team to move, sorted by
+    /// their first move if a move ordering is configured.
+    fn ordered_decisions(&self, state: &GameState, ply: usize) -> Vec<Decision> {
+        let mut decisions = self.decisions(state);
+        if let Some(ordering) = &self.ordering {
+            lock(ordering).order_by_key(state, ply, &mut decisions, |(moves, _)| &moves[0]);
+        }
+        decisions
+    }
+
+    fn record_cutoff(&self, game_move: &Move, ply: usize, depth: usize) {
+        if let Some(ordering) = &self.ordering {
+            let mut ordering = lock(ordering);
+            ordering.record_killer(ply, game_move);
+            ordering.record_history(game_move, depth);
+        }
+    }
+
+    /// Generates the decisions of the team to move.
+    fn decisions(&self, state: &GameState) -> Vec<Decision> {
+        let team = state.current_team();
+        let mut decisions = Vec::new();
+
+        for game_move in state.possible_moves() {
+            let mut next = state.clone();
+            if next.perform_move_unchecked(game_move.clone()).is_err() {
+                continue;
+            }
+
+            if self.mode == SearchMode::Team {
+                if let Some((skips, after_skips)) = forced_skips_until(&next, team) {
+                    let follow_ups: Vec<_> = after_skips.possible_moves().filter(|m| matches!(m, Move::Set { .. })).collect();
+                    if !follow_ups.is_empty() {
+                        for follow_up in follow_ups {
+                            let mut after = after_skips.clone();
+                            if after.perform_move_unchecked(follow_up.clone()).is_ok() {
+                                let moves = Some(game_move.clone()).into_iter().chain(skips.iter().cloned()).chain(Some(follow_up)).collect();
+                                decisions.push((moves, after));
+                            }
+                        }
+                        continue;
+                    }
+                }
+            }
+
+            decisions.push((vec![game_move], next));
+        }
+
+        decisions
+    }
+}
+
+/// Locks the mutex, ignoring poisoning since the guarded
+/// search data stays usable after a panicking heuristic.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Moves the decision starting with the given move (if any) to the front.
+fn move_to_front(decisions: &mut [Decision], first: Option<Move>) {
+    if let Some(i) = first.and_then(|m| decisions.iter().position(|(moves, _)| moves[0] == m)) {
+        decisions[..=i].rotate_right(1);
+    }
+}
+
+/// Performs the skips of the colors that cannot place any
+/// piece until it is the given team's turn again. Returns
+/// `None` if another team can actually place a piece.
+fn forced_skips_until(state: &GameState, team: Team) -> Option<(Vec<Move>, GameState)> {
+    let mut state = state.clone();
+    let mut skips = Vec::new();
+
+    while state.current_team() != team {
+        if skips.len() >= COLOR_COUNT || state.rules.is_past_round_limit(state.round) || can_place(&state) {
+            return None;
+        }
+        let skip = Move::Skip { color: state.current_color() };
+        state.perform_move_unchecked(skip.clone()).ok()?;
+        skips.push(skip);
+    }
+
+    Some((skips, state))
+}
+
+/// Checks whether the current color can place any piece.
+fn can_place(state: &GameState) -> bool {
+    if state.is_first_move() {
+        state.possible_placements(&state.start_piece).next().is_some()
+    } else {
+        state.undeployed_shapes_of_color(state.current_color()).any(|kind| state.possible_placements(kind).next().is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::eval::PointDifference;
+    use crate::game::{Color, GameState, Move, PIECE_SHAPES_BY_NAME};
+    use std::thread;
+    use std::time::Duration;
+    use crate::search::{GameTree, MoveOrdering, SearchHandle, SearchMode, TranspositionTable};
+    use crate::telemetry::Metrics;
+    use super::{AlphaBeta, SearchEntry};
+
+    /// A state after the first round in which only blue and red
+    /// have a domino left, so yellow has to skip between them.
+    fn endgame() -> GameState {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["MONO"].clone());
+        for _ in 0..4 {
+            let first_move = state.possible_moves().next().unwrap();
+            state.perform_move(first_move).unwrap();
+        }
+        state.blue_shapes = [PIECE_SHAPES_BY_NAME["DOMINO"].clone()].into_iter().collect();
+        state.red_shapes = state.blue_shapes.clone();
+        state.yellow_shapes.clear();
+        state.green_shapes.clear();
+        state
+    }
+
+    #[test]
+    fn test_team_mode() {
+        let state = endgame();
+        assert_eq!(state.current_color(), Color::Blue);
+
+        let (moves, _) = AlphaBeta::new(PointDifference).best_decision(&state).unwrap();
+        assert_eq!(moves.len(), 1);
+
+        let search = AlphaBeta::new(PointDifference).mode(SearchMode::Team);
+        let (moves, value) = search.best_decision(&state).unwrap();
+        assert_eq!(moves.len(), 3);
+        assert_eq!(moves[0].color(), Color::Blue);
+        assert_eq!(moves[1], Move::Skip { color: Color::Yellow });
+        assert_eq!(moves[2].color(), Color::Red);
+        assert!(state.validate_move(&moves[0]).is_ok());
+
+        // Red's domino (and its 15 bonus points for placing every
+        // piece) is part of the decision, unlike in the per-color search
+        let (_, per_color_value) = AlphaBeta::new(PointDifference).best_decision(&state).unwrap();
+        assert_eq!(value, per_color_value + 17.0);
+    }
+
+    #[test]
+    fn test_ordering_keeps_value() {
+        let state = endgame();
+        let plain = AlphaBeta::new(PointDifference).depth(3);
+        let ordered = AlphaBeta::new(PointDifference).depth(3).ordering(MoveOrdering::new());
+        assert_eq!(plain.best_decision(&state).unwrap().1, ordered.best_decision(&state).unwrap().1);
+    }
+
+    #[test]
+    fn test_transposition_table_keeps_value() {
+        let state = endgame();
+        let plain = AlphaBeta::new(PointDifference).depth(3).mode(SearchMode::Team);
+        let cached = AlphaBeta::new(PointDifference).depth(3).mode(SearchMode::Team).transposition_table(TranspositionTable::new(1 << 12));
+        let expected = plain.best_decision(&state).unwrap().1;
+        assert_eq!(cached.best_decision(&state).unwrap().1, expected);
+        // The second search may reuse the entries of the first one
+        assert_eq!(cached.iterative_deepening(&state, &SearchHandle::new(), |_| {}).unwrap().score, expected);
+    }
+
+    #[test]
+    fn test_iterative_deepening() {
+        let state = endgame();
+        let search = AlphaBeta::new(PointDifference).depth(3);
+        let handle = SearchHandle::new();
+        let mut depths = Vec::new();
+        let result = search.iterative_deepening(&state, &handle, |r| depths.push(r.depth)).unwrap();
+        assert_eq!(depths, [1, 2, 3]);
+        assert!(handle.is_finished());
+        assert_eq!(handle.latest(), Some(result.clone()));
+        assert_eq!(result.pv[0], result.best_move);
+        assert_eq!(result.pv[1].color(), Color::Yellow);
+
+        let nodes_before = Metrics::global().search_nodes.get();
+        let fixed = search.search(&state).unwrap();
+        assert_eq!((fixed.score, fixed.depth, &fixed.pv), (result.score, 3, &result.pv));
+        // The nodes are added to the global metrics once the search has finished
+        assert!(Metrics::global().search_nodes.get() >= nodes_before + fixed.nodes);
+    }
+
+    #[test]
+    fn test_cancel() {
+        let state = GameState::new(PIECE_SHAPES_BY_NAME["PENTO_L"].clone());
+        let handle = AlphaBeta::new(PointDifference).depth(100).spawn(state.clone());
+        while handle.completed_depth() == 0 {
+            thread::sleep(Duration::from_millis(1));
+        }
+        let canceler = handle.clone();
+        thread::spawn(move || canceler.cancel()).join().unwrap();
+        let result = handle.wait().unwrap();
+        assert!(result.depth < 100);
+        assert!(state.validate_move(&handle.best_move().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<AlphaBeta<PointDifference>>();
+        assert_send_sync::<TranspositionTable<SearchEntry>>();
+        assert_send_sync::<MoveOrdering>();
+        assert_send_sync::<SearchHandle>();
+        assert_send_sync::<GameTree>();
+    }
+}
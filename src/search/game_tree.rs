@@ -0,0 +1,232 @@
+use std::fmt::Write;
+use crate::eval::Heuristic;
+use crate::game::{GameState, Move, Team};
+#[cfg(feature = "json")]
+use crate::util::{JsonValue, ToJson};
+
+/// A node in an expanded game tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameTreeNode {
+    /// The move leading to this node, `None` for the root.
+    pub game_move: Option<Move>,
+    /// The team to move in this node's state.
+    pub team: Team,
+    /// The static evaluation of this node's state, if evaluated.
+    pub value: Option<f64>,
+    /// The minimax value backed up from the leaves, if evaluated.
+    pub backed_up: Option<f64>,
+    pub children: Vec<GameTreeNode>
+}
+
+impl GameTreeNode {
+    /// Counts the nodes in this subtree.
+    pub fn size(&self) -> usize {
+        1 + self.children.iter().map(GameTreeNode::size).sum::<usize>()
+    }
+
+    /// Fetches the child with the best backed-up value for the
+    /// team to move, i.e. the one a minimax search would choose.
+    pub fn best_child(&self, perspective: Team) -> Option<&GameTreeNode> {
+        let key = |child: &&GameTreeNode| child.backed_up.unwrap_or(f64::NAN);
+        if self.team == perspective {
+            self.children.iter().max_by(|a, b| key(a).total_cmp(&key(b)))
+        } else {
+            self.children.iter().min_by(|a, b| key(a).total_cmp(&key(b)))
+        }
+    }
+}
+
+/// A game tree expanded to a fixed depth for debugging,
+/// exportable as Graphviz DOT or JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameTree {
+    pub root: GameTreeNode,
+    /// The team the values are computed for, if evaluated.
+    pub perspective: Option<Team>
+}
+
+impl GameTree {
+    /// Expands the tree to the given depth (in moves) without evaluating it.
+    pub fn expand(state: &GameState, depth: usize) -> Self {
+        GameTreeExpander::new(depth).expand(state)
+    }
+
+    /// Fetches the moves a minimax search would play
+    /// according to the backed-up values.
+    pub fn principal_variation(&self) -> Vec<Move> {
+        let mut moves = Vec::new();
+        if let Some(team) = self.perspective {
+            let mut node = &self.root;
+            while let Some(child) = node.best_child(team) {
+                moves.extend(child.game_move.clone());
+                node = child;
+            }
+        }
+        moves
+    }
+
+    /// Exports the tree in the Graphviz DOT format, with the
+    /// edges of the principal variation drawn in bold.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph GameTree {\n    node [shape=box, fontname=monospace];\n");
+        let mut next_id = 0;
+        self.write_dot(&self.root, &mut next_id, true, &mut dot);
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn write_dot(&self, node: &GameTreeNode, next_id: &mut usize, on_pv: bool, dot: &mut String) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+
+        let mut label = format!("{} to move", node.team);
+        if let Some(value) = node.value {
+            write!(label, "\\nvalue: {}", value).unwrap();
+        }
+        if let Some(backed_up) = node.backed_up {
+            write!(label, "\\nminimax: {}", backed_up).unwrap();
+        }
+        writeln!(dot, "    n{} [label=\"{}\"];", id, label).unwrap();
+
+        let best = self.perspective.and_then(|team| node.best_child(team)).map(|child| child as *const _);
+        for child in &node.children {
+            let child_on_pv = on_pv && best == Some(child as *const _);
+            let child_id = self.write_dot(child, next_id, child_on_pv, dot);
+            let edge_label = child.game_move.as_ref().map(Move::to_string).unwrap_or_default();
+            let style = if child_on_pv { ", style=bold" } else { "" };
+            writeln!(dot, "    n{} -> n{} [label=\"{}\"{}];", id, child_id, edge_label, style).unwrap();
+        }
+
+        id
+    }
+}
+
+#[cfg(feature = "json")]
+impl ToJson for GameTreeNode {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object([
+            ("move", self.game_move.as_ref().map(Move::to_json).unwrap_or(JsonValue::Null)),
+            ("team", self.team.to_json()),
+            ("value", self.value.into()),
+            ("minimax", self.backed_up.into()),
+            ("children", JsonValue::array(self.children.iter().map(GameTreeNode::to_json)))
+        ])
+    }
+}
+
+#[cfg(feature = "json")]
+impl ToJson for GameTree {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object([
+            ("perspective", self.perspective.as_ref().map(Team::to_json).unwrap_or(JsonValue::Null)),
+            ("principalVariation", JsonValue::array(self.principal_variation().iter().map(Move::to_json))),
+            ("root", self.root.to_json())
+        ])
+    }
+}
+
+/// Configures how a game tree is expanded.
+pub struct GameTreeExpander<'a> {
+    depth: usize,
+    max_children: Option<usize>,
+    evaluation: Option<(&'a dyn Heuristic, Team)>
+}
+
+impl<'a> GameTreeExpander<'a> {
+    /// Creates an expander for the given depth (in moves)
+    /// that expands every move and evaluates nothing.
+    pub fn new(depth: usize) -> Self {
+        Self { depth, max_children: None, evaluation: None }
+    }
+
+    /// Limits the number of children per node. If the tree is
+    /// evaluated, the most promising children for the team to
+    /// move are kept, otherwise the first generated ones.
+    pub fn max_children(mut self, max_children: usize) -> Self {
+        self.max_children = Some(max_children);
+        self
+    }
+
+    /// Evaluates every node with the given heuristic
+    /// from the perspective of the given team.
+    pub fn evaluate_with(mut self, heuristic: &'a dyn Heuristic, team: Team) -> Self {
+        self.evaluation = Some((heuristic, team));
+        self
+    }
+
+    /// Expands the tree from the given state.
+    pub fn expand(&self, state: &GameState) -> GameTree {
+        GameTree {
+            root: self.expand_node(state, None, self.depth),
+            perspective: self.evaluation.map(|(_, team)| team)
+        }
+    }
+
+    fn expand_node(&self, state: &GameState, game_move: Option<Move>, depth: usize) -> GameTreeNode {
+        let team = state.current_team();
+        let value = self.evaluation.map(|(heuristic, perspective)| heuristic.evaluate(state, perspective));
+
+        let mut children = Vec::new();
+        if depth > 0 && !state.rules.is_past_round_limit(state.round) {
+            for next_move in state.possible_moves() {
+                let mut next = state.clone();
+                if next.perform_move_unchecked(next_move.clone()).is_ok() {
+                    children.push(self.expand_node(&next, Some(next_move), depth - 1));
+                }
+            }
+        }
+
+        if let Some((_, perspective)) = self.evaluation {
+            // Sort the most promising children for the team to move first
+            children.sort_by(|a, b| {
+                let (a, b) = (a.backed_up.unwrap_or(f64::NAN), b.backed_up.unwrap_or(f64::NAN));
+                if team == perspective { b.total_cmp(&a) } else { a.total_cmp(&b) }
+            });
+        }
+        if let Some(max_children) = self.max_children {
+            children.truncate(max_children);
+        }
+
+        let mut node = GameTreeNode { game_move, team, value, backed_up: None, children };
+        node.backed_up = match self.evaluation {
+            Some((_, perspective)) => node.best_child(perspective).and_then(|child| child.backed_up).or(value),
+            None => None
+        };
+        node
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::eval::PointDifference;
+    use crate::game::{GameState, PIECE_SHAPES_BY_NAME, Team};
+    #[cfg(feature = "json")]
+    use crate::util::ToJson;
+    use super::{GameTree, GameTreeExpander};
+
+    #[test]
+    fn test_expansion_and_export() {
+        let state = GameState::new(PIECE_SHAPES_BY_NAME["MONO"].clone());
+        let tree = GameTree::expand(&state, 2);
+        assert_eq!(tree.root.children.len(), state.possible_moves().count());
+        let grandchildren: usize = state.possible_moves().map(|game_move| {
+            let mut next = state.clone();
+            next.perform_move(game_move).unwrap();
+            next.possible_moves().count()
+        }).sum();
+        assert_eq!(tree.root.size(), 1 + tree.root.children.len() + grandchildren);
+        assert!(tree.principal_variation().is_empty());
+
+        let tree = GameTreeExpander::new(2).max_children(2).evaluate_with(&PointDifference, Team::One).expand(&state);
+        assert_eq!(tree.root.size(), 1 + 2 + 2 * 2);
+        assert_eq!(tree.principal_variation().len(), 2);
+        assert!(tree.root.backed_up.is_some());
+
+        let dot = tree.to_dot();
+        assert!(dot.starts_with("digraph GameTree {"));
+        assert_eq!(dot.matches(" -> ").count(), 6);
+        assert_eq!(dot.matches("style=bold").count(), 2);
+        #[cfg(feature = "json")]
+        assert_eq!(tree.to_json().get("root").unwrap().get("children").unwrap().as_array().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,17 @@
+//! Game tree search on top of the evaluation functions.
+
+mod alpha_beta;
+mod game_tree;
+mod move_ordering;
+mod search_handle;
+mod search_mode;
+mod search_result;
+mod transposition_table;
+
+pub use alpha_beta::*;
+pub use game_tree::*;
+pub use move_ordering::*;
+pub use search_handle::*;
+pub use search_mode::*;
+pub use search_result::*;
+pub use transposition_table::*;
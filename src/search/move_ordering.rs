@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use crate::game::{BOARD_SIZE, BoardMask, GameState, Move};
+
+/// The number of killer moves remembered per ply.
+const KILLERS_PER_PLY: usize = 2;
+
+/// Orders moves so that promising ones are searched first,
+/// which lets alpha-beta searches cut off more branches.
+///
+/// Moves are ranked by killer moves (quiet moves that caused
+/// cutoffs at the same ply), then by the history table (how
+/// often and how deep a move caused cutoffs) and finally by
+/// a weighted sum of cheap static criteria.
+#[derive(Debug, Clone)]
+pub struct MoveOrdering {
+    size_weight: f64,
+    center_weight: f64,
+    corner_weight: f64,
+    killers: Vec<[Option<Move>; KILLERS_PER_PLY]>,
+    history: HashMap<Move, u64>
+}
+
+impl MoveOrdering {
+    /// Creates an ordering that prefers large pieces, then
+    /// pieces close to the center and pieces opening corners.
+    pub fn new() -> Self {
+        Self { size_weight: 1.0, center_weight: 0.1, corner_weight: 0.5, killers: Vec::new(), history: HashMap::new() }
+    }
+
+    /// Sets the weight of the placed piece's size.
+    pub fn size_weight(mut self, weight: f64) -> Self {
+        self.size_weight = weight;
+        self
+    }
+
+    /// Sets the weight of the placed piece's proximity to the board's center.
+    pub fn center_weight(mut self, weight: f64) -> Self {
+        self.center_weight = weight;
+        self
+    }
+
+    /// Sets the weight of the number of corners the placed piece opens up.
+    pub fn corner_weight(mut self, weight: f64) -> Self {
+        self.corner_weight = weight;
+        self
+    }
+
+    /// Computes the static score of a move in the given state.
+    /// Skips score lowest.
+    pub fn static_score(&self, state: &GameState, game_move: &Move) -> f64 {
+        let piece = match game_move {
+            Move::Set { piece } => piece,
+            Move::Skip { .. } => return f64::NEG_INFINITY
+        };
+        let placed: BoardMask = piece.coordinates().collect();
+
+        let center = (BOARD_SIZE as f64 - 1.0) / 2.0;
+        let distance = placed.into_iter()
+            .map(|p| (p.x as f64 - center).abs() + (p.y as f64 - center).abs())
+            .sum::<f64>() / placed.count() as f64;
+        let proximity = center * 2.0 - distance;
+
+        let own = state.board.color_mask(piece.color) | placed;
+        let occupancy = state.board.occupancy_mask() | placed;
+        let opened = placed.dilate_diagonal() - own.dilate() - occupancy - state.anchors(piece.color);
+
+        self.size_weight * placed.count() as f64
+            + self.center_weight * proximity
+            + self.corner_weight * opened.count() as f64
+    }
+
+    /// Remembers a move that caused a cutoff at the given ply.
+    pub fn record_killer(&mut self, ply: usize, game_move: &Move) {
+        if self.killers.len() <= ply {
+            self.killers.resize(ply + 1, Default::default());
+        }
+        let killers = &mut self.killers[ply];
+        if killers[0].as_ref() != Some(game_move) {
+            killers.rotate_right(1);
+            killers[0] = Some(game_move.clone());
+        }
+    }
+
+    /// Checks whether the given move is a killer move at the given ply.
+    pub fn is_killer(&self, ply: usize, game_move: &Move) -> bool {
+        self.killers.get(ply).is_some_and(|killers| killers.iter().any(|k| k.as_ref() == Some(game_move)))
+    }
+
+    /// Credits a move that caused a cutoff after searching
+    /// the given remaining depth. Deeper cutoffs weigh more.
+    pub fn record_history(&mut self, game_move: &Move, depth: usize) {
+        *self.history.entry(game_move.clone()).or_insert(0) += (depth * depth) as u64;
+    }
+
+    /// Fetches the history score of a move.
+    pub fn history_score(&self, game_move: &Move) -> u64 {
+        self.history.get(game_move).copied().unwrap_or(0)
+    }
+
+    /// Forgets the killer moves and the history, e.g. between turns.
+    pub fn clear(&mut self) {
+        self.killers.clear();
+        self.history.clear();
+    }
+
+    /// Sorts the given moves from most to least promising.
+    pub fn order(&self, state: &GameState, ply: usize, moves: &mut Vec<Move>) {
+        self.order_by_key(state, ply, moves, |game_move| game_move);
+    }
+
+    /// Sorts arbitrary items by the given moves from most
+    /// to least promising, e.g. moves along with their states.
+    pub fn order_by_key<T>(&self, state: &GameState, ply: usize, items: &mut Vec<T>, key: impl Fn(&T) -> &Move) {
+        let mut ranked: Vec<_> = items.drain(..).map(|item| {
+            let game_move = key(&item);
+            ((self.is_killer(ply, game_move), self.history_score(game_move), self.static_score(state, game_move)), item)
+        }).collect();
+        ranked.sort_by(|(a, _), (b, _)| b.0.cmp(&a.0).then(b.1.cmp(&a.1)).then(b.2.total_cmp(&a.2)));
+        items.extend(ranked.into_iter().map(|(_, item)| item));
+    }
+}
+
+impl Default for MoveOrdering {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{GameState, Move, PIECE_SHAPES_BY_NAME};
+    use super::MoveOrdering;
+
+    #[test]
+    fn test_ordering() {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["DOMINO"].clone());
+        for _ in 0..4 {
+            state.perform_move(state.possible_moves().next().unwrap()).unwrap();
+        }
+
+        let mut ordering = MoveOrdering::new();
+        let mut moves: Vec<_> = state.possible_moves().collect();
+        ordering.order(&state, 0, &mut moves);
+        let scores: Vec<_> = moves.iter().map(|m| ordering.static_score(&state, m)).collect();
+        assert!(scores.windows(2).all(|w| w[0] >= w[1]));
+        assert!(matches!(&moves[0], Move::Set { piece } if piece.kind.size() == 5));
+        assert!(matches!(moves.last(), Some(Move::Skip { .. })));
+
+        // Killer moves come first, then moves with history
+        let (killer, historic) = (moves[10].clone(), moves[20].clone());
+        ordering.record_killer(0, &killer);
+        ordering.record_history(&historic, 2);
+        ordering.order(&state, 0, &mut moves);
+        assert_eq!(moves[0], killer);
+        assert_eq!(moves[1], historic);
+        ordering.order(&state, 1, &mut moves);
+        assert_eq!(moves[0], historic);
+    }
+}
@@ -0,0 +1,107 @@
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use crate::game::Move;
+use super::SearchResult;
+
+#[derive(Default)]
+struct Progress {
+    latest: Option<SearchResult>,
+    finished: bool
+}
+
+#[derive(Default)]
+struct Shared {
+    canceled: AtomicBool,
+    progress: Mutex<Progress>,
+    finished: Condvar
+}
+
+/// A handle to a running search that can be shared with other
+/// threads (e.g. a watchdog enforcing the time limit or the
+/// logic that stops pondering once the opponent has moved).
+/// It provides the result of the deepest completed iteration
+/// at any time and cancels the search on request.
+#[derive(Clone, Default)]
+pub struct SearchHandle {
+    shared: Arc<Shared>
+}
+
+impl SearchHandle {
+    /// Creates a handle for a search that has not started yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks the search to stop as soon as possible. The
+    /// iteration in progress is discarded.
+    pub fn cancel(&self) {
+        self.shared.canceled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the search has been asked to stop.
+    pub fn is_canceled(&self) -> bool {
+        self.shared.canceled.load(Ordering::Relaxed)
+    }
+
+    /// Whether the search has stopped, either because it
+    /// reached its maximum depth or because it was canceled.
+    pub fn is_finished(&self) -> bool {
+        self.progress().finished
+    }
+
+    /// The result of the deepest completed iteration.
+    pub fn latest(&self) -> Option<SearchResult> {
+        self.progress().latest.clone()
+    }
+
+    /// The best move found so far.
+    pub fn best_move(&self) -> Option<Move> {
+        self.progress().latest.as_ref().map(|r| r.best_move.clone())
+    }
+
+    /// The depth of the deepest completed iteration (0 if there is none yet).
+    pub fn completed_depth(&self) -> usize {
+        self.progress().latest.as_ref().map_or(0, |r| r.depth)
+    }
+
+    /// Blocks until the search has finished and returns its final result.
+    pub fn wait(&self) -> Option<SearchResult> {
+        let mut progress = self.progress();
+        while !progress.finished {
+            progress = self.shared.finished.wait(progress).unwrap_or_else(|e| e.into_inner());
+        }
+        progress.latest.clone()
+    }
+
+    /// Waits for the search to finish for at most the given
+    /// time, then cancels it and returns the best result.
+    pub fn wait_timeout(&self, limit: Duration) -> Option<SearchResult> {
+        let progress = self.progress();
+        let (progress, _) = self.shared.finished
+            .wait_timeout_while(progress, limit, |p| !p.finished)
+            .unwrap_or_else(|e| e.into_inner());
+        self.cancel();
+        progress.latest.clone()
+    }
+
+    /// The flag the search polls to notice a cancellation.
+    pub(super) fn canceled_flag(&self) -> &AtomicBool {
+        &self.shared.canceled
+    }
+
+    /// Publishes the result of a completed iteration.
+    pub(super) fn publish(&self, result: SearchResult) {
+        self.progress().latest = Some(result);
+    }
+
+    /// Marks the search as finished, waking up waiting threads.
+    pub(super) fn finish(&self) {
+        self.progress().finished = true;
+        self.shared.finished.notify_all();
+    }
+
+    fn progress(&self) -> MutexGuard<'_, Progress> {
+        self.shared.progress.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
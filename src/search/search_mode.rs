@@ -0,0 +1,11 @@
+/// Determines what the search considers a single decision.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum SearchMode {
+    /// Every color decides on its own, one move per ply.
+    #[default]
+    PerColor,
+    /// Whenever both colors of a team move consecutively
+    /// (because the opponent color in between can only skip),
+    /// their placements are searched jointly as one decision.
+    Team
+}
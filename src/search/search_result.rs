@@ -0,0 +1,60 @@
+use std::fmt;
+use std::time::Duration;
+use crate::game::Move;
+#[cfg(feature = "json")]
+use crate::util::{JsonValue, ToJson};
+
+/// The outcome of a search, e.g. of one completed
+/// iteration of an iterative deepening search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    /// The move to play.
+    pub best_move: Move,
+    /// The value of the best move from the perspective of the team to move.
+    pub score: f64,
+    /// The depth searched (0 if the move was not searched).
+    pub depth: usize,
+    /// The principal variation, i.e. the expected line
+    /// of play starting with the best move.
+    pub pv: Vec<Move>,
+    /// The number of visited nodes.
+    pub nodes: u64,
+    /// The time the search took.
+    pub time: Duration
+}
+
+impl SearchResult {
+    /// A result for a move that has been chosen without searching.
+    pub fn unsearched(best_move: Move, time: Duration) -> Self {
+        Self { pv: vec![best_move.clone()], best_move, score: 0.0, depth: 0, nodes: 0, time }
+    }
+}
+
+/// Formats the result as a single line for logging, e.g.
+/// `depth 3, score 12, 1409 nodes in 10ms, pv: BLUE ... | YELLOW skips`.
+impl fmt::Display for SearchResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "depth {}, score {}, {} nodes in {:?}, pv: ", self.depth, self.score, self.nodes, self.time)?;
+        for (i, game_move) in self.pv.iter().enumerate() {
+            if i > 0 {
+                write!(f, " | ")?;
+            }
+            write!(f, "{}", game_move)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "json")]
+impl ToJson for SearchResult {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object([
+            ("bestMove", self.best_move.to_json()),
+            ("score", self.score.into()),
+            ("depth", self.depth.into()),
+            ("pv", self.pv.to_json()),
+            ("nodes", self.nodes.into()),
+            ("millis", (self.time.as_millis() as u64).into())
+        ])
+    }
+}
@@ -0,0 +1,172 @@
+use std::mem;
+
+/// Decides whether a new entry replaces the one stored in its slot.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum ReplacementPolicy {
+    /// New entries always replace old ones.
+    Always,
+    /// New entries only replace entries that were searched at most
+    /// as deep, unless the old entry is from a previous generation.
+    #[default]
+    DepthPreferred
+}
+
+/// An entry in a transposition table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranspositionEntry<V> {
+    /// The full hash of the position, to detect index collisions.
+    pub hash: u64,
+    /// The depth the value was searched to.
+    pub depth: u32,
+    /// The generation the entry was stored in.
+    pub generation: u32,
+    pub value: V
+}
+
+/// A fixed-size table caching search results by the positions'
+/// Zobrist hashes. Since the size is fixed upfront, the memory
+/// usage does not grow over the course of a game; instead, old
+/// entries are replaced according to the replacement policy.
+///
+/// Bots searching once per turn should call `new_generation`
+/// before each search, so entries from previous searches
+/// are preferably replaced.
+#[derive(Debug, Clone)]
+pub struct TranspositionTable<V> {
+    slots: Vec<Option<TranspositionEntry<V>>>,
+    policy: ReplacementPolicy,
+    generation: u32,
+    len: usize
+}
+
+impl<V> TranspositionTable<V> {
+    /// Creates a table with the given number of slots,
+    /// rounded up to the next power of two.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1).next_power_of_two();
+        Self {
+            slots: (0..capacity).map(|_| None).collect(),
+            policy: ReplacementPolicy::default(),
+            generation: 0,
+            len: 0
+        }
+    }
+
+    /// Creates the largest table that fits into the given number of bytes.
+    pub fn with_memory_limit(bytes: usize) -> Self {
+        let slot_size = mem::size_of::<Option<TranspositionEntry<V>>>();
+        let slots = (bytes / slot_size).max(1);
+        // Round down to stay within the limit
+        Self::new(1 << (usize::BITS - 1 - slots.leading_zeros()))
+    }
+
+    /// Sets the replacement policy.
+    pub fn policy(mut self, policy: ReplacementPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// The number of slots.
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// The number of occupied slots.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks whether the table holds no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The current generation.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Starts a new generation, e.g. before searching the next turn.
+    pub fn new_generation(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Removes every entry.
+    pub fn clear(&mut self) {
+        self.slots.iter_mut().for_each(|slot| *slot = None);
+        self.len = 0;
+    }
+
+    fn index(&self, hash: u64) -> usize {
+        (hash as usize) & (self.slots.len() - 1)
+    }
+
+    /// Looks up the entry for the given hash.
+    pub fn get(&self, hash: u64) -> Option<&TranspositionEntry<V>> {
+        self.slots[self.index(hash)].as_ref().filter(|entry| entry.hash == hash)
+    }
+
+    /// Looks up the value for the given hash if it was
+    /// searched to at least the given depth.
+    pub fn probe(&self, hash: u64, min_depth: u32) -> Option<&V> {
+        self.get(hash).filter(|entry| entry.depth >= min_depth).map(|entry| &entry.value)
+    }
+
+    /// Stores a value, returning whether it was stored
+    /// (which depends on the replacement policy).
+    pub fn insert(&mut self, hash: u64, depth: u32, value: V) -> bool {
+        let index = self.index(hash);
+        let replace = match &self.slots[index] {
+            None => true,
+            Some(old) => match self.policy {
+                ReplacementPolicy::Always => true,
+                ReplacementPolicy::DepthPreferred => old.hash == hash || old.generation != self.generation || depth >= old.depth
+            }
+        };
+        if replace {
+            let entry = TranspositionEntry { hash, depth, generation: self.generation, value };
+            if self.slots[index].replace(entry).is_none() {
+                self.len += 1;
+            }
+        }
+        replace
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReplacementPolicy, TranspositionTable};
+
+    #[test]
+    fn test_replacement() {
+        let mut table = TranspositionTable::new(3);
+        assert_eq!(table.capacity(), 4);
+
+        // 1 and 5 share a slot
+        assert!(table.insert(1, 3, "deep"));
+        assert!(!table.insert(5, 2, "shallow"));
+        assert_eq!(table.probe(1, 3), Some(&"deep"));
+        assert_eq!(table.probe(1, 4), None);
+        assert_eq!(table.get(5), None);
+
+        table.new_generation();
+        assert!(table.insert(5, 2, "shallow"));
+        assert_eq!(table.get(1), None);
+        assert_eq!(table.get(5).unwrap().generation, 1);
+        assert_eq!(table.len(), 1);
+
+        let mut table = TranspositionTable::new(4).policy(ReplacementPolicy::Always);
+        table.insert(1, 3, "deep");
+        assert!(table.insert(5, 2, "shallow"));
+
+        table.clear();
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        let table = TranspositionTable::<f64>::with_memory_limit(1 << 20);
+        assert!(table.capacity().is_power_of_two());
+        assert!(table.capacity() * std::mem::size_of::<Option<super::TranspositionEntry<f64>>>() <= 1 << 20);
+    }
+}
@@ -0,0 +1,76 @@
+use std::fmt;
+use crate::game::GameState;
+use super::ReferenceTrace;
+
+/// A difference between a reference trace and
+/// the game as simulated by this crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Discrepancy {
+    /// The index of the step at which the discrepancy occurred
+    /// (which equals the number of steps for the final state).
+    pub step: usize,
+    /// The property that differs.
+    pub property: &'static str,
+    pub expected: String,
+    pub actual: String
+}
+
+impl fmt::Display for Discrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Step {}: expected {} {}, but got {}", self.step, self.property, self.expected, self.actual)
+    }
+}
+
+/// Replays the given trace using this crate's rule engine and
+/// compares the turn/round/color progression and the final
+/// scores. Replaying stops at the first move that is rejected.
+pub fn compare_with_trace(trace: &ReferenceTrace) -> Vec<Discrepancy> {
+    let mut state = GameState::new(trace.start_piece.clone());
+    let mut discrepancies = Vec::new();
+
+    fn check<T>(discrepancies: &mut Vec<Discrepancy>, step: usize, property: &'static str, expected: T, actual: T) where T: PartialEq + fmt::Debug {
+        if expected != actual {
+            discrepancies.push(Discrepancy { step, property, expected: format!("{:?}", expected), actual: format!("{:?}", actual) });
+        }
+    }
+
+    for (i, step) in trace.steps.iter().enumerate() {
+        check(&mut discrepancies, i, "turn", step.turn, state.turn);
+        check(&mut discrepancies, i, "round", step.round, state.round);
+        check(&mut discrepancies, i, "color", step.color, state.current_color());
+
+        if let Err(e) = state.perform_move_checked(step.game_move.clone()) {
+            let expected = format!("{:?} to be valid", step.game_move);
+            discrepancies.push(Discrepancy { step: i, property: "move", expected, actual: format!("{:?}", e) });
+            return discrepancies;
+        }
+    }
+
+    let end = trace.steps.len();
+    check(&mut discrepancies, end, "turn", trace.final_turn, state.turn);
+    check(&mut discrepancies, end, "round", trace.final_round, state.round);
+    for &(team, points) in &trace.final_points {
+        check(&mut discrepancies, end, "points", (team, points), (team, state.points_of_team(team)));
+    }
+
+    discrepancies
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sim::ReferenceTrace;
+    use super::compare_with_trace;
+
+    const TRACES: [(&str, &str); 1] = [
+        ("mono_domino", include_str!("../../fixtures/traces/mono_domino.trace"))
+    ];
+
+    #[test]
+    fn test_reference_traces() {
+        for (name, raw) in TRACES {
+            let trace: ReferenceTrace = raw.parse().unwrap();
+            let discrepancies = compare_with_trace(&trace);
+            assert!(discrepancies.is_empty(), "Trace {} differs:\n{}", name, discrepancies.iter().map(|d| d.to_string()).collect::<Vec<_>>().join("\n"));
+        }
+    }
+}
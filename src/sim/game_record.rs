@@ -0,0 +1,89 @@
+use crate::BotInfo;
+use crate::game::{GameState, Move, Team};
+use crate::util::RuleViolationKind;
+#[cfg(feature = "json")]
+use crate::util::{JsonValue, ToJson};
+
+/// Describes why a simulated game ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameOutcome {
+    /// The game ended regularly.
+    Regular,
+    /// The given team performed an invalid move and thus lost.
+    RuleViolation { team: Team, kind: Option<RuleViolationKind>, message: String }
+}
+
+/// A full log of a simulated game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRecord {
+    /// The state the game started with.
+    pub initial_state: GameState,
+    /// The state the game ended with.
+    pub final_state: GameState,
+    /// Every committed move, in order.
+    pub moves: Vec<Move>,
+    /// How the game ended.
+    pub outcome: GameOutcome,
+    /// The bot playing team one.
+    pub bot_one: BotInfo,
+    /// The bot playing team two.
+    pub bot_two: BotInfo
+}
+
+impl GameRecord {
+    /// The final points of the given team.
+    pub fn points(&self, team: Team) -> i32 {
+        self.final_state.points_of_team(team)
+    }
+
+    /// The bot playing the given team.
+    pub fn bot(&self, team: Team) -> &BotInfo {
+        match team {
+            Team::Two => &self.bot_two,
+            _ => &self.bot_one
+        }
+    }
+
+    /// Summarizes the game (without its states and moves) as JSON,
+    /// e.g. for archiving the results of long evaluation runs.
+    #[cfg(feature = "json")]
+    pub fn summary(&self) -> JsonValue {
+        let team = |team: Team| JsonValue::object([
+            ("bot", self.bot(team).to_json()),
+            ("points", self.points(team).into())
+        ]);
+        let outcome = match &self.outcome {
+            GameOutcome::Regular => JsonValue::from("Regular"),
+            GameOutcome::RuleViolation { team, kind, message } => JsonValue::object([("RuleViolation", JsonValue::object([
+                ("team", team.to_json()),
+                ("kind", kind.map(|k| k.to_string()).into()),
+                ("message", message.as_str().into())
+            ]))])
+        };
+        JsonValue::object([
+            ("one", team(Team::One)),
+            ("two", team(Team::Two)),
+            ("winner", self.winner().map(|t| t.to_json()).unwrap_or(JsonValue::Null)),
+            ("outcome", outcome),
+            ("moves", self.moves.len().into()),
+            ("start_piece", self.initial_state.start_piece.to_json())
+        ])
+    }
+
+    /// The winning team or `None` in case of a draw.
+    pub fn winner(&self) -> Option<Team> {
+        match self.outcome {
+            GameOutcome::RuleViolation { team, .. } => Some(team.opponent()),
+            GameOutcome::Regular => {
+                let (one, two) = (self.points(Team::One), self.points(Team::Two));
+                if one > two {
+                    Some(Team::One)
+                } else if two > one {
+                    Some(Team::Two)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use log::{debug, info, warn};
+use crate::client::{self, SCClientDelegate};
+use crate::game::{GameState, Move, PieceShape, Player, Team};
+use crate::protocol::{GameResult, PlayerScore, ScoreCause, ScoreDefinition};
+use crate::util::{SCError, SCResult};
+use super::{GameOutcome, GameRecord};
+
+/// Plays a game between two delegates locally, enforcing
+/// the turn order and the game rules like the server would.
+///
+/// The game ends once the round limit has been reached or
+/// every color in the game skipped in succession (i.e. no
+/// color wants to or can place another piece).
+pub struct GameRunner<A, B> where A: SCClientDelegate, B: SCClientDelegate {
+    one: A,
+    two: B,
+    state: GameState
+}
+
+impl<A, B> GameRunner<A, B> where A: SCClientDelegate, B: SCClientDelegate {
+    /// Creates a new runner with the first delegate playing
+    /// team one and the second one playing team two.
+    pub fn new(one: A, two: B, start_piece: PieceShape) -> Self {
+        Self::from_state(one, two, GameState::new(start_piece))
+    }
+
+    /// Creates a new runner that continues from the given state.
+    pub fn from_state(one: A, two: B, state: GameState) -> Self {
+        Self { one, two, state }
+    }
+
+    /// Plays the game to its end.
+    pub fn run(mut self) -> SCResult<(GameRecord, A, B)> {
+        let (bot_one, bot_two) = (self.one.bot_info(), self.two.bot_info());
+        info!("Playing {} against {}", bot_one, bot_two);
+        self.state.first.display_name = bot_one.name.clone();
+        self.state.second.display_name = bot_two.name.clone();
+
+        let initial_state = self.state.clone();
+        let mut moves = Vec::new();
+        let mut consecutive_skips = 0;
+
+        self.one.on_welcome_message(&Team::One);
+        self.two.on_welcome_message(&Team::Two);
+
+        let outcome = loop {
+            if self.state.rules.is_past_round_limit(self.state.round) || consecutive_skips >= self.state.valid_colors.len() {
+                break GameOutcome::Regular;
+            }
+
+            self.one.on_update_state(&self.state);
+            self.two.on_update_state(&self.state);
+
+            let team = self.state.current_team();
+            let game_move = match team {
+                Team::One => client::search_move(&mut self.one, &self.state, team),
+                Team::Two => client::search_move(&mut self.two, &self.state, team),
+                Team::None => return Err(SCError::protocol("Cannot request a move from team 'none'!"))
+            };
+            debug!("Team {} @ turn {} played {:?}", team, self.state.turn, game_move);
+
+            if let Err(e) = self.state.perform_move_checked(game_move.clone()) {
+                break GameOutcome::RuleViolation { team, kind: e.rule_violation_kind(), message: e.to_string() };
+            }
+
+            consecutive_skips = match game_move {
+                Move::Skip { .. } => consecutive_skips + 1,
+                Move::Set { .. } => 0
+            };
+            moves.push(game_move);
+        };
+
+        let record = GameRecord { initial_state, final_state: self.state, moves, outcome, bot_one, bot_two };
+        match &record.outcome {
+            GameOutcome::Regular => info!("Game ended after {} moves with {} : {}", record.moves.len(), record.points(Team::One), record.points(Team::Two)),
+            GameOutcome::RuleViolation { team, message, .. } => warn!("Team {} violated the rules: {}", team, message)
+        }
+
+        let result = Self::game_result(&record);
+        self.one.on_game_end(result.clone());
+        self.two.on_game_end(result);
+
+        Ok((record, self.one, self.two))
+    }
+
+    /// Creates a server-like game result from the given record.
+    fn game_result(record: &GameRecord) -> GameResult {
+        let state = &record.final_state;
+        let score = |team: Team| PlayerScore {
+            cause: match &record.outcome {
+                GameOutcome::RuleViolation { team: t, .. } if *t == team => ScoreCause::RuleViolation,
+                _ => ScoreCause::Regular
+            },
+            reason: match &record.outcome {
+                GameOutcome::RuleViolation { team: t, message, .. } if *t == team => message.clone(),
+                _ => String::new()
+            }
+        };
+        let player = |team: Team| -> Player {
+            match team {
+                Team::Two => state.second.clone(),
+                _ => state.first.clone()
+            }
+        };
+        GameResult {
+            definition: ScoreDefinition { fragments: Vec::new() },
+            scores: vec![score(Team::One), score(Team::Two)],
+            winners: record.winner().map(player).into_iter().collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::SCClientDelegate;
+    use crate::eval::PointDifference;
+    use crate::game::{GameState, Move, PIECE_SHAPES_BY_NAME, Team};
+    use crate::search::{AlphaBeta, SearchResult};
+    use crate::sim::{GameOutcome, Skipper};
+    #[cfg(feature = "json")]
+    use crate::util::JsonValue;
+    use crate::util::RuleViolationKind;
+    use super::GameRunner;
+
+    /// Tries to skip the first move.
+    struct Cheater;
+
+    impl SCClientDelegate for Cheater {
+        fn request_move(&mut self, state: &GameState, _my_team: Team) -> Move {
+            Move::Skip { color: state.current_color() }
+        }
+    }
+
+    /// Searches one move ahead and keeps the results.
+    #[derive(Default)]
+    struct Searcher {
+        results: Vec<SearchResult>
+    }
+
+    impl SCClientDelegate for Searcher {
+        fn on_search_finished(&mut self, result: &SearchResult) {
+            self.results.push(result.clone());
+        }
+
+        fn request_move(&mut self, state: &GameState, my_team: Team) -> Move {
+            self.request_search(state, my_team).best_move
+        }
+
+        fn request_search(&mut self, state: &GameState, _my_team: Team) -> SearchResult {
+            AlphaBeta::new(PointDifference).search(state).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_regular_game() {
+        let runner = GameRunner::new(Skipper, Skipper, PIECE_SHAPES_BY_NAME["MONO"].clone());
+        let (record, _, _) = runner.run().unwrap();
+        assert_eq!(record.outcome, GameOutcome::Regular);
+        assert_eq!(record.moves.len(), 8);
+        assert_eq!(record.points(Team::One), record.points(Team::Two));
+        assert_eq!(record.winner(), None);
+        assert_eq!(record.final_state.first.display_name, "Skipper");
+
+        #[cfg(feature = "json")]
+        {
+            let summary = record.summary();
+            assert_eq!(summary.get("one").unwrap().get("bot").unwrap().get("name").unwrap().as_str().unwrap(), "Skipper");
+            assert_eq!(summary.get("winner").unwrap(), &JsonValue::Null);
+        }
+    }
+
+    #[test]
+    fn test_rule_violation() {
+        let runner = GameRunner::new(Skipper, Cheater, PIECE_SHAPES_BY_NAME["MONO"].clone());
+        let (record, _, _) = runner.run().unwrap();
+        assert!(matches!(record.outcome, GameOutcome::RuleViolation { team: Team::Two, kind: Some(RuleViolationKind::SkipInFirstRound), .. }));
+        assert_eq!(record.moves.len(), 1);
+        assert_eq!(record.winner(), Some(Team::One));
+    }
+
+    #[test]
+    fn test_search_results() {
+        let runner = GameRunner::new(Searcher::default(), Skipper, PIECE_SHAPES_BY_NAME["MONO"].clone());
+        let (record, searcher, _) = runner.run().unwrap();
+        let searched: Vec<_> = searcher.results.iter().map(|r| r.best_move.clone()).collect();
+        let played: Vec<_> = record.moves.iter().filter(|m| m.color().team() == Team::One).cloned().collect();
+        assert_eq!(searched, played);
+        assert!(searcher.results.iter().all(|r| r.depth == 1 && r.nodes > 0 && r.pv[0] == r.best_move));
+    }
+}
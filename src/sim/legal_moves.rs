@@ -0,0 +1,160 @@
+use std::{collections::HashSet, fmt, str::FromStr};
+use crate::game::{GameState, Move};
+use crate::util::{SCError, SCResult};
+use super::{format_trace_move, parse_trace_move};
+
+/// The legal moves in a position, which the move generation of this
+/// crate can be compared against. The fixtures in this repository are
+/// regression snapshots of this crate's own move generation, so they
+/// catch changes in behavior, but not whether it matches the backend.
+///
+/// The format is line-based, with `#` starting comments and
+/// the moves described as in a `ReferenceTrace`:
+///
+/// ```text
+/// <color> SET <kind> <rotation> <flipped> <x> <y>
+/// <color> SKIP
+/// ...
+/// ```
+///
+/// Since different transformations of a shape may describe the
+/// same placement, moves are compared in their canonical form.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LegalMoves {
+    pub moves: Vec<Move>
+}
+
+/// The differences between the expected legal moves in
+/// a position and those generated by this crate.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LegalMovesDiff {
+    /// Expected moves that were not generated.
+    pub missing: Vec<Move>,
+    /// Generated moves that were not expected.
+    pub unexpected: Vec<Move>
+}
+
+impl LegalMoves {
+    /// Creates the list from the given moves, canonicalized,
+    /// deduplicated and sorted by color and encoding.
+    pub fn new(moves: impl IntoIterator<Item=Move>) -> Self {
+        let unique: HashSet<_> = moves.into_iter().map(canonical_move).collect();
+        let mut moves: Vec<_> = unique.into_iter().collect();
+        moves.sort_by_key(|m| (m.color() as u8, m.encode()));
+        Self { moves }
+    }
+
+    /// Fetches the legal moves in the given position
+    /// as generated by this crate's rules engine.
+    pub fn generate(state: &GameState) -> Self {
+        Self::new(state.possible_moves())
+    }
+
+    /// Compares the moves with those generated in the given position.
+    pub fn compare(&self, state: &GameState) -> LegalMovesDiff {
+        let expected = Self::new(self.moves.iter().cloned());
+        let actual = Self::generate(state);
+        let expected_set: HashSet<_> = expected.moves.iter().collect();
+        let actual_set: HashSet<_> = actual.moves.iter().collect();
+        LegalMovesDiff {
+            missing: expected.moves.iter().filter(|m| !actual_set.contains(m)).cloned().collect(),
+            unexpected: actual.moves.iter().filter(|m| !expected_set.contains(m)).cloned().collect()
+        }
+    }
+}
+
+impl LegalMovesDiff {
+    /// Whether the moves matched exactly.
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.unexpected.is_empty()
+    }
+}
+
+fn canonical_move(game_move: Move) -> Move {
+    match game_move {
+        Move::Set { piece } => Move::Set { piece: piece.canonicalize() },
+        skip => skip
+    }
+}
+
+impl FromStr for LegalMoves {
+    type Err = SCError;
+
+    fn from_str(raw: &str) -> SCResult<Self> {
+        let lines = raw.lines()
+            .map(|l| l.split('#').next().unwrap_or("").trim())
+            .enumerate()
+            .filter(|(_, l)| !l.is_empty());
+        let mut moves = Vec::new();
+
+        for (i, line) in lines {
+            let tokens: Vec<_> = line.split_whitespace().collect();
+            let error = || -> SCError { SCError::parse(format!("Invalid move line {}: {}", i + 1, line)) };
+            let (color, rest) = tokens.split_first().ok_or_else(error)?;
+            moves.push(parse_trace_move(color.parse()?, rest)?.ok_or_else(error)?);
+        }
+
+        Ok(Self { moves })
+    }
+}
+
+/// Writes the moves in the format described in `LegalMoves`.
+impl fmt::Display for LegalMoves {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for game_move in &self.moves {
+            writeln!(f, "{} {}", game_move.color(), format_trace_move(game_move))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for LegalMovesDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for game_move in &self.missing {
+            writeln!(f, "missing: {}", game_move)?;
+        }
+        for game_move in &self.unexpected {
+            writeln!(f, "unexpected: {}", game_move)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{COLORS, GameState, Move};
+    use super::LegalMoves;
+
+    const FIXTURES: [(&str, &str, &str); 3] = [
+        ("early", include_str!("../../fixtures/positions/early.xml"), include_str!("../../fixtures/legal_moves/early.moves")),
+        ("midgame", include_str!("../../fixtures/positions/midgame.xml"), include_str!("../../fixtures/legal_moves/midgame.moves")),
+        ("late", include_str!("../../fixtures/positions/late.xml"), include_str!("../../fixtures/legal_moves/late.moves"))
+    ];
+
+    #[test]
+    fn test_legal_move_snapshots() {
+        for (name, position, raw) in FIXTURES {
+            let state: GameState = position.parse().unwrap();
+            let expected: LegalMoves = raw.parse().unwrap();
+            assert!(!expected.moves.is_empty(), "Fixture {} is empty", name);
+            let diff = expected.compare(&state);
+            assert!(diff.is_empty(), "Legal moves in {} differ from the snapshot:\n{}", name, diff);
+        }
+    }
+
+    #[test]
+    fn test_format_roundtrip() {
+        let state: GameState = FIXTURES[1].1.parse().unwrap();
+        let moves = LegalMoves::generate(&state);
+        assert_eq!(moves.to_string().parse::<LegalMoves>().unwrap(), moves);
+
+        let mut incomplete = moves.clone();
+        let removed = incomplete.moves.pop().unwrap();
+        let foreign = Move::Skip { color: *COLORS.iter().find(|&&c| c != state.current_color()).unwrap() };
+        incomplete.moves.push(foreign.clone());
+        let diff = incomplete.compare(&state);
+        assert_eq!(diff.missing, vec![foreign]);
+        assert_eq!(diff.unexpected, vec![removed]);
+        assert!("BLUE PLACE MONO".parse::<LegalMoves>().is_err());
+    }
+}
@@ -0,0 +1,18 @@
+//! A local, headless simulator for playing games
+//! between delegates without a server.
+
+mod differential;
+mod game_record;
+mod game_runner;
+mod legal_moves;
+mod reference_trace;
+#[cfg(test)]
+mod test_delegates;
+
+pub use differential::*;
+pub use game_record::*;
+pub use game_runner::*;
+pub use legal_moves::*;
+pub use reference_trace::*;
+#[cfg(test)]
+pub(crate) use test_delegates::*;
@@ -0,0 +1,108 @@
+use std::str::FromStr;
+use crate::game::{Color, Move, Piece, PieceShape, Team, Vec2};
+use crate::util::{SCError, SCResult};
+
+/// Parses a move of the given color in the trace format, i.e. either
+/// `SKIP` or `SET <kind> <rotation> <flipped> <x> <y>`. Yields `None`
+/// if the tokens do not have this form.
+pub(crate) fn parse_trace_move(color: Color, tokens: &[&str]) -> SCResult<Option<Move>> {
+    Ok(match tokens {
+        ["SKIP"] => Some(Move::Skip { color }),
+        ["SET", kind, rotation, flipped, x, y] => Some(Move::Set {
+            piece: Piece {
+                kind: kind.parse()?,
+                rotation: rotation.parse()?,
+                is_flipped: flipped.parse()?,
+                color,
+                position: Vec2::new(x.parse()?, y.parse()?)
+            }
+        }),
+        _ => None
+    })
+}
+
+/// Formats a move in the trace format (without the color).
+pub(crate) fn format_trace_move(game_move: &Move) -> String {
+    match game_move {
+        Move::Skip { .. } => "SKIP".to_owned(),
+        Move::Set { piece } => format!("SET {} {} {} {} {}", piece.kind, piece.rotation, piece.is_flipped, piece.position.x, piece.position.y)
+    }
+}
+
+/// A single step of a reference trace, i.e. the
+/// progression state before a move and the move itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep {
+    pub turn: u32,
+    pub round: u32,
+    pub color: Color,
+    pub game_move: Move
+}
+
+/// A game trace with the expected turn/round/color progression
+/// and scores, which the rules engine of this crate can be checked
+/// against. The traces in `fixtures/traces` are hand-written from
+/// the documented rules rather than exported from the backend.
+///
+/// The format is line-based, with `#` starting comments:
+///
+/// ```text
+/// start <start piece>
+/// <turn> <round> <color> SET <kind> <rotation> <flipped> <x> <y>
+/// <turn> <round> <color> SKIP
+/// ...
+/// end <turn> <round> ONE <points> TWO <points>
+/// ```
+///
+/// Every move line describes the state before the move.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceTrace {
+    pub start_piece: PieceShape,
+    pub steps: Vec<TraceStep>,
+    pub final_turn: u32,
+    pub final_round: u32,
+    pub final_points: Vec<(Team, i32)>
+}
+
+impl FromStr for ReferenceTrace {
+    type Err = SCError;
+
+    fn from_str(raw: &str) -> SCResult<Self> {
+        let mut start_piece = None;
+        let mut steps = Vec::new();
+        let mut end = None;
+
+        let lines = raw.lines()
+            .map(|l| l.split('#').next().unwrap_or("").trim())
+            .enumerate()
+            .filter(|(_, l)| !l.is_empty());
+
+        for (i, line) in lines {
+            let tokens: Vec<_> = line.split_whitespace().collect();
+            let error = || -> SCError { SCError::parse(format!("Invalid trace line {}: {}", i + 1, line)) };
+            match tokens.as_slice() {
+                ["start", piece] => start_piece = Some(piece.parse()?),
+                ["end", turn, round, t1, p1, t2, p2] => end = Some((
+                    turn.parse()?,
+                    round.parse()?,
+                    vec![(t1.parse()?, p1.parse()?), (t2.parse()?, p2.parse()?)]
+                )),
+                [turn, round, color, rest @ ..] => {
+                    let color: Color = color.parse()?;
+                    let game_move = parse_trace_move(color, rest)?.ok_or_else(error)?;
+                    steps.push(TraceStep { turn: turn.parse()?, round: round.parse()?, color, game_move });
+                },
+                _ => return Err(error())
+            }
+        }
+
+        let (final_turn, final_round, final_points) = end.ok_or_else(|| SCError::parse("Trace has no 'end' line"))?;
+        Ok(Self {
+            start_piece: start_piece.ok_or_else(|| SCError::parse("Trace has no 'start' line"))?,
+            steps,
+            final_turn,
+            final_round,
+            final_points
+        })
+    }
+}
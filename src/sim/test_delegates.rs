@@ -0,0 +1,22 @@
+//! Simple delegates shared by the simulator's and the tournament's tests.
+
+use crate::BotInfo;
+use crate::client::SCClientDelegate;
+use crate::game::{GameState, Move, Team};
+
+/// Places the start piece and skips afterwards.
+pub(crate) struct Skipper;
+
+impl SCClientDelegate for Skipper {
+    fn bot_info(&self) -> BotInfo {
+        BotInfo::new("Skipper", "1.0").settings_summary("skips after the first move")
+    }
+
+    fn request_move(&mut self, state: &GameState, _my_team: Team) -> Move {
+        if state.is_first_move() {
+            state.possible_moves().next().unwrap()
+        } else {
+            Move::Skip { color: state.current_color() }
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// The upper bounds of the histogram buckets in seconds.
+pub const HISTOGRAM_BOUNDS: [f64; 8] = [0.00001, 0.0001, 0.001, 0.01, 0.1, 0.5, 1.0, 2.0];
+
+/// A distribution of durations, counted in buckets with
+/// the bounds from `HISTOGRAM_BOUNDS` (and an overflow bucket).
+#[derive(Debug, Default)]
+pub struct Histogram {
+    buckets: [AtomicU64; HISTOGRAM_BOUNDS.len() + 1],
+    sum_nanos: AtomicU64,
+    count: AtomicU64
+}
+
+impl Histogram {
+    /// Creates an empty histogram.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a duration.
+    pub fn observe(&self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        let bucket = HISTOGRAM_BOUNDS.iter().position(|&bound| seconds <= bound).unwrap_or(HISTOGRAM_BOUNDS.len());
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sum_nanos.fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records the time elapsed since the given instant.
+    pub fn observe_since(&self, start: Instant) {
+        self.observe(start.elapsed());
+    }
+
+    /// Runs the given function and records its duration.
+    pub fn time<R>(&self, f: impl FnOnce() -> R) -> R {
+        let start = Instant::now();
+        let result = f();
+        self.observe_since(start);
+        result
+    }
+
+    /// The number of recorded durations.
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// The sum of the recorded durations.
+    pub fn sum(&self) -> Duration {
+        Duration::from_nanos(self.sum_nanos.load(Ordering::Relaxed))
+    }
+
+    /// The mean of the recorded durations, if any.
+    pub fn mean(&self) -> Option<Duration> {
+        let count = self.count();
+        if count == 0 { None } else { Some(self.sum() / count as u32) }
+    }
+
+    /// The cumulative bucket counts, i.e. the number of durations
+    /// at most as long as the respective bound (the last one
+    /// counting every duration).
+    pub fn cumulative_counts(&self) -> Vec<u64> {
+        self.buckets.iter().scan(0, |total, bucket| {
+            *total += bucket.load(Ordering::Relaxed);
+            Some(*total)
+        }).collect()
+    }
+
+    /// Removes every recorded duration.
+    pub fn reset(&self) {
+        self.buckets.iter().chain([&self.sum_nanos, &self.count]).for_each(|value| value.store(0, Ordering::Relaxed));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use super::Histogram;
+
+    #[test]
+    fn test_histogram() {
+        let histogram = Histogram::new();
+        assert_eq!(histogram.mean(), None);
+        histogram.observe(Duration::from_micros(50));
+        histogram.observe(Duration::from_micros(150));
+        histogram.observe(Duration::from_secs(5));
+        assert_eq!(histogram.count(), 3);
+        assert_eq!(histogram.cumulative_counts(), vec![0, 1, 2, 2, 2, 2, 2, 2, 3]);
+        assert_eq!(histogram.sum(), Duration::from_micros(5_000_200));
+
+        histogram.reset();
+        assert_eq!(histogram.count(), 0);
+    }
+}
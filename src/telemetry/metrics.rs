@@ -0,0 +1,102 @@
+use std::fmt::Write;
+use lazy_static::lazy_static;
+use super::{Counter, HISTOGRAM_BOUNDS, Histogram};
+
+lazy_static! {
+    static ref GLOBAL: Metrics = Metrics::default();
+}
+
+/// The performance metrics collected by the client and the search.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    /// How long the delegate took to pick a move.
+    pub move_time: Histogram,
+    /// How long generating the possible moves at the root of a search took.
+    pub move_generation_time: Histogram,
+    /// How long parsing an incoming XML message took.
+    pub xml_parse_time: Histogram,
+    /// How long the searches took.
+    pub search_time: Histogram,
+    /// The number of nodes visited by the searches.
+    pub search_nodes: Counter
+}
+
+impl Metrics {
+    /// Fetches the metrics collected by the crate's instrumentation.
+    pub fn global() -> &'static Metrics {
+        &GLOBAL
+    }
+
+    /// The average number of nodes searched per second.
+    pub fn nodes_per_second(&self) -> f64 {
+        let seconds = self.search_time.sum().as_secs_f64();
+        if seconds > 0.0 { self.search_nodes.get() as f64 / seconds } else { 0.0 }
+    }
+
+    fn histograms(&self) -> [(&'static str, &Histogram); 4] {
+        [
+            ("socha_move_time_seconds", &self.move_time),
+            ("socha_move_generation_time_seconds", &self.move_generation_time),
+            ("socha_xml_parse_time_seconds", &self.xml_parse_time),
+            ("socha_search_time_seconds", &self.search_time)
+        ]
+    }
+
+    /// Renders the metrics in the Prometheus text exposition format.
+    pub fn render_prometheus(&self) -> String {
+        let mut s = String::new();
+        for (name, histogram) in self.histograms() {
+            writeln!(s, "# TYPE {} histogram", name).unwrap();
+            let counts = histogram.cumulative_counts();
+            for (bound, count) in HISTOGRAM_BOUNDS.iter().zip(&counts) {
+                writeln!(s, "{}_bucket{{le=\"{}\"}} {}", name, bound, count).unwrap();
+            }
+            writeln!(s, "{}_bucket{{le=\"+Inf\"}} {}", name, histogram.count()).unwrap();
+            writeln!(s, "{}_sum {}", name, histogram.sum().as_secs_f64()).unwrap();
+            writeln!(s, "{}_count {}", name, histogram.count()).unwrap();
+        }
+        writeln!(s, "# TYPE socha_search_nodes_total counter").unwrap();
+        writeln!(s, "socha_search_nodes_total {}", self.search_nodes.get()).unwrap();
+        writeln!(s, "# TYPE socha_search_nodes_per_second gauge").unwrap();
+        writeln!(s, "socha_search_nodes_per_second {}", self.nodes_per_second()).unwrap();
+        s
+    }
+
+    /// Summarizes the metrics in a single line, e.g. for logs.
+    pub fn summary(&self) -> String {
+        let mean = |histogram: &Histogram| histogram.mean().map(|mean| format!("{:?}", mean)).unwrap_or_else(|| "-".to_owned());
+        format!(
+            "{} moves in {} on average, move generation {}, XML parsing {}, {:.0} nodes/s",
+            self.move_time.count(), mean(&self.move_time), mean(&self.move_generation_time), mean(&self.xml_parse_time), self.nodes_per_second()
+        )
+    }
+
+    /// Resets every metric.
+    pub fn reset(&self) {
+        for (_, histogram) in self.histograms() {
+            histogram.reset();
+        }
+        self.search_nodes.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use super::Metrics;
+
+    #[test]
+    fn test_prometheus_format() {
+        let metrics = Metrics::default();
+        metrics.search_time.observe(Duration::from_millis(500));
+        metrics.search_nodes.add(1000);
+        assert_eq!(metrics.nodes_per_second(), 2000.0);
+
+        let text = metrics.render_prometheus();
+        assert!(text.contains("socha_search_time_seconds_bucket{le=\"0.5\"} 1\n"));
+        assert!(text.contains("socha_search_time_seconds_bucket{le=\"0.1\"} 0\n"));
+        assert!(text.contains("socha_search_time_seconds_count 1\n"));
+        assert!(text.contains("socha_search_nodes_total 1000\n"));
+        assert!(metrics.summary().starts_with("0 moves"));
+    }
+}
@@ -0,0 +1,11 @@
+//! Counters and histograms instrumenting the client and the
+//! search, exportable in the Prometheus text format.
+//! (The `metrics` module holds metrics on game states instead.)
+
+mod counter;
+mod histogram;
+mod metrics;
+
+pub use counter::*;
+pub use histogram::*;
+pub use metrics::*;
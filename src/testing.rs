@@ -0,0 +1,245 @@
+//! A mock game server replaying canned XML transcripts over a
+//! local TCP socket, for testing the full client loop (join,
+//! welcome, mementos, move requests and the result) together
+//! with a bot's delegate without the official server:
+//!
+//! ```no_run
+//! # use socha_client_2021::{client::SCClient, logic::RandomLogic, testing::MockServer};
+//! let transcript = std::fs::read_to_string("transcript.xml").unwrap();
+//! let server = MockServer::start(&transcript).unwrap();
+//! SCClient::builder(RandomLogic::new())
+//!     .host("127.0.0.1")
+//!     .port(server.port())
+//!     .build()
+//!     .run()
+//!     .unwrap();
+//! assert_eq!(server.finish().unwrap().moves().len(), 1);
+//! ```
+
+use std::io::{BufReader, ErrorKind, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use log::{debug, info};
+use crate::game::Move;
+use crate::protocol::{Data, Message, MessageStream, ReceivedMessage, Room};
+use crate::util::{SCError, SCResult, FromXmlNode, XmlNode};
+
+/// How long the server waits for the client to connect or to respond.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A message from the transcript to send to the client.
+struct ScriptedMessage {
+    xml: String,
+    /// Whether the client has to respond with a move.
+    is_move_request: bool
+}
+
+/// The messages a `MockServer` has received from its client.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MockSession {
+    /// The messages in order, beginning with the join request.
+    pub received: Vec<XmlNode>
+}
+
+impl MockSession {
+    /// Fetches the client's join request.
+    pub fn join(&self) -> Option<Message> {
+        self.received.first().and_then(|node| Message::from_node(node).ok())
+    }
+
+    /// Fetches the moves sent by the client, in order.
+    pub fn moves(&self) -> Vec<Move> {
+        self.received.iter().filter_map(|node| match Message::from_node(node) {
+            Ok(Message::Room(Room { data: Data::Move(game_move), .. })) => Some(game_move),
+            _ => None
+        }).collect()
+    }
+}
+
+/// A game server serving a single client on a free local port.
+///
+/// Once the client has sent its join request, the server sends
+/// the messages of the transcript, i.e. of a `<protocol>` stream
+/// (as recorded by `XmlTranscript`), in order. After each move
+/// request, it waits for the client's move. The messages in the
+/// transcript that were sent by a client (join requests and moves)
+/// are skipped, since the client under test sends its own.
+pub struct MockServer {
+    local_addr: SocketAddr,
+    handle: JoinHandle<SCResult<MockSession>>
+}
+
+impl MockServer {
+    /// Starts serving the given transcript in the background.
+    pub fn start(transcript: &str) -> SCResult<Self> {
+        let script = parse_script(transcript)?;
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let local_addr = listener.local_addr()?;
+        let handle = thread::Builder::new()
+            .name("mock-server".to_owned())
+            .spawn(move || serve(&listener, &script))?;
+        Ok(Self { local_addr, handle })
+    }
+
+    /// The address the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// The port the server is listening on.
+    pub fn port(&self) -> u16 {
+        self.local_addr.port()
+    }
+
+    /// Waits until the transcript has been replayed and the client
+    /// has disconnected. Fails if the client did not connect, did
+    /// not respond to a move request in time or disconnected early.
+    pub fn finish(self) -> SCResult<MockSession> {
+        self.handle.join().unwrap_or_else(|_| Err(SCError::protocol("The mock server panicked")))
+    }
+}
+
+/// Extracts the messages to send from the transcript.
+fn parse_script(transcript: &str) -> SCResult<Vec<ScriptedMessage>> {
+    let mut script = Vec::new();
+    for message in MessageStream::new(transcript.as_bytes()) {
+        let ReceivedMessage { xml, node } = message?;
+        let is_move_request = match Message::from_node(&node) {
+            Ok(Message::Join { .. } | Message::JoinPrepared { .. } | Message::JoinRoom { .. }) => continue,
+            Ok(Message::Room(Room { data: Data::Move(_), .. })) => continue,
+            Ok(Message::Room(Room { data: Data::MoveRequest, .. })) => true,
+            _ => false
+        };
+        script.push(ScriptedMessage { xml, is_move_request });
+    }
+    Ok(script)
+}
+
+/// Waits for the client to connect.
+fn accept(listener: &TcpListener) -> SCResult<TcpStream> {
+    listener.set_nonblocking(true)?;
+    let deadline = Instant::now() + TIMEOUT;
+    loop {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                stream.set_nonblocking(false)?;
+                return Ok(stream);
+            },
+            Err(e) if e.kind() == ErrorKind::WouldBlock && Instant::now() < deadline => thread::sleep(Duration::from_millis(10)),
+            Err(e) => return Err(e.into())
+        }
+    }
+}
+
+/// Replays the script to a single client.
+fn serve(listener: &TcpListener, script: &[ScriptedMessage]) -> SCResult<MockSession> {
+    let mut stream = accept(listener)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    info!("Mock server accepted {}", stream.peer_addr()?);
+
+    let mut messages = MessageStream::new(BufReader::new(stream.try_clone()?));
+    let mut session = MockSession::default();
+    let mut receive = |session: &mut MockSession| -> SCResult<()> {
+        let message = messages.next().ok_or_else(|| SCError::protocol("The client disconnected early"))??;
+        debug!("Mock server received {}", message.xml);
+        session.received.push(message.node);
+        Ok(())
+    };
+
+    receive(&mut session)?;
+    stream.write_all(b"<protocol>\n")?;
+    for message in script {
+        writeln!(stream, "{}", message.xml)?;
+        if message.is_move_request {
+            receive(&mut session)?;
+        }
+    }
+    stream.write_all(b"</protocol>")?;
+    stream.shutdown(Shutdown::Write)?;
+
+    // Keep whatever the client sends until it disconnects
+    session.received.extend(messages.filter_map(|message| message.ok()).map(|message| message.node));
+    Ok(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Write};
+    use std::sync::{Arc, Mutex};
+    use crate::client::{GAME_TYPE, SCClient, SCClientDelegate, XmlTranscript};
+    use crate::game::{GameState, Move, Team};
+    use crate::protocol::{GameResult, Message};
+    use super::MockServer;
+
+    const TRANSCRIPT: &str = include_str!("../fixtures/transcripts/player_one.xml");
+
+    /// Records the invoked hooks and plays the first legal move.
+    struct Recorder(Arc<Mutex<Vec<&'static str>>>);
+
+    impl SCClientDelegate for Recorder {
+        fn on_welcome_message(&mut self, _team: &Team) {
+            self.0.lock().unwrap().push("welcome");
+        }
+
+        fn on_update_state(&mut self, _state: &GameState) {
+            self.0.lock().unwrap().push("update");
+        }
+
+        fn on_game_end(&mut self, _result: GameResult) {
+            self.0.lock().unwrap().push("result");
+        }
+
+        fn request_move(&mut self, state: &GameState, _my_team: Team) -> Move {
+            self.0.lock().unwrap().push("move");
+            state.possible_moves().next().unwrap()
+        }
+    }
+
+    /// A writer into a buffer that outlives the client.
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_full_game_loop() {
+        let server = MockServer::start(TRANSCRIPT).unwrap();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recording = Shared::default();
+        SCClient::builder(Recorder(Arc::clone(&events)))
+            .host("127.0.0.1")
+            .port(server.port())
+            .interceptor(XmlTranscript::new(recording.clone()))
+            .build()
+            .run()
+            .unwrap();
+
+        let session = server.finish().unwrap();
+        assert_eq!(session.join(), Some(Message::Join { game_type: GAME_TYPE.to_owned() }));
+        assert_eq!(session.moves().len(), 1);
+        assert_eq!(*events.lock().unwrap(), vec!["welcome", "update", "move", "update", "update", "result"]);
+
+        // A transcript recorded by the client can be replayed as well
+        let recorded = String::from_utf8(recording.0.lock().unwrap().clone()).unwrap();
+        let server = MockServer::start(&recorded).unwrap();
+        SCClient::builder(Recorder(Arc::default()))
+            .host("127.0.0.1")
+            .port(server.port())
+            .reservation("r1")
+            .build()
+            .run()
+            .unwrap();
+        let replayed = server.finish().unwrap();
+        assert_eq!(replayed.join(), Some(Message::JoinPrepared { reservation_code: "r1".to_owned() }));
+        assert_eq!(replayed.moves(), session.moves());
+    }
+}
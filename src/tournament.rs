@@ -0,0 +1,215 @@
+//! A round-robin tournament runner on top of the local
+//! simulator, e.g. for tuning bots against each other.
+
+use std::{fmt, io};
+use log::info;
+use crate::client::SCClientDelegate;
+use crate::game::{PIECE_SHAPES, PieceShape, Team};
+use crate::sim::{GameOutcome, GameRecord, GameRunner};
+use crate::util::SCResult;
+
+/// The rating every participant starts with.
+pub const INITIAL_ELO: f64 = 1500.0;
+
+/// The statistics of a participant after a tournament.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    /// The participant's identification, e.g. `name 1.0 (depth 2)`.
+    pub name: String,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    /// The number of games lost through rule violations.
+    pub rule_violations: usize,
+    /// The points summed over every game.
+    pub points: i64,
+    pub elo: f64
+}
+
+impl Standing {
+    fn new(name: String) -> Self {
+        Self { name, wins: 0, draws: 0, losses: 0, rule_violations: 0, points: 0, elo: INITIAL_ELO }
+    }
+
+    /// The number of games played.
+    pub fn games(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+
+    /// The average points per game.
+    pub fn average_points(&self) -> f64 {
+        if self.games() == 0 { 0.0 } else { self.points as f64 / self.games() as f64 }
+    }
+
+    /// The score, counting wins as 1 and draws as 0.5, divided by the played games.
+    pub fn score_rate(&self) -> f64 {
+        if self.games() == 0 { 0.0 } else { (self.wins as f64 + 0.5 * self.draws as f64) / self.games() as f64 }
+    }
+}
+
+/// The outcome of a tournament.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TournamentResult {
+    /// The standings, sorted by rating (best first).
+    pub standings: Vec<Standing>,
+    /// The records of every game, in the order they were played.
+    pub records: Vec<GameRecord>
+}
+
+/// Formats the standings as a table.
+impl fmt::Display for TournamentResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.standings.iter().map(|s| s.name.len()).max().unwrap_or(0).max(4);
+        writeln!(f, "{:>3}  {:<width$}  {:>6}  {:>5}  {:>3} {:>3} {:>3}  {:>6}  {:>8}", "#", "Name", "Elo", "Games", "W", "D", "L", "Score", "Points", width = width)?;
+        for (i, s) in self.standings.iter().enumerate() {
+            writeln!(
+                f, "{:>3}  {:<width$}  {:>6.0}  {:>5}  {:>3} {:>3} {:>3}  {:>5.1}%  {:>8.1}",
+                i + 1, s.name, s.elo, s.games(), s.wins, s.draws, s.losses, 100.0 * s.score_rate(), s.average_points(), width = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A round-robin tournament between delegates. Each pairing plays
+/// the configured number of games, swapping teams after every game
+/// and cycling through the start pieces after every second game
+/// (so both sides play every start piece).
+pub struct Tournament {
+    participants: Vec<Option<Box<dyn SCClientDelegate>>>,
+    games_per_pairing: usize,
+    start_pieces: Vec<PieceShape>,
+    k_factor: f64
+}
+
+impl Tournament {
+    /// Creates a tournament without participants, playing two games
+    /// per pairing and using every start piece the server may pick.
+    pub fn new() -> Self {
+        Self {
+            participants: Vec::new(),
+            games_per_pairing: 2,
+            // The X-shaped pentomino cannot be placed in a corner
+            start_pieces: PIECE_SHAPES.iter().filter(|s| s.size() == 5 && s.name() != "PENTO_X").cloned().collect(),
+            k_factor: 16.0
+        }
+    }
+
+    /// Adds a participant.
+    pub fn participant(mut self, delegate: impl SCClientDelegate + 'static) -> Self {
+        self.participants.push(Some(Box::new(delegate)));
+        self
+    }
+
+    /// Sets the number of games each pair of participants plays.
+    pub fn games_per_pairing(mut self, games_per_pairing: usize) -> Self {
+        self.games_per_pairing = games_per_pairing;
+        self
+    }
+
+    /// Sets the start pieces to cycle through.
+    pub fn start_pieces(mut self, start_pieces: Vec<PieceShape>) -> Self {
+        self.start_pieces = start_pieces;
+        self
+    }
+
+    /// Sets how strongly a single game changes the ratings.
+    pub fn k_factor(mut self, k_factor: f64) -> Self {
+        self.k_factor = k_factor;
+        self
+    }
+
+    /// Plays every game of the tournament.
+    pub fn run(mut self) -> SCResult<TournamentResult> {
+        if self.start_pieces.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "A tournament needs at least one start piece").into());
+        }
+
+        let mut standings: Vec<_> = self.participants.iter().flatten().map(|p| Standing::new(p.bot_info().to_string())).collect();
+        let mut records = Vec::new();
+
+        for i in 0..self.participants.len() {
+            for j in (i + 1)..self.participants.len() {
+                for game in 0..self.games_per_pairing {
+                    let (one, two) = if game % 2 == 0 { (i, j) } else { (j, i) };
+                    let start_piece = self.start_pieces[(game / 2) % self.start_pieces.len()].clone();
+                    let runner = GameRunner::new(self.participants[one].take().unwrap(), self.participants[two].take().unwrap(), start_piece);
+                    let (record, delegate_one, delegate_two) = runner.run()?;
+                    self.participants[one] = Some(delegate_one);
+                    self.participants[two] = Some(delegate_two);
+
+                    Self::update_standings(&mut standings, one, two, &record, self.k_factor);
+                    info!("Game {} between {} and {}: winner {:?}", records.len() + 1, standings[one].name, standings[two].name, record.winner());
+                    records.push(record);
+                }
+            }
+        }
+
+        standings.sort_by(|a, b| b.elo.total_cmp(&a.elo));
+        Ok(TournamentResult { standings, records })
+    }
+
+    fn update_standings(standings: &mut [Standing], one: usize, two: usize, record: &GameRecord, k_factor: f64) {
+        let score_one = match record.winner() {
+            Some(Team::One) => 1.0,
+            Some(_) => 0.0,
+            None => 0.5
+        };
+        for (index, team) in [(one, Team::One), (two, Team::Two)] {
+            let standing = &mut standings[index];
+            standing.points += record.points(team) as i64;
+            match record.winner() {
+                Some(winner) if winner == team => standing.wins += 1,
+                Some(_) => standing.losses += 1,
+                None => standing.draws += 1
+            }
+            if matches!(record.outcome, GameOutcome::RuleViolation { team: violator, .. } if violator == team) {
+                standing.rule_violations += 1;
+            }
+        }
+
+        let (elo_one, elo_two) = (standings[one].elo, standings[two].elo);
+        let expected_one = 1.0 / (1.0 + 10f64.powf((elo_two - elo_one) / 400.0));
+        let delta = k_factor * (score_one - expected_one);
+        standings[one].elo += delta;
+        standings[two].elo -= delta;
+    }
+}
+
+impl Default for Tournament {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+    use crate::logic::GreedyLogic;
+    use crate::sim::Skipper;
+    use crate::util::SCError;
+    use super::{INITIAL_ELO, Tournament};
+
+    #[test]
+    fn test_round_robin() {
+        let result = Tournament::new().participant(GreedyLogic).participant(Skipper).games_per_pairing(4).run().unwrap();
+        assert_eq!(result.records.len(), 4);
+        let (greedy, skipper) = (&result.standings[0], &result.standings[1]);
+        assert!(greedy.name.starts_with("GreedyLogic"));
+        assert_eq!((greedy.wins, greedy.draws, greedy.losses), (4, 0, 0));
+        assert_eq!(skipper.losses, 4);
+        assert!(greedy.elo > INITIAL_ELO && skipper.elo < INITIAL_ELO);
+        assert_eq!(greedy.elo + skipper.elo, 2.0 * INITIAL_ELO);
+
+        // Both bots played both teams
+        assert_eq!(result.records[0].bot_one.name, "GreedyLogic");
+        assert_eq!(result.records[1].bot_one.name, "Skipper");
+
+        let table = result.to_string();
+        assert_eq!(table.lines().count(), 3);
+        assert!(table.lines().nth(1).unwrap().contains("100.0%"));
+
+        let empty = Tournament::new().participant(GreedyLogic).participant(Skipper).start_pieces(Vec::new()).run();
+        assert!(matches!(empty, Err(SCError::Io(e)) if e.kind() == ErrorKind::InvalidInput));
+    }
+}
@@ -0,0 +1,189 @@
+//! A live game viewer rendering the board, the undeployed
+//! pieces, the scores and a clock to the terminal using
+//! ANSI escape codes.
+
+use std::fmt::Write as _;
+use std::io::{self, Stdout, Write};
+use std::time::{Duration, Instant};
+use log::warn;
+use crate::BotInfo;
+use crate::client::SCClientDelegate;
+use crate::game::{BOARD_SIZE, COLORS, Color, GameState, Move, PIECE_SHAPE_KINDS, Team, Vec2};
+use crate::protocol::GameResult;
+use crate::search::SearchResult;
+use crate::util::XmlNode;
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+const RESET: &str = "\x1b[0m";
+
+/// The ANSI escape code for a color's background.
+fn background(color: Color) -> &'static str {
+    match color {
+        Color::Blue => "\x1b[44m",
+        Color::Yellow => "\x1b[43m",
+        Color::Red => "\x1b[41m",
+        Color::Green => "\x1b[42m",
+        Color::None => ""
+    }
+}
+
+/// Renders the given state as a frame, with the board on
+/// the left and the scores, clock and pieces on the right.
+pub fn render(state: &GameState, my_team: Option<Team>, game_time: Duration, turn_time: Duration) -> String {
+    let mut panel = vec![
+        format!("Round {}, turn {}", state.round, state.turn),
+        format!("Game time {:.1}s, this turn {:.1}s", game_time.as_secs_f64(), turn_time.as_secs_f64()),
+        String::new()
+    ];
+    for player in [&state.first, &state.second] {
+        let marker = if Some(player.team) == my_team { " (us)" } else { "" };
+        panel.push(format!("Team {} {}{}: {} points", player.team, player.display_name, marker, state.points_of_team(player.team)));
+    }
+    panel.push(String::new());
+    for color in COLORS {
+        let to_move = if state.current_color() == color { "▶" } else { " " };
+        let status = if state.is_color_active(color) { "" } else { " (out)" };
+        panel.push(format!("{} {}  {} {}: {} points{}", to_move, background(color), RESET, color, state.points_of_color(color), status));
+        let mut pieces: Vec<_> = state.undeployed_shapes_of_color(color).map(|shape| shape.kind()).collect();
+        pieces.sort();
+        let names: Vec<_> = PIECE_SHAPE_KINDS.iter().filter(|kind| pieces.contains(kind)).map(|kind| kind.name()).collect();
+        for line in names.chunks(6) {
+            panel.push(format!("    {}", line.join(" ")));
+        }
+    }
+
+    let mut frame = String::new();
+    for y in 0..BOARD_SIZE {
+        for x in 0..BOARD_SIZE {
+            match state.board.get(Vec2::new(x as i32, y as i32)) {
+                Color::None => frame.push_str("· "),
+                color => write!(frame, "{}  {}", background(color), RESET).unwrap()
+            }
+        }
+        if let Some(line) = panel.get(y) {
+            write!(frame, "   {}", line).unwrap();
+        }
+        frame.push('\n');
+    }
+    for line in panel.iter().skip(BOARD_SIZE) {
+        writeln!(frame, "{}   {}", " ".repeat(2 * BOARD_SIZE), line).unwrap();
+    }
+    frame
+}
+
+/// A delegate that renders the live game to a terminal
+/// on every state update and forwards every callback
+/// to the wrapped delegate.
+pub struct TuiViewer<D, W = Stdout> where D: SCClientDelegate, W: Write {
+    inner: D,
+    writer: W,
+    my_team: Option<Team>,
+    game_start: Option<Instant>,
+    turn_start: Instant
+}
+
+impl<D> TuiViewer<D> where D: SCClientDelegate {
+    /// Wraps the given delegate, rendering to stdout.
+    pub fn new(inner: D) -> Self {
+        Self::with_writer(inner, io::stdout())
+    }
+}
+
+impl<D, W> TuiViewer<D, W> where D: SCClientDelegate, W: Write {
+    /// Wraps the given delegate, rendering to the given writer.
+    pub fn with_writer(inner: D, writer: W) -> Self {
+        Self { inner, writer, my_team: None, game_start: None, turn_start: Instant::now() }
+    }
+
+    /// Fetches the wrapped delegate.
+    pub fn inner(&self) -> &D {
+        &self.inner
+    }
+
+    fn draw(&mut self, frame: &str) {
+        if let Err(e) = write!(self.writer, "{}{}", CLEAR_SCREEN, frame).and_then(|_| self.writer.flush()) {
+            warn!("Could not render the game: {}", e);
+        }
+    }
+}
+
+impl<D, W> SCClientDelegate for TuiViewer<D, W> where D: SCClientDelegate, W: Write {
+    fn bot_info(&self) -> BotInfo {
+        self.inner.bot_info()
+    }
+
+    fn on_update_state(&mut self, state: &GameState) {
+        let now = Instant::now();
+        let game_start = *self.game_start.get_or_insert(now);
+        let frame = render(state, self.my_team, now - game_start, now - self.turn_start);
+        self.turn_start = now;
+        self.draw(&frame);
+        self.inner.on_update_state(state);
+    }
+
+    fn on_game_end(&mut self, result: GameResult) {
+        let winners: Vec<_> = result.winners.iter().map(|player| player.display_name.as_str()).collect();
+        let summary = if winners.is_empty() { "Draw".to_owned() } else { format!("Won by {}", winners.join(", ")) };
+        if let Err(e) = writeln!(self.writer, "\nGame over: {}", summary) {
+            warn!("Could not render the game: {}", e);
+        }
+        self.inner.on_game_end(result);
+    }
+
+    fn on_welcome_message(&mut self, color: &Team) {
+        self.my_team = Some(*color);
+        self.inner.on_welcome_message(color);
+    }
+
+    fn on_disconnect(&mut self) {
+        self.inner.on_disconnect();
+    }
+
+    fn on_rejoin(&mut self, success: bool) {
+        self.inner.on_rejoin(success);
+    }
+
+    fn on_search_finished(&mut self, result: &SearchResult) {
+        if let Err(e) = writeln!(self.writer, "\nSearch: {}", result).and_then(|_| self.writer.flush()) {
+            warn!("Could not render the game: {}", e);
+        }
+        self.inner.on_search_finished(result);
+    }
+
+    fn on_unknown_data(&mut self, class: &str, node: &XmlNode) {
+        self.inner.on_unknown_data(class, node);
+    }
+
+    fn request_move(&mut self, state: &GameState, my_team: Team) -> Move {
+        self.inner.request_move(state, my_team)
+    }
+
+    fn request_search(&mut self, state: &GameState, my_team: Team) -> SearchResult {
+        self.inner.request_search(state, my_team)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use crate::client::SCClientDelegate;
+    use crate::game::{BOARD_SIZE, GameState, PIECE_SHAPES_BY_NAME, Team};
+    use crate::logic::GreedyLogic;
+    use super::{TuiViewer, render};
+
+    #[test]
+    fn test_render() {
+        let mut state = GameState::new(PIECE_SHAPES_BY_NAME["TETRO_O"].clone());
+        state.perform_move(state.possible_moves().next().unwrap()).unwrap();
+        let frame = render(&state, Some(Team::One), Duration::from_secs(3), Duration::from_millis(500));
+        assert!(frame.lines().count() >= BOARD_SIZE);
+        assert_eq!(frame.matches("\x1b[44m  ").count(), 4 + 1);
+        assert!(frame.contains("Alice (us)"));
+        assert!(frame.contains("Game time 3.0s, this turn 0.5s"));
+
+        let mut viewer = TuiViewer::with_writer(GreedyLogic, Vec::new());
+        viewer.on_welcome_message(&Team::Two);
+        viewer.on_update_state(&state);
+        assert!(String::from_utf8(viewer.writer).unwrap().contains("Bob (us)"));
+    }
+}
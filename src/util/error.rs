@@ -1,8 +1,11 @@
+use std::error::Error;
+use std::fmt;
 use std::io::Error as IoError;
 use std::str::ParseBoolError;
 use std::num::{ParseIntError, ParseFloatError};
 use xml::reader::Error as XmlReaderError;
 use xml::writer::Error as XmlWriterError;
+use super::{ParseError, RuleViolationKind, XmlError};
 
 /// A custom error type that abstracts over
 /// other errors (such as IO/XML errors) and
@@ -10,43 +13,94 @@ use xml::writer::Error as XmlWriterError;
 /// `Result`.
 #[derive(Debug)]
 pub enum SCError {
+    /// Reading from or writing to a stream failed.
     Io(IoError),
-    XmlReader(XmlReaderError),
-    XmlWriter(XmlWriterError),
-    ParseInt(ParseIntError),
-    ParseFloat(ParseFloatError),
-    ParseBool(ParseBoolError),
-    Custom(String)
+    /// An XML document could not be read or written.
+    Xml(XmlError),
+    /// A value (e.g. an attribute or JSON) could not be parsed.
+    Parse(ParseError),
+    /// A message was unexpected or the connection behaved unexpectedly.
+    Protocol(String),
+    /// A move or an action does not conform to the game's rules.
+    RuleViolation { kind: RuleViolationKind, message: String }
+}
+
+impl SCError {
+    /// Creates a parse error with the given message.
+    pub fn parse(message: impl Into<String>) -> Self {
+        Self::Parse(ParseError::Invalid(message.into()))
+    }
+
+    /// Creates a protocol error with the given message.
+    pub fn protocol(message: impl Into<String>) -> Self {
+        Self::Protocol(message.into())
+    }
+
+    /// Creates a rule violation of the given kind.
+    pub fn rule_violation(kind: RuleViolationKind, message: impl Into<String>) -> Self {
+        Self::RuleViolation { kind, message: message.into() }
+    }
+
+    /// Fetches the kind of rule violation, if this is one.
+    pub fn rule_violation_kind(&self) -> Option<RuleViolationKind> {
+        match self {
+            Self::RuleViolation { kind, .. } => Some(*kind),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for SCError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "IO error: {}", e),
+            Self::Xml(e) => write!(f, "XML error: {}", e),
+            Self::Parse(e) => write!(f, "Parse error: {}", e),
+            Self::Protocol(message) => write!(f, "Protocol error: {}", message),
+            Self::RuleViolation { kind, message } => write!(f, "Rule violation ({}): {}", kind, message)
+        }
+    }
+}
+
+impl Error for SCError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Xml(e) => Some(e),
+            Self::Parse(e) => Some(e),
+            _ => None
+        }
+    }
 }
 
 impl From<IoError> for SCError {
     fn from(error: IoError) -> Self { Self::Io(error) }
 }
 
+impl From<XmlError> for SCError {
+    fn from(error: XmlError) -> Self { Self::Xml(error) }
+}
+
 impl From<XmlReaderError> for SCError {
-    fn from(error: XmlReaderError) -> Self { Self::XmlReader(error) }
+    fn from(error: XmlReaderError) -> Self { Self::Xml(XmlError::Reader(error)) }
 }
 
 impl From<XmlWriterError> for SCError {
-    fn from(error: XmlWriterError) -> Self { Self::XmlWriter(error) }
+    fn from(error: XmlWriterError) -> Self { Self::Xml(XmlError::Writer(error)) }
+}
+
+impl From<ParseError> for SCError {
+    fn from(error: ParseError) -> Self { Self::Parse(error) }
 }
 
 impl From<ParseIntError> for SCError {
-    fn from(error: ParseIntError) -> Self { Self::ParseInt(error) }
+    fn from(error: ParseIntError) -> Self { Self::Parse(ParseError::Int(error)) }
 }
 
 impl From<ParseFloatError> for SCError {
-    fn from(error: ParseFloatError) -> Self { Self::ParseFloat(error) }
+    fn from(error: ParseFloatError) -> Self { Self::Parse(ParseError::Float(error)) }
 }
 
 impl From<ParseBoolError> for SCError {
-    fn from(error: ParseBoolError) -> Self { Self::ParseBool(error) }
-}
-
-impl From<String> for SCError {
-    fn from(error: String) -> Self { Self::Custom(error) }
-}
-
-impl<'a> From<&'a str> for SCError {
-    fn from(error: &'a str) -> Self { Self::Custom(error.to_owned()) }
+    fn from(error: ParseBoolError) -> Self { Self::Parse(ParseError::Bool(error)) }
 }
@@ -0,0 +1,56 @@
+use std::hash::Hasher;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// A 64-bit FNV-1a hasher. Unlike the standard library's
+/// default hasher, its output is stable across runs and
+/// versions, which makes it suitable for persisted hashes.
+#[derive(Debug, Copy, Clone)]
+pub struct FnvHasher {
+    state: u64
+}
+
+impl FnvHasher {
+    /// Creates a new hasher.
+    pub fn new() -> Self {
+        Self { state: FNV_OFFSET_BASIS }
+    }
+}
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.state ^= byte as u64;
+            self.state = self.state.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::Hasher;
+    use super::FnvHasher;
+
+    #[test]
+    fn test_fnv_hasher() {
+        let hash = |bytes: &[u8]| {
+            let mut hasher = FnvHasher::new();
+            hasher.write(bytes);
+            hasher.finish()
+        };
+        assert_eq!(hash(b""), 0xcbf29ce484222325);
+        assert_eq!(hash(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(hash(b"foobar"), 0x85944171f73967e8);
+    }
+}
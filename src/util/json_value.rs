@@ -0,0 +1,410 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::iter::Peekable;
+use std::str::{Chars, FromStr};
+use super::{SCResult, SCError};
+
+/// The maximum nesting depth of arrays and objects accepted by
+/// the parser, which protects it against overflowing the stack.
+pub const MAX_JSON_DEPTH: usize = 128;
+
+/// An in-memory representation of a JSON value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(BTreeMap<String, JsonValue>)
+}
+
+/// Indicates that the type can be converted to JSON.
+pub trait ToJson {
+    fn to_json(&self) -> JsonValue;
+}
+
+/// Indicates that the type can be created from JSON.
+pub trait FromJson where Self: Sized {
+    fn from_json(json: &JsonValue) -> SCResult<Self>;
+}
+
+impl JsonValue {
+    /// Creates a JSON object from the given key-value pairs.
+    pub fn object(entries: impl IntoIterator<Item=(impl Into<String>, JsonValue)>) -> Self {
+        Self::Object(entries.into_iter().map(|(k, v)| (k.into(), v)).collect())
+    }
+
+    /// Creates a JSON array from the given values.
+    pub fn array(values: impl IntoIterator<Item=JsonValue>) -> Self {
+        Self::Array(values.into_iter().collect())
+    }
+
+    /// Fetches a member of an object by key.
+    pub fn get(&self, key: &str) -> SCResult<&JsonValue> {
+        match self {
+            Self::Object(entries) => entries.get(key).ok_or_else(|| SCError::parse(format!("No member with key '{}' found in JSON object!", key))),
+            _ => Err(SCError::parse(format!("Cannot fetch member '{}' of non-object {}", key, self)))
+        }
+    }
+
+    /// Fetches a member of an object by key, if present and not null.
+    pub fn opt(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            Self::Object(entries) => entries.get(key).filter(|v| **v != Self::Null),
+            _ => None
+        }
+    }
+
+    /// Interprets the value as a boolean.
+    pub fn as_bool(&self) -> SCResult<bool> {
+        match self {
+            Self::Bool(b) => Ok(*b),
+            _ => Err(SCError::parse(format!("{} is not a boolean!", self)))
+        }
+    }
+
+    /// Interprets the value as a floating-point number.
+    pub fn as_f64(&self) -> SCResult<f64> {
+        match self {
+            Self::Number(n) => Ok(*n),
+            _ => Err(SCError::parse(format!("{} is not a number!", self)))
+        }
+    }
+
+    /// Interprets the value as an integer.
+    pub fn as_i64(&self) -> SCResult<i64> {
+        let n = self.as_f64()?;
+        // 2^63 is exactly representable, unlike i64::MAX
+        if n.fract() == 0.0 && n >= i64::MIN as f64 && n < 9_223_372_036_854_775_808.0 {
+            Ok(n as i64)
+        } else {
+            Err(SCError::parse(format!("{} is not an integer!", n)))
+        }
+    }
+
+    /// Interprets the value as a 32-bit integer.
+    pub fn as_i32(&self) -> SCResult<i32> {
+        let n = self.as_i64()?;
+        i32::try_from(n).map_err(|_| SCError::parse(format!("{} is out of range for a 32-bit integer!", n)))
+    }
+
+    /// Interprets the value as an unsigned 32-bit integer.
+    pub fn as_u32(&self) -> SCResult<u32> {
+        let n = self.as_i64()?;
+        u32::try_from(n).map_err(|_| SCError::parse(format!("{} is out of range for an unsigned 32-bit integer!", n)))
+    }
+
+    /// Interprets the value as a string.
+    pub fn as_str(&self) -> SCResult<&str> {
+        match self {
+            Self::String(s) => Ok(s.as_str()),
+            _ => Err(SCError::parse(format!("{} is not a string!", self)))
+        }
+    }
+
+    /// Interprets the value as an array.
+    pub fn as_array(&self) -> SCResult<&[JsonValue]> {
+        match self {
+            Self::Array(values) => Ok(values.as_slice()),
+            _ => Err(SCError::parse(format!("{} is not an array!", self)))
+        }
+    }
+
+    /// Interprets the value as an object.
+    pub fn as_object(&self) -> SCResult<&BTreeMap<String, JsonValue>> {
+        match self {
+            Self::Object(entries) => Ok(entries),
+            _ => Err(SCError::parse(format!("{} is not an object!", self)))
+        }
+    }
+
+    /// Pretty-prints the value using the given indentation.
+    pub fn pretty(&self, indent: usize) -> String {
+        let mut s = String::new();
+        self.write_pretty(&mut s, indent, 0);
+        s
+    }
+
+    fn write_pretty(&self, s: &mut String, indent: usize, level: usize) {
+        let pad = |s: &mut String, level: usize| s.extend(std::iter::repeat_n(' ', indent * level));
+        match self {
+            Self::Array(values) if !values.is_empty() => {
+                s.push_str("[\n");
+                for (i, value) in values.iter().enumerate() {
+                    pad(s, level + 1);
+                    value.write_pretty(s, indent, level + 1);
+                    s.push_str(if i + 1 < values.len() { ",\n" } else { "\n" });
+                }
+                pad(s, level);
+                s.push(']');
+            },
+            Self::Object(entries) if !entries.is_empty() => {
+                s.push_str("{\n");
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    pad(s, level + 1);
+                    s.push_str(&format!("{}: ", Self::String(key.clone())));
+                    value.write_pretty(s, indent, level + 1);
+                    s.push_str(if i + 1 < entries.len() { ",\n" } else { "\n" });
+                }
+                pad(s, level);
+                s.push('}');
+            },
+            value => s.push_str(&value.to_string())
+        }
+    }
+}
+
+impl fmt::Display for JsonValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::Bool(b) => write!(f, "{}", b),
+            Self::Number(n) if !n.is_finite() => write!(f, "null"),
+            Self::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => write!(f, "{}", *n as i64),
+            Self::Number(n) => write!(f, "{}", n),
+            Self::String(s) => {
+                write!(f, "\"")?;
+                for c in s.chars() {
+                    match c {
+                        '"' => write!(f, "\\\"")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        '\r' => write!(f, "\\r")?,
+                        '\t' => write!(f, "\\t")?,
+                        c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+                        c => write!(f, "{}", c)?
+                    }
+                }
+                write!(f, "\"")
+            },
+            Self::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 { write!(f, ",")?; }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            },
+            Self::Object(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 { write!(f, ",")?; }
+                    write!(f, "{}:{}", Self::String(key.clone()), value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+impl FromStr for JsonValue {
+    type Err = SCError;
+
+    fn from_str(raw: &str) -> SCResult<Self> {
+        let mut chars = raw.chars().peekable();
+        let value = parse_value(&mut chars, 0)?;
+        skip_whitespace(&mut chars);
+        match chars.next() {
+            None => Ok(value),
+            Some(c) => Err(SCError::parse(format!("Unexpected trailing character '{}' in JSON", c)))
+        }
+    }
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+        chars.next();
+    }
+}
+
+fn expect_literal(chars: &mut Peekable<Chars>, literal: &str) -> SCResult<()> {
+    for expected in literal.chars() {
+        if chars.next() != Some(expected) {
+            return Err(SCError::parse(format!("Expected '{}' in JSON", literal)));
+        }
+    }
+    Ok(())
+}
+
+fn parse_value(chars: &mut Peekable<Chars>, depth: usize) -> SCResult<JsonValue> {
+    skip_whitespace(chars);
+    if matches!(chars.peek(), Some('[' | '{')) && depth >= MAX_JSON_DEPTH {
+        return Err(SCError::parse(format!("JSON is nested deeper than {} levels", MAX_JSON_DEPTH)));
+    }
+    match chars.peek() {
+        Some('n') => expect_literal(chars, "null").map(|_| JsonValue::Null),
+        Some('t') => expect_literal(chars, "true").map(|_| JsonValue::Bool(true)),
+        Some('f') => expect_literal(chars, "false").map(|_| JsonValue::Bool(false)),
+        Some('"') => parse_string(chars).map(JsonValue::String),
+        Some('[') => {
+            chars.next();
+            let mut values = Vec::new();
+            skip_whitespace(chars);
+            if chars.peek() == Some(&']') {
+                chars.next();
+                return Ok(JsonValue::Array(values));
+            }
+            loop {
+                values.push(parse_value(chars, depth + 1)?);
+                skip_whitespace(chars);
+                match chars.next() {
+                    Some(',') => continue,
+                    Some(']') => return Ok(JsonValue::Array(values)),
+                    c => return Err(SCError::parse(format!("Expected ',' or ']' in JSON array, but got {:?}", c)))
+                }
+            }
+        },
+        Some('{') => {
+            chars.next();
+            let mut entries = BTreeMap::new();
+            skip_whitespace(chars);
+            if chars.peek() == Some(&'}') {
+                chars.next();
+                return Ok(JsonValue::Object(entries));
+            }
+            loop {
+                skip_whitespace(chars);
+                let key = parse_string(chars)?;
+                skip_whitespace(chars);
+                expect_literal(chars, ":")?;
+                entries.insert(key, parse_value(chars, depth + 1)?);
+                skip_whitespace(chars);
+                match chars.next() {
+                    Some(',') => continue,
+                    Some('}') => return Ok(JsonValue::Object(entries)),
+                    c => return Err(SCError::parse(format!("Expected ',' or '}}' in JSON object, but got {:?}", c)))
+                }
+            }
+        },
+        Some(&c) if c == '-' || c.is_ascii_digit() => {
+            let mut raw = String::new();
+            while let Some(&c) = chars.peek().filter(|&&c| c.is_ascii_digit() || "+-.eE".contains(c)) {
+                raw.push(c);
+                chars.next();
+            }
+            Ok(JsonValue::Number(raw.parse()?))
+        },
+        c => Err(SCError::parse(format!("Unexpected {:?} in JSON", c)))
+    }
+}
+
+fn parse_string(chars: &mut Peekable<Chars>) -> SCResult<String> {
+    expect_literal(chars, "\"")?;
+    let mut s = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(s),
+            Some('\\') => match chars.next() {
+                Some('"') => s.push('"'),
+                Some('\\') => s.push('\\'),
+                Some('/') => s.push('/'),
+                Some('b') => s.push('\u{8}'),
+                Some('f') => s.push('\u{c}'),
+                Some('n') => s.push('\n'),
+                Some('r') => s.push('\r'),
+                Some('t') => s.push('\t'),
+                Some('u') => {
+                    let mut code = parse_hex4(chars)?;
+                    if (0xD800..0xDC00).contains(&code) {
+                        // Decode a surrogate pair
+                        expect_literal(chars, "\\u")?;
+                        let low = parse_hex4(chars)?;
+                        code = 0x10000 + ((code - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF);
+                    }
+                    s.push(char::from_u32(code).ok_or_else(|| SCError::parse(format!("Invalid unicode escape {:x} in JSON", code)))?);
+                },
+                c => return Err(SCError::parse(format!("Invalid escape {:?} in JSON string", c)))
+            },
+            Some(c) => s.push(c),
+            None => return Err(SCError::parse("Unterminated JSON string"))
+        }
+    }
+}
+
+fn parse_hex4(chars: &mut Peekable<Chars>) -> SCResult<u32> {
+    let hex: String = chars.take(4).collect();
+    Ok(u32::from_str_radix(&hex, 16)?)
+}
+
+impl From<bool> for JsonValue {
+    fn from(b: bool) -> Self { Self::Bool(b) }
+}
+
+impl From<i32> for JsonValue {
+    fn from(n: i32) -> Self { Self::Number(n as f64) }
+}
+
+impl From<u32> for JsonValue {
+    fn from(n: u32) -> Self { Self::Number(n as f64) }
+}
+
+impl From<u64> for JsonValue {
+    fn from(n: u64) -> Self { Self::Number(n as f64) }
+}
+
+impl From<usize> for JsonValue {
+    fn from(n: usize) -> Self { Self::Number(n as f64) }
+}
+
+impl From<f64> for JsonValue {
+    fn from(n: f64) -> Self { Self::Number(n) }
+}
+
+impl From<&str> for JsonValue {
+    fn from(s: &str) -> Self { Self::String(s.to_owned()) }
+}
+
+impl From<String> for JsonValue {
+    fn from(s: String) -> Self { Self::String(s) }
+}
+
+impl<T> From<Option<T>> for JsonValue where T: Into<JsonValue> {
+    fn from(option: Option<T>) -> Self { option.map(Into::into).unwrap_or(Self::Null) }
+}
+
+impl<T> ToJson for Vec<T> where T: ToJson {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::array(self.iter().map(ToJson::to_json))
+    }
+}
+
+impl<T> FromJson for Vec<T> where T: FromJson {
+    fn from_json(json: &JsonValue) -> SCResult<Self> {
+        json.as_array()?.iter().map(T::from_json).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JsonValue, MAX_JSON_DEPTH};
+
+    #[test]
+    fn test_round_trip() {
+        let raw = r#"{"a":[1,2.5,-3e2,true,null],"b":{"c":"x\"y\\z\nä\ud83d\ude00"},"d":[]}"#;
+        let value: JsonValue = raw.parse().unwrap();
+        assert_eq!(value.get("a").unwrap().as_array().unwrap()[2].as_i64().unwrap(), -300);
+        assert_eq!(value.get("b").unwrap().get("c").unwrap().as_str().unwrap(), "x\"y\\z\nä😀");
+        assert_eq!(value.to_string().parse::<JsonValue>().unwrap(), value);
+        assert_eq!(value.pretty(2).parse::<JsonValue>().unwrap(), value);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!("{\"a\":}".parse::<JsonValue>().is_err());
+        assert!("[1,2".parse::<JsonValue>().is_err());
+        assert!("1 2".parse::<JsonValue>().is_err());
+        assert!("1e30".parse::<JsonValue>().unwrap().as_i64().is_err());
+        assert!("-1".parse::<JsonValue>().unwrap().as_u32().is_err());
+        assert!("4294967296".parse::<JsonValue>().unwrap().as_u32().is_err());
+        assert!("2147483648".parse::<JsonValue>().unwrap().as_i32().is_err());
+    }
+
+    #[test]
+    fn test_nesting_limit() {
+        let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(nested(MAX_JSON_DEPTH).parse::<JsonValue>().is_ok());
+        assert!(nested(MAX_JSON_DEPTH + 1).parse::<JsonValue>().is_err());
+        assert!("[".repeat(200_000).parse::<JsonValue>().is_err());
+    }
+}
@@ -1,9 +1,26 @@
+mod checksum;
 mod error;
+mod fnv_hasher;
+#[cfg(feature = "json")]
+mod json_value;
+mod parse_error;
 mod result;
 mod macros;
+mod rule_violation_kind;
+mod span;
+mod xml_error;
+mod xml_frame_buffer;
 mod xml_node;
 
+pub use checksum::*;
 pub use error::*;
+pub use fnv_hasher::*;
+#[cfg(feature = "json")]
+pub use json_value::*;
+pub use parse_error::*;
 pub use result::*;
-pub use macros::*;
+pub use rule_violation_kind::*;
+pub use span::*;
+pub use xml_error::*;
+pub use xml_frame_buffer::*;
 pub use xml_node::*;
@@ -0,0 +1,36 @@
+use std::error::Error;
+use std::fmt;
+use std::str::ParseBoolError;
+use std::num::{ParseIntError, ParseFloatError};
+
+/// An error that occurred while parsing a value.
+#[derive(Debug)]
+pub enum ParseError {
+    Int(ParseIntError),
+    Float(ParseFloatError),
+    Bool(ParseBoolError),
+    /// The value is missing or malformed.
+    Invalid(String)
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int(e) => write!(f, "{}", e),
+            Self::Float(e) => write!(f, "{}", e),
+            Self::Bool(e) => write!(f, "{}", e),
+            Self::Invalid(message) => write!(f, "{}", message)
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Int(e) => Some(e),
+            Self::Float(e) => Some(e),
+            Self::Bool(e) => Some(e),
+            Self::Invalid(_) => None
+        }
+    }
+}
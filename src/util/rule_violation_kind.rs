@@ -0,0 +1,46 @@
+use std::fmt;
+
+/// The reason why a move violates the game's rules.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RuleViolationKind {
+    /// The game has already ended.
+    GameOver,
+    /// The moving color is no longer in the game.
+    ColorNotInGame,
+    /// It is not the moving color's turn.
+    WrongColor,
+    /// The first piece of a color has to be the start piece.
+    NotStartPiece,
+    /// The piece has already been placed before.
+    AlreadyPlaced,
+    /// The piece does not lie within the board's bounds.
+    OutOfBounds,
+    /// The piece overlaps with another piece.
+    Obstructed,
+    /// The piece shares an edge with a piece of the same color.
+    BordersOwnColor,
+    /// The first piece of a color has to cover a corner of the board.
+    NotInCorner,
+    /// The piece shares no corner with a piece of the same color.
+    NoCornerContact,
+    /// Colors cannot skip before having placed their first piece.
+    SkipInFirstRound
+}
+
+impl fmt::Display for RuleViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::GameOver => write!(f, "game over"),
+            Self::ColorNotInGame => write!(f, "color not in game"),
+            Self::WrongColor => write!(f, "wrong color"),
+            Self::NotStartPiece => write!(f, "not the start piece"),
+            Self::AlreadyPlaced => write!(f, "already placed"),
+            Self::OutOfBounds => write!(f, "out of bounds"),
+            Self::Obstructed => write!(f, "obstructed"),
+            Self::BordersOwnColor => write!(f, "borders own color"),
+            Self::NotInCorner => write!(f, "not in corner"),
+            Self::NoCornerContact => write!(f, "no corner contact"),
+            Self::SkipInFirstRound => write!(f, "skip in first round")
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::{cell::RefCell, fmt::{self, Write}, time::Instant};
+use log::{Level, log, log_enabled};
+
+/// The log target of span records, e.g. for filtering them.
+pub const SPAN_TARGET: &str = "socha::span";
+
+/// An open span's name and fields.
+type SpanContext = (&'static str, Vec<(&'static str, String)>);
+
+thread_local! {
+    /// The open spans on this thread, outermost first.
+    static CONTEXT: RefCell<Vec<SpanContext>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A lightweight, `tracing`-style span on top of the `log` crate.
+///
+/// When closed (dropped), a span logs a single structured line in
+/// the logfmt format, e.g.
+///
+/// `span=turn/search turn=12 color=BLUE depth=2 nodes=1534 elapsed_ms=5.210`
+///
+/// which includes the fields of every enclosing span on the same
+/// thread, so that every line can be analyzed on its own.
+pub struct Span {
+    level: Level,
+    start: Instant,
+    depth: usize
+}
+
+impl Span {
+    /// Opens a span logging at the given level.
+    pub fn enter(level: Level, name: &'static str) -> Self {
+        let depth = CONTEXT.with(|context| {
+            let mut context = context.borrow_mut();
+            context.push((name, Vec::new()));
+            context.len()
+        });
+        Self { level, start: Instant::now(), depth }
+    }
+
+    /// Attaches a field, which is inherited by nested spans.
+    pub fn record(&self, key: &'static str, value: impl fmt::Display) {
+        CONTEXT.with(|context| {
+            if let Some((_, fields)) = context.borrow_mut().get_mut(self.depth - 1) {
+                fields.push((key, value.to_string()));
+            }
+        });
+    }
+
+    /// Attaches a field, builder-style.
+    pub fn with(self, key: &'static str, value: impl fmt::Display) -> Self {
+        self.record(key, value);
+        self
+    }
+
+    /// Formats the current context of this thread in logfmt,
+    /// e.g. to structure other log messages within a span.
+    pub fn current_context() -> String {
+        CONTEXT.with(|context| format_context(&context.borrow()))
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        CONTEXT.with(|context| {
+            let mut context = context.borrow_mut();
+            if log_enabled!(target: SPAN_TARGET, self.level) {
+                let mut line = format_context(&context[..self.depth]);
+                write!(line, " elapsed_ms={:.3}", elapsed.as_secs_f64() * 1000.0).unwrap();
+                log!(target: SPAN_TARGET, self.level, "{}", line);
+            }
+            context.truncate(self.depth - 1);
+        });
+    }
+}
+
+fn format_context(spans: &[SpanContext]) -> String {
+    let names: Vec<_> = spans.iter().map(|(name, _)| *name).collect();
+    let mut line = format!("span={}", names.join("/"));
+    for (key, value) in spans.iter().flat_map(|(_, fields)| fields) {
+        write!(line, " {}={}", key, logfmt_value(value)).unwrap();
+    }
+    line
+}
+
+/// Quotes a value if logfmt requires it.
+fn logfmt_value(value: &str) -> String {
+    if value.is_empty() || value.contains(|c: char| c.is_whitespace() || c == '"' || c == '=') {
+        format!("{:?}", value)
+    } else {
+        value.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use log::Level;
+    use super::Span;
+
+    #[test]
+    fn test_context() {
+        assert_eq!(Span::current_context(), "span=");
+        {
+            let turn = Span::enter(Level::Info, "turn").with("turn", 12);
+            turn.record("color", "BLUE");
+            {
+                let _search = Span::enter(Level::Debug, "search").with("chosen", "BLUE skips");
+                assert_eq!(Span::current_context(), "span=turn/search turn=12 color=BLUE chosen=\"BLUE skips\"");
+            }
+            assert_eq!(Span::current_context(), "span=turn turn=12 color=BLUE");
+        }
+        assert_eq!(Span::current_context(), "span=");
+    }
+}
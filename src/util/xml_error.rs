@@ -0,0 +1,33 @@
+use std::error::Error;
+use std::fmt;
+use xml::reader::Error as XmlReaderError;
+use xml::writer::Error as XmlWriterError;
+
+/// An error that occurred while reading or writing XML.
+#[derive(Debug)]
+pub enum XmlError {
+    Reader(XmlReaderError),
+    Writer(XmlWriterError),
+    /// The document is not well-formed.
+    Malformed(String)
+}
+
+impl fmt::Display for XmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Reader(e) => write!(f, "{}", e),
+            Self::Writer(e) => write!(f, "{}", e),
+            Self::Malformed(message) => write!(f, "{}", message)
+        }
+    }
+}
+
+impl Error for XmlError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Reader(e) => Some(e),
+            Self::Writer(e) => Some(e),
+            Self::Malformed(_) => None
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use std::collections::VecDeque;
+use std::str;
+use super::{SCResult, XmlError};
+
+/// The name of the element wrapping the entire stream.
+const PROTOCOL_ELEMENT: &[u8] = b"protocol";
+
+/// The lexical context the scanner is currently in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ScanState {
+    /// Character data between elements.
+    Text,
+    /// Right after a '<'.
+    TagOpen,
+    /// Inside the name of a start or an end tag.
+    TagName { is_end: bool },
+    /// Inside a start or an end tag, after the name.
+    Tag { is_end: bool },
+    /// Inside a quoted attribute value.
+    Quoted { quote: u8 },
+    /// Right after a '<!'.
+    Bang,
+    /// Inside a comment, a CDATA section, a processing
+    /// instruction or a declaration, terminated by the
+    /// given delimiter.
+    Skipping { terminator: &'static [u8] }
+}
+
+/// An incremental buffer that splits the continuous XML
+/// stream sent by the server (`<protocol>...</protocol>`)
+/// into its top-level messages, independently of how the
+/// underlying transport chunks the bytes.
+///
+/// Bytes are only scanned once, even if a message spans
+/// many chunks, and character data/comments/CDATA sections
+/// and quoted attribute values are handled correctly.
+#[derive(Debug)]
+pub struct XmlFrameBuffer {
+    /// The bytes that have not been emitted as part of a frame yet.
+    buffer: Vec<u8>,
+    /// The number of bytes in the buffer that have already been scanned.
+    scanned: usize,
+    state: ScanState,
+    /// The name of the tag currently being scanned.
+    tag_name: Vec<u8>,
+    /// The last byte inside the current tag, used to detect self-closing tags.
+    last_tag_byte: u8,
+    /// The nesting depth inside the current frame.
+    depth: usize,
+    /// The start of the current frame in the buffer.
+    frame_start: Option<usize>,
+    /// The start of the tag currently being scanned.
+    tag_start: usize,
+    frames: VecDeque<String>,
+    in_protocol: bool,
+    closed: bool
+}
+
+impl XmlFrameBuffer {
+    /// Creates a new, empty frame buffer.
+    pub fn new() -> Self {
+        Self {
+            buffer: Vec::new(),
+            scanned: 0,
+            state: ScanState::Text,
+            tag_name: Vec::new(),
+            last_tag_byte: 0,
+            depth: 0,
+            frame_start: None,
+            tag_start: 0,
+            frames: VecDeque::new(),
+            in_protocol: false,
+            closed: false
+        }
+    }
+
+    /// Appends the given bytes and splits off every message
+    /// that has been completed by them.
+    pub fn push(&mut self, bytes: &[u8]) -> SCResult<()> {
+        self.buffer.extend_from_slice(bytes);
+
+        while self.scanned < self.buffer.len() {
+            let i = self.scanned;
+            let byte = self.buffer[i];
+            self.scanned += 1;
+            self.state = match self.state {
+                ScanState::Text => if byte == b'<' {
+                    self.tag_start = i;
+                    ScanState::TagOpen
+                } else {
+                    ScanState::Text
+                },
+                ScanState::TagOpen => match byte {
+                    b'/' => {
+                        self.tag_name.clear();
+                        ScanState::TagName { is_end: true }
+                    },
+                    b'?' => ScanState::Skipping { terminator: b"?>" },
+                    b'!' => ScanState::Bang,
+                    _ => {
+                        self.tag_name.clear();
+                        self.tag_name.push(byte);
+                        self.last_tag_byte = byte;
+                        ScanState::TagName { is_end: false }
+                    }
+                },
+                ScanState::TagName { is_end } => match byte {
+                    b'>' => self.finish_tag(is_end, i)?,
+                    b if b.is_ascii_whitespace() || b == b'/' => {
+                        self.last_tag_byte = b;
+                        ScanState::Tag { is_end }
+                    },
+                    b => {
+                        self.tag_name.push(b);
+                        self.last_tag_byte = b;
+                        ScanState::TagName { is_end }
+                    }
+                },
+                ScanState::Tag { is_end } => match byte {
+                    b'>' => self.finish_tag(is_end, i)?,
+                    b'"' | b'\'' => ScanState::Quoted { quote: byte },
+                    b => {
+                        if !b.is_ascii_whitespace() {
+                            self.last_tag_byte = b;
+                        }
+                        ScanState::Tag { is_end }
+                    }
+                },
+                ScanState::Quoted { quote } => if byte == quote {
+                    self.last_tag_byte = byte;
+                    ScanState::Tag { is_end: false }
+                } else {
+                    ScanState::Quoted { quote }
+                },
+                ScanState::Bang => if self.buffer[self.tag_start..self.scanned].starts_with(b"<!--") {
+                    ScanState::Skipping { terminator: b"-->" }
+                } else if self.buffer[self.tag_start..self.scanned].starts_with(b"<![CDATA[") {
+                    ScanState::Skipping { terminator: b"]]>" }
+                } else if b"<!--".starts_with(&self.buffer[self.tag_start..self.scanned])
+                       || b"<![CDATA[".starts_with(&self.buffer[self.tag_start..self.scanned]) {
+                    // Not enough bytes to decide yet
+                    ScanState::Bang
+                } else {
+                    ScanState::Skipping { terminator: b">" }
+                },
+                ScanState::Skipping { terminator } => if self.buffer[..self.scanned].ends_with(terminator)
+                                                     && self.scanned - self.tag_start >= 2 + terminator.len() {
+                    ScanState::Text
+                } else {
+                    ScanState::Skipping { terminator }
+                }
+            };
+        }
+
+        // Discard bytes that will never be part of a frame
+        let consumed = match (self.frame_start, self.state) {
+            (Some(start), _) => start,
+            (None, ScanState::Text) => self.scanned,
+            (None, _) => self.tag_start
+        };
+        self.buffer.drain(..consumed);
+        self.scanned -= consumed;
+        self.tag_start -= consumed.min(self.tag_start);
+        self.frame_start = self.frame_start.map(|s| s - consumed);
+
+        Ok(())
+    }
+
+    /// Handles the end of a tag at the given index.
+    fn finish_tag(&mut self, is_end: bool, end: usize) -> SCResult<ScanState> {
+        let is_self_closing = !is_end && self.last_tag_byte == b'/';
+
+        if is_end {
+            if self.depth == 0 {
+                if self.in_protocol && self.tag_name == PROTOCOL_ELEMENT {
+                    self.closed = true;
+                } else {
+                    return Err(XmlError::Malformed(format!("Unexpected closing tag </{}>", String::from_utf8_lossy(&self.tag_name))).into());
+                }
+            } else {
+                self.depth -= 1;
+                if self.depth == 0 {
+                    self.emit_frame(end)?;
+                }
+            }
+        } else if self.depth == 0 && !self.in_protocol && !is_self_closing && self.tag_name == PROTOCOL_ELEMENT {
+            self.in_protocol = true;
+        } else {
+            if self.depth == 0 {
+                self.frame_start = Some(self.tag_start);
+            }
+            if is_self_closing {
+                if self.depth == 0 {
+                    self.emit_frame(end)?;
+                }
+            } else {
+                self.depth += 1;
+            }
+        }
+
+        Ok(ScanState::Text)
+    }
+
+    /// Emits the frame ending (inclusively) at the given index.
+    fn emit_frame(&mut self, end: usize) -> SCResult<()> {
+        let start = self.frame_start.take().expect("A frame has to be started before it can be emitted");
+        let frame = str::from_utf8(&self.buffer[start..=end]).map_err(|e| XmlError::Malformed(format!("Frame is not valid UTF-8: {}", e)))?;
+        self.frames.push_back(frame.to_owned());
+        Ok(())
+    }
+
+    /// Removes the next complete message from the buffer, if any.
+    pub fn next_frame(&mut self) -> Option<String> {
+        self.frames.pop_front()
+    }
+
+    /// Whether the closing `</protocol>` tag has been received.
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+}
+
+impl Default for XmlFrameBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+    use super::XmlFrameBuffer;
+
+    const FRAMES: [&str; 5] = [
+        r#"<joined roomId="abc"/>"#,
+        r#"<room roomId="abc"><data class="welcomeMessage" color="ONE"></data></room>"#,
+        r#"<room roomId="abc"><!-- a <comment> --><data class="memento"><state turn="0" note='a > b'><![CDATA[<not a tag>]]></state></data></room>"#,
+        r#"<room roomId="abc"><data class="sc.framework.plugins.protocol.MoveRequest"/></room>"#,
+        "<sc.protocol.responses.CloseConnection />"
+    ];
+
+    fn transcript() -> String {
+        format!("<?xml version=\"1.0\"?>\n<protocol>\n  {}\n</protocol>", FRAMES.join("\n  "))
+    }
+
+    fn split(chunks: impl IntoIterator<Item=Vec<u8>>) -> (Vec<String>, bool) {
+        let mut buffer = XmlFrameBuffer::new();
+        let mut frames = Vec::new();
+        for chunk in chunks {
+            buffer.push(&chunk).unwrap();
+            while let Some(frame) = buffer.next_frame() {
+                frames.push(frame);
+            }
+        }
+        (frames, buffer.is_closed())
+    }
+
+    #[test]
+    fn test_single_chunk() {
+        let (frames, closed) = split(vec![transcript().into_bytes()]);
+        assert_eq!(frames, FRAMES);
+        assert!(closed);
+    }
+
+    #[test]
+    fn test_byte_by_byte() {
+        let (frames, closed) = split(transcript().bytes().map(|b| vec![b]));
+        assert_eq!(frames, FRAMES);
+        assert!(closed);
+    }
+
+    #[test]
+    fn test_every_split_point() {
+        let bytes = transcript().into_bytes();
+        for i in 0..bytes.len() {
+            let (frames, closed) = split(vec![bytes[..i].to_vec(), bytes[i..].to_vec()]);
+            assert_eq!(frames, FRAMES, "Splitting at {} failed", i);
+            assert!(closed);
+        }
+    }
+
+    #[test]
+    fn test_random_chunkings() {
+        let bytes = transcript().into_bytes();
+        let mut rng = StdRng::seed_from_u64(2021);
+        for _ in 0..200 {
+            let mut chunks = Vec::new();
+            let mut i = 0;
+            while i < bytes.len() {
+                let j = (i + rng.gen_range(1..=24)).min(bytes.len());
+                chunks.push(bytes[i..j].to_vec());
+                i = j;
+            }
+            let (frames, closed) = split(chunks);
+            assert_eq!(frames, FRAMES);
+            assert!(closed);
+        }
+    }
+
+    #[test]
+    fn test_without_protocol_element() {
+        let (frames, closed) = split(vec![FRAMES.join("").into_bytes()]);
+        assert_eq!(frames, FRAMES);
+        assert!(!closed);
+    }
+
+    #[test]
+    fn test_incomplete_frame() {
+        let mut buffer = XmlFrameBuffer::new();
+        buffer.push(b"<protocol><room roomId=\"abc\"><data class=\"memento\">").unwrap();
+        assert_eq!(buffer.next_frame(), None);
+        buffer.push(b"</data></room>").unwrap();
+        assert_eq!(buffer.next_frame().as_deref(), Some("<room roomId=\"abc\"><data class=\"memento\"></data></room>"));
+        assert!(!buffer.is_closed());
+    }
+}
@@ -1,16 +1,17 @@
 use std::collections::{HashMap, VecDeque};
 use std::convert::TryInto;
 use std::fmt;
-use std::str;
+use std::iter::FromIterator;
+use std::str::{self, FromStr};
 use std::io::{Read, Write, Cursor};
 use xml::reader::{EventReader, XmlEvent as XmlReadEvent};
 use xml::writer::{EventWriter, EmitterConfig, XmlEvent as XmlWriteEvent};
 use log::{warn, error};
-use super::{SCResult, SCError};
+use super::{SCResult, SCError, XmlError};
 
 /// A deserialized, in-memory tree-representation
 /// of an XML node.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct XmlNode {
     name: String,
     content: String,
@@ -20,9 +21,9 @@ pub struct XmlNode {
 
 /// A builder that makes the construction of new
 /// XML nodes more convenient.
-pub struct XmlNodeBuilder<'a> {
-    name: &'a str,
-    content: &'a str,
+pub struct XmlNodeBuilder {
+    name: String,
+    content: String,
     attributes: HashMap<String, String>,
     childs: Vec<XmlNode>
 }
@@ -34,10 +35,19 @@ pub trait FromXmlNode where Self: Sized {
 
 impl XmlNode {
     /// Creates a new XML node builder.
-    pub fn new(name: &str) -> XmlNodeBuilder {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(name: impl Into<String>) -> XmlNodeBuilder {
         XmlNodeBuilder::new(name)
     }
 
+    /// Changes the tag name, e.g. for types that are
+    /// serialized under different names depending on
+    /// their parent.
+    pub fn renamed(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
     /// Deserializes an XML node tree
     /// from the given XML event reader.
     pub fn read_from<R>(reader: &mut EventReader<R>) -> SCResult<XmlNode> where R: Read {
@@ -73,6 +83,7 @@ impl XmlNode {
                         warn!("Found characters {} outside of any node", content);
                     }
                 },
+                Ok(XmlReadEvent::EndDocument) => return Err(XmlError::Malformed("Reached the end of the document before the node was complete!".to_owned()).into()),
                 Err(e) => return Err(e.into()),
                 _ => ()
             }
@@ -98,6 +109,15 @@ impl XmlNode {
         writer.write(XmlWriteEvent::end_element())?;
         Ok(())
     }
+
+    /// Serializes the node without indentation, as sent over the wire.
+    pub fn serialize(&self) -> SCResult<String> {
+        let mut config = EmitterConfig::new();
+        config.write_document_declaration = false;
+        let mut writer = config.create_writer(Vec::new());
+        self.write_to(&mut writer)?;
+        String::from_utf8(writer.into_inner()).map_err(|e| XmlError::Malformed(e.to_string()).into())
+    }
     
     /// Fetches the node's tag name.
     pub fn name(&self) -> &str {
@@ -111,12 +131,48 @@ impl XmlNode {
     
     /// Fetches an attribute's value by key.
     pub fn attribute(&self, key: &str) -> SCResult<&str> {
-        self.attributes.get(key).map(|s| s.as_str()).ok_or_else(|| format!("No attribute with key '{}' found in <{}>!", key, self.name).into())
+        self.opt_attribute(key).ok_or_else(|| SCError::parse(format!("No attribute with key '{}' found in <{}>!", key, self.name)))
+    }
+
+    /// Fetches an attribute's value by key if present.
+    pub fn opt_attribute(&self, key: &str) -> Option<&str> {
+        self.attributes.get(key).map(|s| s.as_str())
+    }
+
+    /// Iterates over the attributes as key-value pairs (in no particular order).
+    pub fn attributes(&self) -> impl Iterator<Item=(&str, &str)> {
+        self.attributes.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Iterates over the child elements.
+    pub fn children(&self) -> impl Iterator<Item=&XmlNode> {
+        self.childs.iter()
     }
     
+    /// Fetches and parses an attribute's value by key.
+    pub fn parse_attribute<T>(&self, key: &str) -> SCResult<T> where T: FromStr, SCError: From<T::Err> {
+        Ok(self.attribute(key)?.parse()?)
+    }
+
+    /// Parses the node's textual contents.
+    pub fn parse_content<T>(&self) -> SCResult<T> where T: FromStr, SCError: From<T::Err> {
+        Ok(self.content.parse()?)
+    }
+
+    /// Finds and deserializes the first child element with the provided tag name.
+    pub fn parse_child<T>(&self, name: &str) -> SCResult<T> where T: FromXmlNode {
+        T::from_node(self.child_by_name(name)?)
+    }
+
+    /// Deserializes every child element with the provided tag name
+    /// into a collection, failing if any of them is invalid.
+    pub fn parse_children<T, C>(&self, name: &str) -> SCResult<C> where T: FromXmlNode, C: FromIterator<T> {
+        self.childs_by_name(name).map(T::from_node).collect()
+    }
+
     /// Finds the first child element with the provided tag name.
     pub fn child_by_name<'a, 'n: 'a>(&'a self, name: &'n str) -> SCResult<&'a XmlNode> {
-        self.childs_by_name(name).next().ok_or_else(|| format!("No <{}> found in <{}>!", name, self.name).into())
+        self.childs_by_name(name).next().ok_or_else(|| SCError::parse(format!("No <{}> found in <{}>!", name, self.name)))
     }
     
     /// Fetches a list of all child elements matching the provided tag name.
@@ -125,6 +181,15 @@ impl XmlNode {
     }
 }
 
+impl FromStr for XmlNode {
+    type Err = SCError;
+
+    /// Parses a single XML node from the given string.
+    fn from_str(raw: &str) -> SCResult<Self> {
+        Self::read_from(&mut EventReader::new(raw.as_bytes()))
+    }
+}
+
 impl fmt::Display for XmlNode {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         // Writes the node as XML
@@ -137,22 +202,22 @@ impl fmt::Display for XmlNode {
     }
 }
 
-impl<'a> XmlNodeBuilder<'a> {
+impl XmlNodeBuilder {
     /// Creates a new XML node builder with the
     /// specified tag name.
-    pub fn new(name: &'a str) -> Self {
-        Self { name: name, content: "", attributes: HashMap::new(), childs: Vec::new() }
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(), content: String::new(), attributes: HashMap::new(), childs: Vec::new() }
     }
     
     /// Sets the tag name of the XML node.
-    pub fn name(mut self, name: &'a str) -> Self {
-        self.name = name;
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
         self
     }
     
     /// Sets the contents of the XML node.
-    pub fn content(mut self, data: &'a str) -> Self {
-        self.content = data;
+    pub fn content(mut self, data: impl ToString) -> Self {
+        self.content = data.to_string();
         self
     }
     
@@ -163,14 +228,14 @@ impl<'a> XmlNodeBuilder<'a> {
     }
     
     /// Adds the specified attribute.
-    pub fn attribute(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
-        self.attributes.insert(key.into(), value.into());
+    pub fn attribute(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.attributes.insert(key.into(), value.to_string());
         self
     }
     
     /// Adds the specified children.
-    pub fn childs(mut self, childs: impl IntoIterator<Item=XmlNode>) -> Self {
-        self.childs.extend(childs);
+    pub fn childs(mut self, childs: impl IntoIterator<Item=impl Into<XmlNode>>) -> Self {
+        self.childs.extend(childs.into_iter().map(Into::into));
         self
     }
     
@@ -189,20 +254,41 @@ impl<'a> XmlNodeBuilder<'a> {
     /// Builds the XML node.
     pub fn build(self) -> XmlNode {
         XmlNode {
-            name: self.name.to_owned(),
-            content: self.content.to_owned(),
+            name: self.name,
+            content: self.content,
             attributes: self.attributes,
             childs: self.childs
         }
     }
 }
 
-impl<'a> Default for XmlNodeBuilder<'a> {
+impl Default for XmlNodeBuilder {
     fn default() -> Self {
         Self::new("")
     }
 }
 
-impl<'a> From<XmlNodeBuilder<'a>> for XmlNode {
-    fn from(builder: XmlNodeBuilder<'a>) -> Self { builder.build() }
+impl From<XmlNodeBuilder> for XmlNode {
+    fn from(builder: XmlNodeBuilder) -> Self { builder.build() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::XmlNode;
+
+    #[test]
+    fn test_xml_node() {
+        let node = XmlNode::new("room")
+            .attribute("roomId", 42)
+            .childs((0..3).map(|i| XmlNode::new("field").content(i)))
+            .child(XmlNode::new("data").attribute("class", "memento"))
+            .build();
+        assert_eq!(node.opt_attribute("roomId"), Some("42"));
+        assert_eq!(node.opt_attribute("missing"), None);
+        assert!(node.attribute("missing").is_err());
+        assert_eq!(node.attributes().collect::<Vec<_>>(), vec![("roomId", "42")]);
+        assert_eq!(node.children().map(|c| c.name()).collect::<Vec<_>>(), vec!["field", "field", "field", "data"]);
+        assert_eq!(node.childs_by_name("field").map(|c| c.parse_content().unwrap()).collect::<Vec<i32>>(), vec![0, 1, 2]);
+        assert_eq!(node.serialize().unwrap().parse::<XmlNode>().unwrap().children().count(), 4);
+    }
 }